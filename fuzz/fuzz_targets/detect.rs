@@ -0,0 +1,17 @@
+//! Throws arbitrary bytes at detection and at matching against every type.
+//!
+//! Run with `cargo +nightly fuzz run detect` from the repository root.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use once_cell::sync::Lazy;
+
+static TYPES: Lazy<Vec<&'static str>> = Lazy::new(tree_magic_mini::supported_types);
+
+fuzz_target!(|data: &[u8]| {
+    tree_magic_mini::from_u8(data);
+    tree_magic_mini::from_u8_all(data);
+    for mimetype in TYPES.iter() {
+        tree_magic_mini::match_u8(mimetype, data);
+    }
+});
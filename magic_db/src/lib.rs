@@ -20,6 +20,17 @@ pub fn subclasses() -> &'static str {
     include_str!("subclasses")
 }
 
+/// Returns a static reference to the MIME database 'globs2' file name patterns
+pub fn globs2() -> &'static str {
+    include_str!("globs2")
+}
+
+/// Returns a static reference to English descriptions of each MIME type,
+/// one `type<TAB>description` pair per line
+pub fn descriptions() -> &'static str {
+    include_str!("descriptions")
+}
+
 /// Returns a static reference to the MIME database 'magic' data
 pub fn magic() -> &'static [u8] {
     include_bytes!("magic")
@@ -11,7 +11,9 @@ impl crate::Checker for BaseType {
             return true;
         }
         if mimetype == "text/plain" {
-            is_text_plain_from_u8(bytes)
+            super::looks_like_text(bytes)
+        } else if mimetype == super::EMPTY {
+            bytes.is_empty()
         } else {
             // ...how did we get bytes for this?
             false
@@ -28,12 +30,17 @@ impl crate::Checker for BaseType {
         match mimetype {
             "all/all" => true,
             "all/allfiles" | "application/octet-stream" => meta.is_file(),
-            "inode/directory" => meta.is_dir(),
             "text/plain" => is_text_plain_from_file(file),
-            _ => false,
+            // Files in /proc and the like claim to be empty, so read it
+            super::EMPTY => meta.is_file() && read_bytes(file, 1).is_ok_and(|b| b.is_empty()),
+            _ => super::inode_type(meta.file_type()) == Some(mimetype),
         }
     }
 
+    fn name(&self) -> &'static str {
+        "basetype"
+    }
+
     fn get_supported(&self) -> Vec<Mime> {
         super::init::get_supported()
     }
@@ -47,15 +54,10 @@ impl crate::Checker for BaseType {
     }
 }
 
-/// If there are any null bytes, return False. Otherwise return True.
-fn is_text_plain_from_u8(bytes: &[u8]) -> bool {
-    memchr::memchr(0, bytes).is_none()
-}
-
 // TODO: Hoist the main logic here somewhere else. This'll get redundant fast!
 fn is_text_plain_from_file(file: &File) -> bool {
     let Ok(bytes) = read_bytes(file, 512) else {
         return false;
     };
-    is_text_plain_from_u8(&bytes)
+    super::looks_like_text(&bytes)
 }
@@ -10,8 +10,14 @@ pub fn get_subclasses() -> Vec<(Mime, Mime)> {
     vec![
         ("all/all", "all/allfiles"),
         ("all/all", "inode/directory"),
+        ("all/all", "inode/symlink"),
+        ("all/all", "inode/fifo"),
+        ("all/all", "inode/socket"),
+        ("all/all", "inode/blockdevice"),
+        ("all/all", "inode/chardevice"),
         ("all/allfiles", "application/octet-stream"),
         ("application/octet-stream", "text/plain"),
+        ("application/octet-stream", super::EMPTY),
     ]
 }
 
@@ -1,11 +1,97 @@
 //! Handles "base types" such as inode/* and text/plain
-const TYPES: [&str; 5] = [
+use crate::Mime;
+use std::fs::FileType;
+
+/// What zero-length input is, rather than whatever the walk would settle on
+pub(crate) const EMPTY: Mime = "application/x-empty";
+
+const TYPES: [&str; 11] = [
     "all/all",
     "all/allfiles",
     "inode/directory",
+    "inode/symlink",
+    "inode/fifo",
+    "inode/socket",
+    "inode/blockdevice",
+    "inode/chardevice",
     "text/plain",
     "application/octet-stream",
+    EMPTY,
 ];
 
+/// Types that any input is, which still match when no checker supports them,
+/// so that the types under them can be reached
+pub(crate) fn is_catch_all(mimetype: &str) -> bool {
+    matches!(
+        mimetype,
+        "all/all" | "all/allfiles" | "application/octet-stream"
+    )
+}
+
+/// The inode/* type of anything that isn't a regular file
+pub(crate) fn inode_type(file_type: FileType) -> Option<Mime> {
+    if file_type.is_dir() {
+        return Some("inode/directory");
+    }
+    if file_type.is_symlink() {
+        return Some("inode/symlink");
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        if file_type.is_fifo() {
+            return Some("inode/fifo");
+        }
+        if file_type.is_socket() {
+            return Some("inode/socket");
+        }
+        if file_type.is_block_device() {
+            return Some("inode/blockdevice");
+        }
+        if file_type.is_char_device() {
+            return Some("inode/chardevice");
+        }
+    }
+    None
+}
+
 pub mod check;
 pub mod init;
+
+/// Byte order marks of the wider Unicode encodings, with their code unit
+/// width and whether they're little endian. UTF-32 LE comes first, since it
+/// starts with the UTF-16 LE mark.
+const BOMS: [(&[u8], usize, bool); 4] = [
+    (b"\xff\xfe\0\0", 4, true),
+    (b"\0\0\xfe\xff", 4, false),
+    (b"\xff\xfe", 2, true),
+    (b"\xfe\xff", 2, false),
+];
+
+/// Whether bytes look like text, the way shared-mime-info decides it: there
+/// are no control characters other than tab, line feed, form feed and
+/// carriage return. Bytes above ASCII are allowed, so UTF-8 and legacy 8-bit
+/// encodings both pass.
+///
+/// UTF-16 and UTF-32 are recognized by their byte order mark, and checked a
+/// code unit at a time, since their ASCII characters are padded with NULs.
+pub(crate) fn looks_like_text(bytes: &[u8]) -> bool {
+    for (bom, width, little_endian) in BOMS {
+        if let Some(rest) = bytes.strip_prefix(bom) {
+            // A code unit cut off at the end of the window is ignored
+            return rest.chunks_exact(width).all(|unit| {
+                let unit = if little_endian {
+                    unit.iter().rev().fold(0, |c, &b| (c << 8) | b as u32)
+                } else {
+                    unit.iter().fold(0, |c, &b| (c << 8) | b as u32)
+                };
+                is_text_char(unit)
+            });
+        }
+    }
+    bytes.iter().all(|&b| is_text_char(b as u32))
+}
+
+fn is_text_char(c: u32) -> bool {
+    !(c < 0x20 || c == 0x7f) || matches!(c, 0x09 | 0x0a | 0x0c | 0x0d)
+}
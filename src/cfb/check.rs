@@ -0,0 +1,37 @@
+use super::{get_format, is_supported, matches, Source};
+use crate::Mime;
+use std::collections::HashMap;
+use std::fs::File;
+
+pub(crate) struct Cfb;
+
+impl crate::Checker for Cfb {
+    fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
+        is_supported(mimetype) && matches(Source::Bytes(bytes), mimetype)
+    }
+
+    fn match_file(&self, file: &File, mimetype: &str) -> bool {
+        is_supported(mimetype) && matches(Source::File(file), mimetype)
+    }
+
+    /// The directory can be anywhere in the file, and often comes last
+    fn needs_file(&self, mimetype: &str) -> bool {
+        get_format(mimetype).is_some()
+    }
+
+    fn name(&self) -> &'static str {
+        "cfb"
+    }
+
+    fn get_supported(&self) -> Vec<Mime> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(Mime, Mime)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> HashMap<Mime, Mime> {
+        super::init::get_aliaslist()
+    }
+}
@@ -0,0 +1,18 @@
+use super::{CFB, FORMATS};
+use crate::Mime;
+use std::collections::HashMap;
+
+pub fn get_supported() -> Vec<Mime> {
+    std::iter::once(CFB)
+        .chain(FORMATS.iter().map(|f| f.mime))
+        .collect()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(Mime, Mime)> {
+    FORMATS.iter().map(|f| (CFB, f.mime)).collect()
+}
+
+pub fn get_aliaslist() -> HashMap<Mime, Mime> {
+    HashMap::default()
+}
@@ -0,0 +1,252 @@
+//! Tells apart the formats stored in Compound File Binary containers, like
+//! legacy Office documents, by what's in their root storage
+use crate::Mime;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+pub mod check;
+pub mod init;
+
+/// Parent of every type here, for containers whose format isn't known
+const CFB: Mime = "application/x-ole-storage";
+
+const SIGNATURE: &[u8] = b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1";
+
+const HEADER_LEN: usize = 512;
+const DIR_ENTRY_LEN: usize = 128;
+
+/// FAT sector locations kept in the header itself; the rest are in the
+/// DIFAT chain
+const HEADER_DIFAT_LEN: usize = 109;
+
+/// Sector numbers above this mean the end of a chain, or an unused sector
+const MAX_SECTOR: u32 = 0xffff_fffa;
+const NO_STREAM: u32 = 0xffff_ffff;
+
+/// Most directory sectors read, which is at least 128 entries. The root
+/// entry is always first, and its children are usually soon after it.
+const MAX_DIR_SECTORS: usize = 32;
+
+/// Most DIFAT sectors followed to find a FAT sector
+const MAX_DIFAT_SECTORS: usize = 64;
+
+/// Most entries looked at in the root storage
+const MAX_CHILDREN: usize = 256;
+
+const ENTRY_ROOT: u8 = 5;
+
+/// Class ID of Windows Installer packages, as stored on disk
+const MSI_CLSID: [u8; 16] = *b"\x84\x10\x0c\0\0\0\0\0\xc0\0\0\0\0\0\0\x46";
+
+/// A format stored in a container, and what its root storage has in it
+struct Format {
+    mime: Mime,
+    /// Any of these streams in the root storage means it's this format
+    streams: &'static [&'static str],
+    /// Or the root storage has this class ID
+    clsid: Option<[u8; 16]>,
+}
+
+static FORMATS: &[Format] = &[
+    Format {
+        mime: "application/msword",
+        streams: &["WordDocument"],
+        clsid: None,
+    },
+    Format {
+        mime: "application/vnd.ms-excel",
+        // Excel 5 and later, and Excel 2 to 4 as saved by later versions
+        streams: &["Workbook", "Book"],
+        clsid: None,
+    },
+    Format {
+        mime: "application/vnd.ms-powerpoint",
+        streams: &["PowerPoint Document"],
+        clsid: None,
+    },
+    Format {
+        mime: "application/x-msi",
+        streams: &[],
+        clsid: Some(MSI_CLSID),
+    },
+];
+
+fn get_format(mimetype: &str) -> Option<&'static Format> {
+    FORMATS.iter().find(|f| f.mime == mimetype)
+}
+
+fn is_supported(mimetype: &str) -> bool {
+    mimetype == CFB || get_format(mimetype).is_some()
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    let b = bytes.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    let b = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Where a container is read from. Sectors can be anywhere in a file, so
+/// whole files are read by seeking to each one.
+#[derive(Clone, Copy)]
+enum Source<'a> {
+    Bytes(&'a [u8]),
+    File(&'a File),
+}
+
+impl<'a> Source<'a> {
+    fn read_at(self, offset: u64, len: usize) -> Option<Cow<'a, [u8]>> {
+        match self {
+            Source::Bytes(bytes) => {
+                let start = usize::try_from(offset).ok()?;
+                bytes.get(start..start + len).map(Cow::Borrowed)
+            }
+            Source::File(mut file) => {
+                file.seek(SeekFrom::Start(offset)).ok()?;
+                let mut buf = vec![0; len];
+                file.read_exact(&mut buf).ok()?;
+                Some(Cow::Owned(buf))
+            }
+        }
+    }
+}
+
+/// The parts of a container that say what's in it
+struct Container<'a> {
+    source: Source<'a>,
+    header: Cow<'a, [u8]>,
+    sector_shift: u32,
+}
+
+impl<'a> Container<'a> {
+    fn new(source: Source<'a>) -> Option<Container<'a>> {
+        let header = source.read_at(0, HEADER_LEN)?;
+        if !header.starts_with(SIGNATURE) || u16_at(&header, 0x1c)? != 0xfffe {
+            return None;
+        }
+        // 512 byte sectors in version 3, and 4096 in version 4
+        let sector_shift = match u16_at(&header, 0x1e)? {
+            shift @ (9 | 12) => shift as u32,
+            _ => return None,
+        };
+        Some(Container {
+            source,
+            header,
+            sector_shift,
+        })
+    }
+
+    fn sector_len(&self) -> usize {
+        1 << self.sector_shift
+    }
+
+    /// The header takes up the first sector's worth of space
+    fn read_sector(&self, sector: u32) -> Option<Cow<'a, [u8]>> {
+        let offset = (sector as u64 + 1) << self.sector_shift;
+        self.source.read_at(offset, self.sector_len())
+    }
+
+    /// Where the `index`th sector of the FAT is
+    fn fat_sector(&self, index: usize) -> Option<u32> {
+        if index < HEADER_DIFAT_LEN {
+            return u32_at(&self.header, 0x4c + index * 4);
+        }
+        // Each DIFAT sector ends with the location of the next one
+        let per_sector = self.sector_len() / 4 - 1;
+        let index = index - HEADER_DIFAT_LEN;
+        let hops = index / per_sector;
+        if hops >= MAX_DIFAT_SECTORS {
+            return None;
+        }
+        let mut sector = u32_at(&self.header, 0x44)?;
+        for _ in 0..hops {
+            sector = u32_at(&self.read_sector(sector)?, per_sector * 4)?;
+        }
+        u32_at(&self.read_sector(sector)?, (index % per_sector) * 4)
+    }
+
+    /// The sector after `sector` in its chain, if there is one
+    fn next_sector(&self, sector: u32) -> Option<u32> {
+        let per_sector = self.sector_len() / 4;
+        let fat = self.fat_sector(sector as usize / per_sector)?;
+        let offset = (sector as usize % per_sector) * 4;
+        u32_at(&self.read_sector(fat)?, offset).filter(|&next| next <= MAX_SECTOR)
+    }
+
+    /// As much of the directory as could be read
+    fn directory(&self) -> Vec<u8> {
+        let mut dir = Vec::new();
+        let mut sector = u32_at(&self.header, 0x30).filter(|&s| s <= MAX_SECTOR);
+        for _ in 0..MAX_DIR_SECTORS {
+            let Some(data) = sector.and_then(|s| self.read_sector(s)) else {
+                break;
+            };
+            dir.extend_from_slice(&data);
+            sector = sector.and_then(|s| self.next_sector(s));
+        }
+        dir
+    }
+}
+
+/// The class ID of the root storage, and the names of what's in it
+struct Root {
+    clsid: [u8; 16],
+    names: Vec<String>,
+}
+
+impl Root {
+    fn read(source: Source) -> Option<Root> {
+        let dir = Container::new(source)?.directory();
+        let entry =
+            |id: u32| dir.get(id as usize * DIR_ENTRY_LEN..(id as usize + 1) * DIR_ENTRY_LEN);
+
+        let root = entry(0).filter(|root| root[0x42] == ENTRY_ROOT)?;
+        let mut clsid = [0; 16];
+        clsid.copy_from_slice(&root[0x50..0x60]);
+
+        // The children are a tree of siblings, so walk it
+        let mut names = Vec::new();
+        let mut pending = vec![u32_at(root, 0x4c)?];
+        while let Some(id) = pending.pop() {
+            if names.len() >= MAX_CHILDREN {
+                break;
+            }
+            let Some(child) = (id != NO_STREAM).then(|| entry(id)).flatten() else {
+                continue;
+            };
+            names.push(entry_name(child));
+            pending.extend([u32_at(child, 0x44)?, u32_at(child, 0x48)?]);
+        }
+        Some(Root { clsid, names })
+    }
+}
+
+/// The entry's name, which is NUL terminated UTF-16
+fn entry_name(entry: &[u8]) -> String {
+    let len = (entry[0x40] as usize).min(64) / 2;
+    let units = entry[..len * 2]
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .take_while(|&unit| unit != 0);
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+fn matches(source: Source, mimetype: &str) -> bool {
+    let Some(format) = get_format(mimetype) else {
+        // Just the container
+        return mimetype == CFB && Container::new(source).is_some();
+    };
+    Root::read(source).is_some_and(|root| {
+        format.clsid == Some(root.clsid)
+            || root
+                .names
+                .iter()
+                .any(|name| format.streams.iter().any(|s| s.eq_ignore_ascii_case(name)))
+    })
+}
@@ -0,0 +1,92 @@
+//! The interface between a `Detector` and the ways it can recognize types
+
+use crate::RuleMatch;
+use std::collections::HashMap;
+use std::fs::File;
+
+/// A way of recognizing some set of MIME types, which can be added to a
+/// [`Detector`](crate::Detector) with
+/// [`Detector::add_checker`](crate::Detector::add_checker).
+///
+/// A detector builds its type graph from the types, subclass links, and aliases
+/// of all its checkers, then asks the checker responsible for each type whether
+/// the input matches as it walks down the graph. Detectors are shared between
+/// threads, so checkers must be `Send + Sync`, and the methods here may be
+/// called from several threads at once.
+pub trait Checker: Send + Sync {
+    /// Checks whether `bytes` are of type `mimetype`.
+    ///
+    /// `bytes` may only be the start of the input, since streams and files are
+    /// read into a buffer of limited size first. This may be called with types
+    /// that aren't in [`get_supported`](Checker::get_supported), such as types
+    /// another checker took over, and must return false for those rather than
+    /// panicking.
+    fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool;
+
+    /// Checks whether the file is of type `mimetype`.
+    ///
+    /// The file may not be at its start, so seek first if the position
+    /// matters. As with [`match_bytes`](Checker::match_bytes), this may be
+    /// called with types the checker doesn't support, and must return false
+    /// for them, as it should when the file can't be read.
+    fn match_file(&self, file: &File, mimetype: &str) -> bool;
+
+    /// A short name for the checker, used in diagnostics like
+    /// [`explain`](crate::explain).
+    ///
+    /// Defaults to `"custom"`.
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+
+    /// The rules that made `bytes` match `mimetype`, outermost first, for
+    /// [`explain`](crate::explain).
+    ///
+    /// This is only called after [`match_bytes`](Checker::match_bytes)
+    /// returned true. Checkers that don't work from byte patterns can leave
+    /// it empty, which is the default.
+    fn explain_bytes(&self, _bytes: &[u8], _mimetype: &str) -> Vec<RuleMatch> {
+        Vec::new()
+    }
+
+    /// Whether checking this type needs more of a file than its first bytes,
+    /// so `match_file` must be used even when those bytes are already loaded.
+    ///
+    /// Defaults to false.
+    fn needs_file(&self, _mimetype: &str) -> bool {
+        false
+    }
+
+    /// Bytes from the start of a file needed to check any supported type.
+    ///
+    /// Detectors read at least this much of a file before calling
+    /// `match_bytes`, up to a fixed limit. Defaults to 0.
+    fn scan_len(&self) -> usize {
+        0
+    }
+
+    /// How much a match on this type is preferred over matches on its sibling
+    /// types, from 0 to 100 like the priorities in `shared-mime-info`'s magic.
+    ///
+    /// Siblings with higher priorities are checked first. Defaults to 50.
+    fn priority(&self, _mimetype: &str) -> u32 {
+        50
+    }
+
+    /// The types this checker recognizes, under their canonical names.
+    ///
+    /// When several checkers of a detector support the same type, the one
+    /// added last is used for it. Names only known at runtime can be made
+    /// `'static` with [`MimeType::intern`](crate::MimeType::intern).
+    fn get_supported(&self) -> Vec<&'static str>;
+
+    /// Subclass links, as `(parent, child)` pairs.
+    ///
+    /// Either side may be a type supported by another checker. Links involving
+    /// types that no checker supports are ignored, and supported types with no
+    /// parent go under `text/plain` or `application/octet-stream`.
+    fn get_subclasses(&self) -> Vec<(&'static str, &'static str)>;
+
+    /// Aliases, mapped to the canonical types they stand for.
+    fn get_aliaslist(&self) -> HashMap<&'static str, &'static str>;
+}
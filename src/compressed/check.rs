@@ -0,0 +1,45 @@
+use super::{decompress, get_compressed, is_tar};
+use crate::{read_bytes, Mime};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+
+pub(crate) struct Compressed;
+
+impl crate::Checker for Compressed {
+    fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
+        let Some(compressed) = get_compressed(mimetype) else {
+            return false;
+        };
+        is_tar(&decompress(compressed, bytes))
+    }
+
+    fn match_file(&self, mut file: &File, mimetype: &str) -> bool {
+        let Some(compressed) = get_compressed(mimetype) else {
+            return false;
+        };
+        if file.seek(SeekFrom::Start(0)).is_err() {
+            return false;
+        }
+        let Ok(bytes) = read_bytes(file, compressed.input_len) else {
+            return false;
+        };
+        is_tar(&decompress(compressed, &bytes))
+    }
+
+    fn name(&self) -> &'static str {
+        "compressed"
+    }
+
+    fn get_supported(&self) -> Vec<Mime> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(Mime, Mime)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> HashMap<Mime, Mime> {
+        super::init::get_aliaslist()
+    }
+}
@@ -0,0 +1,74 @@
+//! Looks inside compressed streams to tell compressed tarballs apart from
+//! other compressed files
+use crate::{Mime, BUFFER_LEN, MAX_BUFFER_LEN};
+use std::io::Read;
+
+pub mod check;
+pub mod init;
+
+/// Most bytes decompressed from one stream: enough for the first tar header,
+/// however well the stream compresses
+const DECOMPRESSED_LEN: u64 = 512;
+
+/// A format stored in a compressed stream
+struct Compressed {
+    mime: Mime,
+    parent: Mime,
+    /// Wraps the start of a stream in a decoder
+    decoder: for<'a> fn(&'a [u8]) -> Option<Box<dyn Read + 'a>>,
+    /// How much of a file to read. Some formats can't produce anything
+    /// until they've read a whole block.
+    input_len: usize,
+}
+
+static COMPRESSED: &[Compressed] = &[
+    Compressed {
+        mime: "application/x-compressed-tar",
+        parent: "application/gzip",
+        decoder: |bytes| Some(Box::new(flate2::read::GzDecoder::new(bytes))),
+        input_len: BUFFER_LEN,
+    },
+    Compressed {
+        mime: "application/x-bzip-compressed-tar",
+        parent: "application/x-bzip",
+        decoder: |bytes| Some(Box::new(bzip2::read::BzDecoder::new(bytes))),
+        input_len: MAX_BUFFER_LEN,
+    },
+    Compressed {
+        mime: "application/x-xz-compressed-tar",
+        parent: "application/x-xz",
+        decoder: |bytes| Some(Box::new(xz2::read::XzDecoder::new(bytes))),
+        input_len: BUFFER_LEN,
+    },
+    Compressed {
+        mime: "application/x-zstd-compressed-tar",
+        parent: "application/zstd",
+        decoder: |bytes| {
+            Some(Box::new(
+                zstd::stream::read::Decoder::with_buffer(bytes).ok()?,
+            ))
+        },
+        input_len: MAX_BUFFER_LEN,
+    },
+];
+
+fn get_compressed(mimetype: &str) -> Option<&'static Compressed> {
+    COMPRESSED.iter().find(|c| c.mime == mimetype)
+}
+
+/// Decompresses up to `DECOMPRESSED_LEN` bytes from the start of a stream,
+/// or as many as it has before it's cut off or found to be corrupt
+fn decompress(compressed: &Compressed, bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    if let Some(decoder) = (compressed.decoder)(bytes) {
+        // Errors and truncation just end the output early, keeping what was
+        // read before them
+        let _ = decoder.take(DECOMPRESSED_LEN).read_to_end(&mut out);
+    }
+    out
+}
+
+/// POSIX tar headers have a magic string in the first block
+fn is_tar(bytes: &[u8]) -> bool {
+    bytes.get(257..262) == Some(b"ustar")
+}
@@ -0,0 +1,1416 @@
+//! A self-contained set of checkers and the type graph built from them
+
+// Methods are named after the free functions they back
+#![allow(clippy::wrong_self_convention)]
+
+use crate::fdo_magic::builtin::check::FdoMagic;
+use crate::{
+    basetype, glob, read_bytes, Checker, DetectError, DetectionExplanation, DetectionStats,
+    Error, MatchExplanation, Mime, BUFFER_LEN, MAX_BUFFER_LEN, TYPEORDER,
+};
+use petgraph::prelude::*;
+use petgraph::visit::Reversed;
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Information about currently loaded MIME types
+///
+/// The `graph` contains subclass relations between all given mimes.
+/// (EX: `application/json` -> `text/plain` -> `application/octet-stream`)
+/// This is a `petgraph` DiGraph, so you can walk the tree if needed.
+///
+/// The `hash` is a mapping between MIME types and nodes on the graph.
+/// The root of the graph is "all/all", so start traversing there unless
+/// you need to jump to a particular node.
+pub(crate) struct TypeStruct {
+    pub graph: DiGraph<Mime, u32>,
+    pub hash: HashMap<Mime, NodeIndex>,
+}
+
+impl TypeStruct {
+    fn new(checkers: &[Box<dyn Checker>], aliases: &HashMap<Mime, Mime>) -> TypeStruct {
+        let mut graph = DiGraph::<Mime, u32>::new();
+        let mut added_mimes = HashMap::<Mime, NodeIndex>::default();
+
+        // Get list of MIME types and MIME relations. Types that are aliases of
+        // another share its node, since databases don't all agree on which
+        // name is canonical.
+        let canonical = |m: Mime| aliases.get(m).copied().unwrap_or(m);
+        let mut mimelist = Vec::<Mime>::new();
+        let mut edgelist_raw = Vec::<(Mime, Mime)>::new();
+        for c in checkers {
+            mimelist.extend(c.get_supported().into_iter().map(canonical));
+            edgelist_raw.extend(
+                c.get_subclasses()
+                    .into_iter()
+                    .map(|(a, b)| (canonical(a), canonical(b)))
+                    .filter(|(a, b)| a != b),
+            );
+        }
+        mimelist.sort_unstable();
+        mimelist.dedup();
+        let mimelist = mimelist;
+
+        // Create all nodes
+        for mimetype in mimelist.iter() {
+            let node = graph.add_node(mimetype);
+            added_mimes.insert(mimetype, node);
+        }
+
+        let mut edge_list = HashSet::<(NodeIndex, NodeIndex)>::with_capacity_and_hasher(
+            edgelist_raw.len(),
+            Default::default(),
+        );
+        for (child_raw, parent_raw) in &edgelist_raw {
+            let Some(parent) = added_mimes.get(parent_raw) else {
+                continue;
+            };
+            let Some(child) = added_mimes.get(child_raw) else {
+                continue;
+            };
+            edge_list.insert((*child, *parent));
+        }
+
+        graph.extend_with_edges(&edge_list);
+
+        //Add to applicaton/octet-stream, all/all, or text/plain, depending on top-level
+        //(We'll just do it here because having the graph makes it really nice)
+        let node_text = *added_mimes
+            .entry("text/plain")
+            .or_insert_with(|| graph.add_node("text/plain"));
+
+        let node_octet = *added_mimes
+            .entry("application/octet-stream")
+            .or_insert_with(|| graph.add_node("application/octet-stream"));
+
+        let node_allall = *added_mimes
+            .entry("all/all")
+            .or_insert_with(|| graph.add_node("all/all"));
+
+        let node_allfiles = *added_mimes
+            .entry("all/allfiles")
+            .or_insert_with(|| graph.add_node("all/allfiles"));
+
+        // These hold everything else up even when no checker says so
+        for (parent, child) in [
+            (node_allall, node_allfiles),
+            (node_allfiles, node_octet),
+            (node_octet, node_text),
+        ] {
+            if graph.find_edge(parent, child).is_none() {
+                graph.add_edge(parent, child, 1);
+            }
+        }
+
+        let mut edge_list_2 = HashSet::<(NodeIndex, NodeIndex)>::default();
+        for mimenode in graph.externals(Incoming) {
+            let mimetype = &graph[mimenode];
+            let toplevel = mimetype.split('/').next().unwrap_or("");
+
+            if mimenode == node_text
+                || mimenode == node_octet
+                || mimenode == node_allfiles
+                || mimenode == node_allall
+            {
+                continue;
+            }
+
+            if toplevel == "text" {
+                edge_list_2.insert((node_text, mimenode));
+            } else if toplevel == "inode" {
+                edge_list_2.insert((node_allall, mimenode));
+            } else {
+                edge_list_2.insert((node_octet, mimenode));
+            }
+        }
+        // Don't add duplicate entries
+        graph.extend_with_edges(edge_list_2.difference(&edge_list));
+
+        TypeStruct {
+            graph,
+            hash: added_mimes,
+        }
+    }
+
+    /// The node a type hangs from when nothing says what it's a subclass of
+    fn implicit_parent(&self, mimetype: &str) -> Option<NodeIndex> {
+        let parent = match mimetype.split('/').next().unwrap_or("") {
+            _ if matches!(mimetype, "all/all" | "all/allfiles") => return None,
+            "text" if mimetype != "text/plain" => "text/plain",
+            "inode" => "all/all",
+            _ if mimetype != "application/octet-stream" => "application/octet-stream",
+            _ => return None,
+        };
+        self.hash.get(parent).copied()
+    }
+
+    /// Get the node for a type, adding it under its implicit parent if needed
+    fn node(&mut self, mimetype: Mime) -> NodeIndex {
+        if let Some(&node) = self.hash.get(mimetype) {
+            return node;
+        }
+        let node = self.graph.add_node(mimetype);
+        self.hash.insert(mimetype, node);
+        if let Some(parent) = self.implicit_parent(mimetype) {
+            self.graph.add_edge(parent, node, 1);
+        }
+        node
+    }
+
+    /// Link `child` under `parent`, taking it out from under its implicit parent
+    fn add_subclass(&mut self, child: Mime, parent: Mime) {
+        let parent = self.node(parent);
+        let child = self.node(child);
+        if let Some(implicit) = self.implicit_parent(self.graph[child]) {
+            if let Some(edge) = self.graph.find_edge(implicit, child) {
+                if implicit != parent {
+                    self.graph.remove_edge(edge);
+                }
+            }
+        }
+        self.graph.update_edge(parent, child, 1);
+    }
+}
+
+/// Whether an alias shares its canonical type's node in the type graph.
+///
+/// Aliases with their own rules only give way to a canonical type that some
+/// checker can detect, and databases that alias two names to each other keep
+/// both.
+fn is_merged(
+    alias: &str,
+    aliases: &HashMap<Mime, Mime>,
+    support: &HashMap<Mime, usize>,
+) -> bool {
+    aliases
+        .get(alias)
+        .is_some_and(|c| support.contains_key(c) && !aliases.contains_key(c))
+}
+
+/// Where a [`Detector`]'s magic database was read from, as reported by
+/// [`database_stats`](crate::database_stats).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DatabaseSource {
+    /// The database embedded with the `with-gpl-data` feature
+    Embedded,
+    /// The database installed in the standard locations, read at runtime
+    System,
+    /// The embedded database, with what's installed in the standard locations
+    /// on top, from [`Detector::embedded_plus_system`]
+    EmbeddedPlusSystem,
+    /// A directory given to [`Detector::from_database_dir`]
+    Directory,
+}
+
+impl DatabaseSource {
+    /// Where [`Detector::new`] reads it from
+    const fn builtin() -> DatabaseSource {
+        if cfg!(feature = "with-gpl-data") {
+            DatabaseSource::Embedded
+        } else {
+            DatabaseSource::System
+        }
+    }
+}
+
+/// The size of a [`Detector`]'s type graph, from
+/// [`database_stats`](crate::database_stats).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DatabaseStats {
+    /// How many types are in the type graph, including the ones at its root
+    /// like `all/all`
+    pub types: usize,
+    /// How many subclass links there are between them
+    pub subclasses: usize,
+    /// How many aliases there are for them
+    pub aliases: usize,
+    /// Where the magic database was read from
+    pub source: DatabaseSource,
+}
+
+/// A set of checkers, along with the type graph and aliases built from them.
+///
+/// The free functions in this crate all use a default `Detector` that is built
+/// the first time one of them is called. Building your own lets you keep
+/// several independent configurations in one process. Building one parses the
+/// checkers' databases and builds the type graph, so it's best to create one
+/// and reuse it.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::Detector;
+///
+/// let detector = Detector::new();
+///
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// // Find the MIME type of the GIF
+/// assert_eq!(detector.from_u8(input), "image/gif");
+/// ```
+pub struct Detector {
+    checkers: Vec<Box<dyn Checker>>,
+    /// Index into `checkers` of the checker responsible for each type
+    support: HashMap<Mime, usize>,
+    aliases: HashMap<Mime, Mime>,
+    pub(crate) types: TypeStruct,
+    file_buffer_len: usize,
+    /// Parent -> child links added with `add_subclass`, kept across rebuilds
+    added_subclasses: Vec<(Mime, Mime)>,
+    /// Aliases added with `add_alias`, kept across rebuilds
+    added_aliases: Vec<(Mime, Mime)>,
+    /// Types checked before their siblings
+    type_order: Vec<Mime>,
+    source: DatabaseSource,
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Detector::new()
+    }
+}
+
+impl Detector {
+    /// Creates a detector with the built-in checkers and the default database.
+    pub fn new() -> Detector {
+        Detector::with_checker_list(crate::default_checkers(FdoMagic::builtin()))
+    }
+
+    /// Creates a detector with the built-in checkers, but with the magic
+    /// database in `path` instead of the default one.
+    ///
+    /// The directory is laid out like `/usr/share/mime`: the `magic` file is
+    /// required, while `aliases` and `subclasses` are used if they exist. The
+    /// database is kept in memory for the rest of the program, so avoid loading
+    /// the same directory over and over.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::path::Path;
+    /// use tree_magic_mini::Detector;
+    ///
+    /// let detector = Detector::from_database_dir(Path::new("tests/database/custom")).unwrap();
+    /// assert_eq!(detector.from_u8(b"TREEMAGIC data"), "application/x-tree-magic");
+    /// ```
+    pub fn from_database_dir(path: &Path) -> Result<Detector, Error> {
+        let magic = crate::fdo_magic::builtin::dir::from_dir(path)?;
+        let mut detector = Detector::with_checker_list(crate::default_checkers(magic));
+        detector.source = DatabaseSource::Directory;
+        Ok(detector)
+    }
+
+    /// Creates a detector with the built-in checkers, using the embedded
+    /// database along with any magic, aliases, and subclasses installed in the
+    /// standard locations.
+    ///
+    /// Installed entries win over embedded ones for the same type, and those in
+    /// the user's directory win over system-wide ones. Like
+    /// [`from_database_dir`](Detector::from_database_dir), the installed files
+    /// are kept in memory for the rest of the program.
+    ///
+    /// Only available with the `with-gpl-data` feature, since without it the
+    /// installed database is all there is.
+    #[cfg(feature = "with-gpl-data")]
+    pub fn embedded_plus_system() -> Result<Detector, Error> {
+        let magic = crate::fdo_magic::builtin::dir::embedded_plus_system()?;
+        let mut detector = Detector::with_checker_list(crate::default_checkers(magic));
+        detector.source = DatabaseSource::EmbeddedPlusSystem;
+        Ok(detector)
+    }
+
+    pub(crate) fn with_checker_list(checkers: Vec<Box<dyn Checker>>) -> Detector {
+        // Later checkers take over types also supported by earlier ones
+        let mut support = HashMap::<Mime, usize>::default();
+        let mut aliases = HashMap::<Mime, Mime>::default();
+        for (i, c) in checkers.iter().enumerate() {
+            for m in c.get_supported() {
+                support.insert(m, i);
+            }
+            aliases.extend(c.get_aliaslist());
+        }
+
+        let merged: HashMap<Mime, Mime> = aliases
+            .iter()
+            .filter(|(alias, _)| is_merged(alias, &aliases, &support))
+            .map(|(&alias, &canonical)| (alias, canonical))
+            .collect();
+        let types = TypeStruct::new(&checkers, &merged);
+        let file_buffer_len = checkers
+            .iter()
+            .map(|c| c.scan_len())
+            .max()
+            .unwrap_or(0)
+            .clamp(BUFFER_LEN, MAX_BUFFER_LEN);
+
+        Detector {
+            checkers,
+            support,
+            aliases,
+            types,
+            file_buffer_len,
+            added_subclasses: Vec::new(),
+            added_aliases: Vec::new(),
+            type_order: TYPEORDER.to_vec(),
+            source: DatabaseSource::builtin(),
+        }
+    }
+
+    /// Adds a checker, and rebuilds the type graph to include its types.
+    ///
+    /// The checker takes over any types it supports that were already handled
+    /// by another checker. Its types are found by the normal walk of the type
+    /// graph, so they need a supported parent type to be reachable; types with
+    /// no parent go under `text/plain` or `application/octet-stream`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use std::fs::File;
+    /// use tree_magic_mini::{Checker, Detector};
+    ///
+    /// struct Custom;
+    ///
+    /// impl Checker for Custom {
+    ///     fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
+    ///         mimetype == "application/x-custom" && bytes.starts_with(b"CUSTOM")
+    ///     }
+    ///     fn match_file(&self, _file: &File, _mimetype: &str) -> bool {
+    ///         false
+    ///     }
+    ///     fn get_supported(&self) -> Vec<&'static str> {
+    ///         vec!["application/x-custom"]
+    ///     }
+    ///     fn get_subclasses(&self) -> Vec<(&'static str, &'static str)> {
+    ///         vec![]
+    ///     }
+    ///     fn get_aliaslist(&self) -> HashMap<&'static str, &'static str> {
+    ///         HashMap::new()
+    ///     }
+    /// }
+    ///
+    /// let mut detector = Detector::new();
+    /// detector.add_checker(Custom);
+    /// assert_eq!(detector.from_u8(b"CUSTOM data"), "application/x-custom");
+    /// ```
+    pub fn add_checker(&mut self, checker: impl Checker + 'static) {
+        let mut checkers = std::mem::take(&mut self.checkers);
+        checkers.push(Box::new(checker));
+        self.rebuild(checkers);
+    }
+
+    /// Keeps only the checkers with the given [names](Checker::name), and
+    /// rebuilds the type graph from just their types.
+    ///
+    /// The built-in checkers are `text`, `fdo_magic`, `signature`, `zip`,
+    /// `isobmff`, `ebml`, `ogg`, `cfb`, `basetype`, and with the `decompress`
+    /// feature, `compressed`. Checkers added with
+    /// [`add_checker`](Detector::add_checker) are `custom` unless they say
+    /// otherwise. Subclasses and aliases added to the detector are kept.
+    ///
+    /// Dropping checkers means fewer types to walk, but they can't be
+    /// detected any more, and neither can anything only reachable through
+    /// them. In particular, `basetype` is what tells text from binary data.
+    /// Without it, `text/plain` and everything under it is never detected, nor
+    /// are empty files or the `inode/*` types, and the text heuristics are
+    /// unreachable. Input that nothing recognizes is still
+    /// `application/octet-stream`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::Detector;
+    ///
+    /// let mut detector = Detector::new();
+    /// detector.retain_checkers(&["fdo_magic"]);
+    /// assert_eq!(detector.from_u8(include_bytes!("../tests/image/gif")), "image/gif");
+    /// assert_eq!(detector.from_u8(b"Hello, world!"), "application/octet-stream");
+    /// ```
+    pub fn retain_checkers(&mut self, names: &[&str]) {
+        let mut checkers = std::mem::take(&mut self.checkers);
+        checkers.retain(|c| names.contains(&c.name()));
+        self.rebuild(checkers);
+    }
+
+    /// Rebuilds everything from `checkers`, keeping the changes made since
+    /// this detector was created
+    fn rebuild(&mut self, checkers: Vec<Box<dyn Checker>>) {
+        let added_subclasses = std::mem::take(&mut self.added_subclasses);
+        let added_aliases = std::mem::take(&mut self.added_aliases);
+        let type_order = std::mem::take(&mut self.type_order);
+        let source = self.source;
+        *self = Detector::with_checker_list(checkers);
+        self.type_order = type_order;
+        self.source = source;
+        for (alias, canonical) in added_aliases {
+            self.add_alias(alias, canonical);
+        }
+        for (parent, child) in added_subclasses {
+            self.add_subclass(child, parent);
+        }
+    }
+
+    /// Adds the rules from a `magic` file, in the compiled format that
+    /// `update-mime-database` writes.
+    ///
+    /// The rules are added as another checker, as with
+    /// [`add_checker`](Detector::add_checker), so a type in the file has its
+    /// existing rules replaced rather than added to. New types go under
+    /// `application/octet-stream`, unless placed elsewhere with
+    /// [`add_subclass`](Detector::add_subclass). The file is kept in memory
+    /// for the rest of the program.
+    ///
+    /// Returns [`Error::Io`] if the file can't be read, and [`Error::Parse`]
+    /// if it isn't a magic file.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::path::Path;
+    /// use tree_magic_mini::Detector;
+    ///
+    /// let mut detector = Detector::new();
+    /// detector.add_magic_file(Path::new("tests/database/custom/magic")).unwrap();
+    /// assert_eq!(detector.from_u8(b"TREEMAGIC data"), "application/x-tree-magic");
+    /// ```
+    pub fn add_magic_file(&mut self, path: &Path) -> Result<(), Error> {
+        let magic = crate::fdo_magic::builtin::dir::from_magic_file(path)?;
+        self.add_checker(magic);
+        Ok(())
+    }
+
+    /// Records that `child` is a subclass of `parent`.
+    ///
+    /// Types that aren't known yet are added to the type graph. The walk then
+    /// tries `child` once `parent` matches, so `child` still needs a checker or
+    /// magic rule of its own to be detected. Returns false and changes nothing
+    /// if `parent` is already a subclass of `child`, since the graph can't have
+    /// cycles.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::Detector;
+    ///
+    /// let mut detector = Detector::new();
+    /// assert!(detector.add_subclass("application/vnd.myapp+zip", "application/zip"));
+    /// assert!(detector.is_subclass("application/vnd.myapp+zip", "application/zip"));
+    /// assert!(!detector.add_subclass("application/zip", "application/vnd.myapp+zip"));
+    /// ```
+    pub fn add_subclass(&mut self, child: &str, parent: &str) -> bool {
+        let child = self.intern(self.get_alias(child));
+        let parent = self.intern(self.get_alias(parent));
+        if self.is_subclass(parent, child) {
+            return false;
+        }
+
+        self.types.add_subclass(child, parent);
+        self.added_subclasses.push((parent, child));
+        true
+    }
+
+    /// Records that `alias` is another name for `canonical`, replacing anything
+    /// `alias` previously stood for.
+    ///
+    /// If `canonical` is itself an alias, `alias` stands for the type that it
+    /// points to.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::Detector;
+    ///
+    /// let mut detector = Detector::new();
+    /// detector.add_alias("application/x-my-zip", "application/zip");
+    ///
+    /// let input: &[u8] = include_bytes!("../tests/application/zip");
+    /// assert!(detector.match_u8("application/x-my-zip", input));
+    /// ```
+    pub fn add_alias(&mut self, alias: &str, canonical: &str) {
+        let canonical = self.intern(self.get_alias(canonical));
+        let alias = match self.aliases.get_key_value(alias) {
+            Some((&known, _)) => known,
+            None => self.intern(alias),
+        };
+        self.aliases.insert(alias, canonical);
+        self.added_aliases.push((alias, canonical));
+    }
+
+    /// The types checked before their siblings when walking the type graph.
+    ///
+    /// Starts out as a few common image, archive, and document types.
+    pub fn type_order(&self) -> &[&'static str] {
+        &self.type_order
+    }
+
+    /// Replaces the types checked before their siblings when walking the type
+    /// graph, with the earliest in `types` checked first.
+    ///
+    /// These come before the priorities of the types' magic, so this also
+    /// settles which of two matching siblings is returned. Checking the types an
+    /// application sees most often first makes detection of those faster. An
+    /// empty list leaves the order to the priorities, then the types' names.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::Detector;
+    ///
+    /// let mut detector = Detector::new();
+    ///
+    /// // Keep the defaults, but check audio first
+    /// let mut order = vec!["audio/flac", "audio/mpeg"];
+    /// order.extend(detector.type_order());
+    /// detector.set_type_order(&order);
+    ///
+    /// let input: &[u8] = include_bytes!("../tests/image/gif");
+    /// assert_eq!(detector.from_u8(input), "image/gif");
+    /// ```
+    pub fn set_type_order(&mut self, types: &[&str]) {
+        self.type_order = types
+            .iter()
+            .map(|&mimetype| self.intern(self.get_alias(mimetype)))
+            .collect();
+    }
+
+    /// Get a `'static` copy of a type, reusing the one in the graph if there is one
+    fn intern(&self, mimetype: &str) -> Mime {
+        match self.types.hash.get_key_value(mimetype) {
+            Some((&known, _)) => known,
+            None => crate::mime_type::intern(mimetype),
+        }
+    }
+
+    /// The root of the type graph, or None if nothing is loaded
+    fn root(&self) -> Option<NodeIndex> {
+        self.types.graph.externals(Incoming).next()
+    }
+
+    /// Whether a match on a type is a guess by one of the text heuristics,
+    /// rather than one of the database's rules for it
+    fn is_text_guess(&self, mimetype: &str) -> bool {
+        self.checker(mimetype)
+            .is_some_and(|c| c.name() == "text" && crate::text::is_guess(mimetype))
+    }
+
+    /// The checker responsible for a type
+    fn checker(&self, mimetype: &str) -> Option<&dyn Checker> {
+        let &i = self.support.get(mimetype)?;
+        Some(&*self.checkers[i])
+    }
+
+    /// The subclasses of a type in the order they're checked: those in the type
+    /// order first, then by their checkers' priorities, then by name, so
+    /// detection doesn't depend on the order the graph was built in
+    fn children(&self, node: NodeIndex) -> Vec<NodeIndex> {
+        let graph = &self.types.graph;
+        let mut children: Vec<NodeIndex> = graph.neighbors_directed(node, Outgoing).collect();
+        children.sort_by_cached_key(|&child| {
+            let mimetype = graph[child];
+            let common = self.type_order.iter().position(|&t| t == mimetype);
+            let priority = self.checker(mimetype).map_or(0, |c| c.priority(mimetype));
+            (common.unwrap_or(self.type_order.len()), Reverse(priority), mimetype)
+        });
+        children
+    }
+
+    /// Just the part of from_*_node that walks the graph
+    fn typegraph_walker<T, F>(&self, parentnode: NodeIndex, input: &T, matchfn: &F) -> Option<Mime>
+    where
+        T: ?Sized,
+        F: Fn(&str, &T) -> bool,
+    {
+        let graph = &self.types.graph;
+
+        // Walk graph
+        for childnode in self.children(parentnode) {
+            let mimetype = graph[childnode];
+
+            let result = matchfn(mimetype, input);
+            match result {
+                true => match self.typegraph_walker(childnode, input, matchfn) {
+                    Some(foundtype) => return Some(foundtype),
+                    None => return Some(mimetype),
+                },
+                false => continue,
+            }
+        }
+
+        None
+    }
+
+    /// Like `typegraph_walker`, but collects every matching leaf along with its
+    /// depth instead of returning the first one. Returns whether any child of
+    /// `parentnode` matched.
+    fn typegraph_walker_all<T, F>(
+        &self,
+        parentnode: NodeIndex,
+        input: &T,
+        matchfn: &F,
+        depth: usize,
+        visited: &mut HashMap<NodeIndex, bool>,
+        found: &mut Vec<(usize, Mime)>,
+    ) -> bool
+    where
+        T: ?Sized,
+        F: Fn(&str, &T) -> bool,
+    {
+        let graph = &self.types.graph;
+        let mut any = false;
+        for childnode in self.children(parentnode) {
+            // Subclass graph is a DAG, so don't check shared children twice,
+            // but a parent of one that matched still isn't a leaf
+            if let Some(&matched) = visited.get(&childnode) {
+                any |= matched;
+                continue;
+            }
+
+            let mimetype = graph[childnode];
+            let matched = matchfn(mimetype, input);
+            visited.insert(childnode, matched);
+            if !matched {
+                continue;
+            }
+            any = true;
+
+            // Nothing more specific matched, so this is a leaf
+            if !self.typegraph_walker_all(childnode, input, matchfn, depth + 1, visited, found) {
+                found.push((depth, mimetype));
+            }
+        }
+        any
+    }
+
+    /// Transforms an alias into it's real type
+    fn get_alias<'a>(&self, mimetype: &'a str) -> &'a str {
+        match self.aliases.get(mimetype) {
+            Some(x) => x,
+            None => mimetype,
+        }
+    }
+
+    /// Resolves an alias to the canonical MIME type it stands for.
+    /// See [`canonical_mime`](crate::canonical_mime).
+    pub fn canonical_mime<'a>(&self, mimetype: &'a str) -> &'a str {
+        self.get_alias(mimetype)
+    }
+
+    /// Checks if the given MIME type is an alias of another type.
+    /// See [`is_alias`](crate::is_alias).
+    pub fn is_alias(&self, mimetype: &str) -> bool {
+        self.aliases.contains_key(mimetype)
+    }
+
+    /// The name of the checker that detects the given MIME type.
+    /// See [`checker_for`](crate::checker_for).
+    pub fn checker_for(&self, mimetype: &str) -> Option<&'static str> {
+        self.checker(self.get_alias(mimetype)).map(|c| c.name())
+    }
+
+    /// Checks if the given MIME type, or the type it's an alias of, is known.
+    /// See [`is_known`](crate::is_known).
+    pub fn is_known(&self, mimetype: &str) -> bool {
+        let mimetype = self.get_alias(mimetype);
+        self.support.contains_key(mimetype) || self.types.hash.contains_key(mimetype)
+    }
+
+    /// Lists every alias of the given canonical MIME type, sorted.
+    /// See [`aliases_of`](crate::aliases_of).
+    pub fn aliases_of(&self, canonical: &str) -> Vec<Mime> {
+        let mut out: Vec<Mime> = self
+            .aliases
+            .iter()
+            .filter(|&(_, &target)| target == canonical)
+            .map(|(&alias, _)| alias)
+            .collect();
+        out.sort_unstable();
+        out
+    }
+
+    /// Lists every MIME type known to the loaded checkers, sorted.
+    /// See [`supported_types`](crate::supported_types).
+    pub fn supported_types(&self) -> Vec<Mime> {
+        let mut out: Vec<Mime> = self
+            .support
+            .keys()
+            .copied()
+            .filter(|m| !is_merged(m, &self.aliases, &self.support))
+            .collect();
+        out.sort_unstable();
+        out
+    }
+
+    /// Checks if one MIME type is a subclass of another.
+    /// See [`is_subclass`](crate::is_subclass).
+    pub fn is_subclass(&self, child: &str, parent: &str) -> bool {
+        let hash = &self.types.hash;
+        let (Some(&child), Some(&parent)) = (
+            hash.get(self.get_alias(child)),
+            hash.get(self.get_alias(parent)),
+        ) else {
+            return false;
+        };
+
+        // Walk up towards the root, since there are far fewer ancestors than descendants
+        let reversed = Reversed(&self.types.graph);
+        let mut dfs = Dfs::new(reversed, child);
+        while let Some(node) = dfs.next(reversed) {
+            if node == parent {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Lists every type the given MIME type is a subclass of, sorted.
+    /// See [`ancestors`](crate::ancestors).
+    pub fn ancestors(&self, mimetype: &str) -> Vec<Mime> {
+        self.graph_reachable(mimetype, Incoming)
+    }
+
+    /// Lists every type that is a subclass of the given MIME type, sorted.
+    /// See [`descendants`](crate::descendants).
+    pub fn descendants(&self, mimetype: &str) -> Vec<Mime> {
+        self.graph_reachable(mimetype, Outgoing)
+    }
+
+    /// Collects every node reachable from the given type in one direction
+    fn graph_reachable(&self, mimetype: &str, direction: Direction) -> Vec<Mime> {
+        let graph = &self.types.graph;
+        let Some(&start) = self.types.hash.get(self.get_alias(mimetype)) else {
+            return Vec::new();
+        };
+
+        let mut visited = HashSet::<NodeIndex>::default();
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for next in graph.neighbors_directed(node, direction) {
+                if next != start && visited.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+
+        let mut out: Vec<Mime> = visited.into_iter().map(|node| graph[node]).collect();
+        out.sort_unstable();
+        out
+    }
+
+    /// Renders the type graph in GraphViz's DOT language.
+    /// See [`subclass_tree_dot`](crate::subclass_tree_dot).
+    pub fn subclass_tree_dot(&self) -> String {
+        use std::fmt::Write;
+
+        let graph = &self.types.graph;
+        let quote = |m: &str| format!("\"{}\"", m.replace('\\', "\\\\").replace('"', "\\\""));
+
+        let mut nodes: Vec<Mime> = graph.node_weights().copied().collect();
+        nodes.sort_unstable();
+        let mut edges: Vec<(Mime, Mime)> = graph
+            .edge_indices()
+            .filter_map(|edge| graph.edge_endpoints(edge))
+            .map(|(parent, child)| (graph[parent], graph[child]))
+            .collect();
+        edges.sort_unstable();
+        let mut aliases: Vec<(Mime, Mime)> = self
+            .aliases
+            .iter()
+            .filter(|(_, canonical)| self.types.hash.contains_key(*canonical))
+            .map(|(&alias, &canonical)| (alias, canonical))
+            .collect();
+        aliases.sort_unstable();
+
+        let mut out = String::from("digraph subclasses {\n");
+        for node in nodes {
+            let _ = writeln!(out, "    {};", quote(node));
+        }
+        for (parent, child) in edges {
+            let _ = writeln!(out, "    {} -> {};", quote(parent), quote(child));
+        }
+        for (alias, canonical) in aliases {
+            let (alias, canonical) = (quote(alias), quote(canonical));
+            let _ = writeln!(out, "    {alias} -> {canonical} [style=dashed];");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Renders the subclass tree as an indented outline.
+    /// See [`subclass_tree_text`](crate::subclass_tree_text).
+    pub fn subclass_tree_text(&self) -> String {
+        let mut out = String::new();
+        if let Some(root) = self.root() {
+            self.write_subtree(root, 0, &mut HashSet::default(), &mut out);
+        }
+        out
+    }
+
+    fn write_subtree(
+        &self,
+        node: NodeIndex,
+        depth: usize,
+        seen: &mut HashSet<NodeIndex>,
+        out: &mut String,
+    ) {
+        use std::fmt::Write;
+
+        let mimetype = self.types.graph[node];
+        let indent = "  ".repeat(depth);
+        if !seen.insert(node) {
+            let _ = writeln!(out, "{indent}{mimetype} (see above)");
+            return;
+        }
+        let _ = writeln!(out, "{indent}{mimetype}");
+        for child in self.children(node) {
+            self.write_subtree(child, depth + 1, seen, out);
+        }
+    }
+
+    /// Internal function. Checks if an alias exists, and if it does,
+    /// then runs `match_bytes`.
+    fn match_u8_noalias(&self, mimetype: &str, bytes: &[u8]) -> bool {
+        match self.checker(mimetype) {
+            None => basetype::is_catch_all(mimetype),
+            Some(y) => y.match_bytes(bytes, mimetype),
+        }
+    }
+
+    /// Checks if the given bytestream matches the given MIME type.
+    /// See [`match_u8`](crate::match_u8).
+    pub fn match_u8(&self, mimetype: &str, bytes: &[u8]) -> bool {
+        self.match_u8_noalias(self.get_alias(mimetype), bytes)
+    }
+
+    /// Checks a byte stream against several MIME types.
+    /// See [`match_any`](crate::match_any).
+    pub fn match_any(&self, candidates: &[&str], bytes: &[u8]) -> Option<Mime> {
+        self.first_match(candidates, |m| self.match_u8_noalias(m, bytes))
+    }
+
+    /// The first candidate, resolved through aliases, that passes a check
+    fn first_match(&self, candidates: &[&str], matches: impl Fn(&str) -> bool) -> Option<Mime> {
+        candidates
+            .iter()
+            .map(|&candidate| self.get_alias(candidate))
+            .find(|&m| matches(m))
+            .map(|m| self.intern(m))
+    }
+
+    /// Counts what's loaded. See [`database_stats`](crate::database_stats).
+    pub fn database_stats(&self) -> DatabaseStats {
+        DatabaseStats {
+            types: self.types.graph.node_count(),
+            subclasses: self.types.graph.edge_count(),
+            aliases: self.aliases.len(),
+            source: self.source,
+        }
+    }
+
+    /// Gets the type of a file from a raw bytestream, starting at a certain node
+    /// in the type graph.
+    ///
+    /// Returns MIME as string wrapped in Some if a type matches, or
+    /// None if no match is found under the given node.
+    fn from_u8_node(&self, parentnode: NodeIndex, bytes: &[u8]) -> Option<Mime> {
+        if let Some(empty) = self.empty_type(bytes) {
+            return Some(empty);
+        }
+        self.typegraph_walker(parentnode, bytes, &|m, b| self.match_u8_noalias(m, b))
+    }
+
+    /// Gets the type of a file from a byte stream.
+    /// See [`from_u8`](crate::from_u8).
+    ///
+    /// # Panics
+    /// Panics if the detector has no filetype definitions loaded.
+    pub fn from_u8(&self, bytes: &[u8]) -> Mime {
+        match self.try_from_u8(bytes) {
+            Some(mimetype) => mimetype,
+            None => panic!("No filetype definitions are loaded."),
+        }
+    }
+
+    /// Gets the type of a file from a byte stream, without panicking.
+    /// See [`try_from_u8`](crate::try_from_u8).
+    pub fn try_from_u8(&self, bytes: &[u8]) -> Option<Mime> {
+        let node = self.root()?;
+        Some(
+            self.from_u8_node(node, bytes)
+                .unwrap_or(self.types.graph[node]),
+        )
+    }
+
+    /// Gets the type of a file from a byte stream, only looking under one type.
+    /// See [`from_u8_under`](crate::from_u8_under).
+    pub fn from_u8_under(&self, start_mime: &str, bytes: &[u8]) -> Option<Mime> {
+        let &node = self.types.hash.get(self.get_alias(start_mime))?;
+        Some(
+            self.from_u8_node(node, bytes)
+                .unwrap_or(self.types.graph[node]),
+        )
+    }
+
+    /// Gets every MIME type a byte stream matches.
+    /// See [`from_u8_all`](crate::from_u8_all).
+    pub fn from_u8_all(&self, bytes: &[u8]) -> Vec<Mime> {
+        self.from_u8_leaves(bytes)
+            .into_iter()
+            .map(|(_, mimetype)| mimetype)
+            .collect()
+    }
+
+    /// Every matching leaf with its depth, deepest first, then alphabetically
+    fn from_u8_leaves(&self, bytes: &[u8]) -> Vec<(usize, Mime)> {
+        let Some(node) = self.root() else {
+            return Vec::new();
+        };
+
+        let mut found = Vec::new();
+        self.typegraph_walker_all(
+            node,
+            bytes,
+            &|m, b| self.match_u8_noalias(m, b),
+            0,
+            &mut HashMap::default(),
+            &mut found,
+        );
+
+        found.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+        found
+    }
+
+    /// Gets the most specific type of a file from a byte stream.
+    /// See [`from_u8_best`](crate::from_u8_best).
+    pub fn from_u8_best(&self, bytes: &[u8]) -> Mime {
+        let first = self.from_u8(bytes);
+        let leaves = self.from_u8_leaves(bytes);
+        let Some(&(deepest, _)) = leaves.first() else {
+            return first;
+        };
+
+        // Among equally deep matches, stick with the usual answer
+        let mut best = leaves.iter().take_while(|&&(depth, _)| depth == deepest);
+        match best.clone().any(|&(_, m)| m == first) {
+            true => first,
+            false => best.next().map_or(first, |&(_, m)| m),
+        }
+    }
+
+    /// Explains why a byte stream matches a MIME type.
+    /// See [`explain`](crate::explain).
+    pub fn explain(&self, mimetype: &str, bytes: &[u8]) -> Option<MatchExplanation> {
+        let mimetype = self.get_alias(mimetype);
+        let checker = self.checker(mimetype)?;
+        if !checker.match_bytes(bytes, mimetype) {
+            return None;
+        }
+        Some(MatchExplanation {
+            mimetype: self.intern(mimetype),
+            checker: checker.name(),
+            rules: checker.explain_bytes(bytes, mimetype),
+        })
+    }
+
+    /// Explains how the type of a byte stream was found.
+    /// See [`explain_detection`](crate::explain_detection).
+    pub fn explain_detection(&self, bytes: &[u8]) -> Option<DetectionExplanation> {
+        let graph = &self.types.graph;
+        let mut node = self.root()?;
+        let mut chain = vec![graph[node]];
+
+        // Take the same path as typegraph_walker
+        'walk: loop {
+            for child in self.children(node) {
+                if self.match_u8_noalias(graph[child], bytes) {
+                    chain.push(graph[child]);
+                    node = child;
+                    continue 'walk;
+                }
+            }
+            break;
+        }
+
+        let detected = *chain.last()?;
+        let leaf = match self.explain(detected, bytes) {
+            Some(leaf) => leaf,
+            // Nothing under the root matched
+            None => MatchExplanation {
+                mimetype: detected,
+                checker: self.checker(detected).map_or("none", |c| c.name()),
+                rules: Vec::new(),
+            },
+        };
+        Some(DetectionExplanation { chain, leaf })
+    }
+
+    /// Gets the type of a file from a byte stream, counting the work done.
+    /// See [`from_u8_with_stats`](crate::from_u8_with_stats).
+    pub fn from_u8_with_stats(&self, bytes: &[u8]) -> (Option<Mime>, DetectionStats) {
+        let mut stats = DetectionStats {
+            checker: "none",
+            ..DetectionStats::default()
+        };
+        let Some(node) = self.root() else {
+            return (None, stats);
+        };
+
+        // Empty input is decided without walking the graph
+        let result = match self.empty_type(bytes) {
+            Some(empty) => Some(empty),
+            None => {
+                let (calls, matches) = (Cell::new(0), Cell::new(0));
+                let result = self.typegraph_walker(node, bytes, &|m, b| {
+                    calls.set(calls.get() + 1);
+                    let matched = self.match_u8_noalias(m, b);
+                    matches.set(matches.get() + matched as usize);
+                    matched
+                });
+                // Every match is descended into, as is the root
+                stats.nodes_visited = matches.get() + 1;
+                stats.match_calls = calls.get();
+                result
+            }
+        };
+
+        if let Some(checker) = result.and_then(|m| self.checker(m)) {
+            stats.checker = checker.name();
+        }
+        (Some(result.unwrap_or(self.types.graph[node])), stats)
+    }
+
+    /// Gets the type of a file from a byte stream, checking at most
+    /// `max_nodes` types. See [`from_u8_bounded`](crate::from_u8_bounded).
+    pub fn from_u8_bounded(&self, bytes: &[u8], max_nodes: usize) -> Mime {
+        let Some(node) = self.root() else {
+            return "application/octet-stream";
+        };
+        if let Some(empty) = self.empty_type(bytes) {
+            return empty;
+        }
+
+        // Once the budget's spent nothing else matches, so the walk stops at
+        // the deepest match so far
+        let calls = Cell::new(0);
+        self.typegraph_walker(node, bytes, &|m, b| {
+            if calls.get() >= max_nodes {
+                return false;
+            }
+            calls.set(calls.get() + 1);
+            self.match_u8_noalias(m, b)
+        })
+        .unwrap_or("application/octet-stream")
+    }
+
+    /// Gets the likeliest types of a byte stream, with scores.
+    /// See [`sniff`](crate::sniff).
+    pub fn sniff(&self, bytes: &[u8], limit: usize) -> Vec<(Mime, f32)> {
+        let mut leaves = self.from_u8_leaves(bytes);
+        if let Some(first) = self.try_from_u8(bytes) {
+            // Among equally deep matches, the usual answer goes first
+            leaves.sort_by_key(|&(depth, m)| (Reverse(depth), m != first, m));
+        }
+        leaves
+            .into_iter()
+            .take(limit)
+            .map(|(depth, m)| {
+                // Depth counts from the root's children, which are one edge down
+                let edges = (depth + 1) as f32;
+                (m, edges / (edges + 1.0))
+            })
+            .collect()
+    }
+
+    /// Check if the given file matches the given MIME type.
+    /// See [`match_file`](crate::match_file).
+    pub fn match_file(&self, mimetype: &str, file: &File) -> bool {
+        self.match_file_noalias(self.get_alias(mimetype), file)
+    }
+
+    /// Checks a file against several MIME types.
+    /// See [`match_any_file`](crate::match_any_file).
+    pub fn match_any_file(&self, candidates: &[&str], file: &File) -> Option<Mime> {
+        let _archive = crate::zip::Scope::new(file);
+        let is_regular = file.metadata().is_ok_and(|meta| meta.is_file());
+        if !is_regular {
+            return self.first_match(candidates, |m| self.match_file_noalias(m, file));
+        }
+
+        let bytes = read_bytes(file, self.file_buffer_len).ok()?;
+        let input = (file, &bytes[..]);
+        self.first_match(candidates, |m| self.match_file_buffer_noalias(m, &input))
+    }
+
+    /// Internal function. Checks if an alias exists, and if it does,
+    /// then runs `match_file`.
+    fn match_file_noalias(&self, mimetype: &str, file: &File) -> bool {
+        match self.checker(mimetype) {
+            None => basetype::is_catch_all(mimetype),
+            Some(c) => c.match_file(file, mimetype),
+        }
+    }
+
+    /// Internal function. Runs `match_bytes` on the start of a file already read
+    /// into memory, unless the checker needs to look at the rest of the file.
+    fn match_file_buffer_noalias(&self, mimetype: &str, (file, bytes): &(&File, &[u8])) -> bool {
+        match self.checker(mimetype) {
+            None => basetype::is_catch_all(mimetype),
+            Some(c) if c.needs_file(mimetype) => c.match_file(file, mimetype),
+            Some(c) => c.match_bytes(bytes, mimetype),
+        }
+    }
+
+    /// Check if the file at the given path matches the given MIME type.
+    /// See [`match_filepath`](crate::match_filepath).
+    pub fn match_filepath(&self, mimetype: &str, path: &Path) -> bool {
+        // Opening a FIFO would wait for a writer, so don't open anything
+        // that isn't a regular file
+        let Ok(meta) = std::fs::metadata(path) else {
+            return false;
+        };
+        if let Some(inode) = self.inode_type(meta.file_type()) {
+            let mimetype = self.get_alias(mimetype);
+            return mimetype == inode || mimetype == "all/all";
+        }
+        let Ok(file) = File::open(path) else {
+            return false;
+        };
+        self.match_file(mimetype, &file)
+    }
+
+    /// Checks the file at the given path against several MIME types.
+    /// See [`match_filepath_any`](crate::match_filepath_any).
+    pub fn match_filepath_any(&self, candidates: &[&str], path: &Path) -> Option<Mime> {
+        // Same as match_filepath, so FIFOs aren't opened
+        let meta = std::fs::metadata(path).ok()?;
+        if let Some(inode) = self.inode_type(meta.file_type()) {
+            return self.first_match(candidates, |m| m == inode || m == "all/all");
+        }
+        let file = File::open(path).ok()?;
+        self.match_any_file(candidates, &file)
+    }
+
+    /// Gets the type of a file, starting at a certain node in the type graph.
+    fn from_file_node(
+        &self,
+        parentnode: NodeIndex,
+        file: &File,
+        buffer_len: usize,
+    ) -> Option<Mime> {
+        // We're actually just going to thunk this down to a u8
+        // unless we're checking via basetype for speed reasons.
+        let _archive = crate::zip::Scope::new(file);
+
+        // Ensure it's at least a application/octet-stream, i.e. a regular file,
+        // without going through a checker that might read it
+        let is_regular = file.metadata().is_ok_and(|meta| meta.is_file());
+        if !is_regular {
+            // Check the other base types
+            return self.typegraph_walker(parentnode, file, &|m, f| self.match_file_noalias(m, f));
+        }
+
+        // Load the start of the file once and parse as u8
+        // for batch processing like this
+        let bytes = read_bytes(file, buffer_len).ok()?;
+        if let Some(empty) = self.empty_type(&bytes) {
+            return Some(empty);
+        }
+        self.typegraph_walker(parentnode, &(file, &bytes[..]), &|m, input| {
+            self.match_file_buffer_noalias(m, input)
+        })
+    }
+
+    /// Gets the MIME type of a file.
+    /// See [`from_file`](crate::from_file).
+    pub fn from_file(&self, file: &File) -> Option<Mime> {
+        self.from_file_with_buffer(file, self.file_buffer_len)
+    }
+
+    /// Gets the MIME type of a file, reading up to `buffer_len` bytes of it.
+    /// See [`from_file_with_buffer`](crate::from_file_with_buffer).
+    pub fn from_file_with_buffer(&self, file: &File, buffer_len: usize) -> Option<Mime> {
+        let node = self.root()?;
+        self.from_file_node(node, file, buffer_len)
+    }
+
+    /// Gets the MIME type of a file, reading all of it if need be.
+    /// See [`from_file_thorough`](crate::from_file_thorough).
+    pub fn from_file_thorough(&self, file: &File) -> Option<Mime> {
+        let mut cursor = file;
+        let start = cursor.stream_position().ok()?;
+        let quick = self.from_file(file)?;
+        if quick != "application/octet-stream" {
+            return Some(quick);
+        }
+
+        let len = file.metadata().ok()?.len().saturating_sub(start);
+        if len <= self.file_buffer_len as u64 {
+            // The first pass already saw everything
+            return Some(quick);
+        }
+        cursor.seek(SeekFrom::Start(start)).ok()?;
+        self.from_file_with_buffer(file, usize::try_from(len).ok()?)
+    }
+
+    /// Gets the MIME type of a file.
+    /// See [`from_filepath`](crate::from_filepath).
+    pub fn from_filepath(&self, path: &Path) -> Option<Mime> {
+        self.from_filepath_with_hint(path)
+    }
+
+    /// Gets the MIME types of several files.
+    /// See [`from_filepaths`](crate::from_filepaths).
+    pub fn from_filepaths(&self, paths: &[PathBuf]) -> Vec<(PathBuf, Option<Mime>)> {
+        paths
+            .iter()
+            .map(|path| (path.clone(), self.from_filepath(path)))
+            .collect()
+    }
+
+    /// Gets the MIME types of several files, spread across threads.
+    /// See [`from_filepaths_parallel`](crate::from_filepaths_parallel).
+    pub fn from_filepaths_parallel(&self, paths: &[PathBuf]) -> Vec<(PathBuf, Option<Mime>)> {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_len = paths.len().div_ceil(threads).max(1);
+        std::thread::scope(|scope| {
+            let chunks: Vec<_> = paths
+                .chunks(chunk_len)
+                .map(|chunk| scope.spawn(|| self.from_filepaths(chunk)))
+                .collect();
+            chunks
+                .into_iter()
+                .flat_map(|chunk| chunk.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect()
+        })
+    }
+
+    /// Gets the MIME type of a file, without following a symlink.
+    /// See [`from_filepath_nofollow`](crate::from_filepath_nofollow).
+    pub fn from_filepath_nofollow(&self, path: &Path) -> Option<Mime> {
+        let meta = std::fs::symlink_metadata(path).ok()?;
+        match self.inode_type(meta.file_type()) {
+            Some(inode) => Some(inode),
+            None => self.from_filepath(path),
+        }
+    }
+
+    /// `application/x-empty` for zero-length input, if the type graph has it
+    fn empty_type(&self, bytes: &[u8]) -> Option<Mime> {
+        let empty = basetype::EMPTY;
+        (bytes.is_empty() && self.types.hash.contains_key(empty)).then_some(empty)
+    }
+
+    /// The inode/* type for a file that isn't a regular file, if the type
+    /// graph has it
+    fn inode_type(&self, file_type: std::fs::FileType) -> Option<Mime> {
+        basetype::inode_type(file_type).filter(|m| self.types.hash.contains_key(m))
+    }
+
+    /// Gets the MIME type of a file, using its name to break ties.
+    /// See [`from_filepath_with_hint`](crate::from_filepath_with_hint).
+    pub fn from_filepath_with_hint(&self, path: &Path) -> Option<Mime> {
+        self.from_filepath_result(path).ok()
+    }
+
+    /// Gets the MIME type of a file, telling apart files that can't be read
+    /// from files of unknown type.
+    /// See [`from_filepath_result`](crate::from_filepath_result).
+    pub fn from_filepath_result(&self, path: &Path) -> Result<Mime, DetectError> {
+        if let Some(inode) = self.inode_type(std::fs::metadata(path)?.file_type()) {
+            return Ok(inode);
+        }
+        let file = File::open(path)?;
+        let _archive = crate::zip::Scope::new(&file);
+        let node = self.root().ok_or(DetectError::NoMatch)?;
+
+        let is_regular = file.metadata()?.is_file();
+        if !is_regular {
+            return self
+                .from_file_node(node, &file, self.file_buffer_len)
+                .ok_or(DetectError::NoMatch);
+        }
+
+        let bytes = read_bytes(&file, self.file_buffer_len)?;
+        if let Some(empty) = self.empty_type(&bytes) {
+            return Ok(empty);
+        }
+        let input = (&file, &bytes[..]);
+        let matchfn = |m: &str, input: &(&File, &[u8])| self.match_file_buffer_noalias(m, input);
+        let magic = self
+            .typegraph_walker(node, &input, &matchfn)
+            .ok_or(DetectError::NoMatch)?;
+
+        let candidates = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => glob::matches(name),
+            None => return Ok(magic),
+        };
+
+        // Refine a generic match, like a text/plain that's named as Markdown
+        if let Some(&refined) = candidates.iter().find(|&&c| glob::refines(self, c, magic)) {
+            return Ok(refined);
+        }
+
+        // A guess from the contents gives way to the name of any other text
+        // type, like a Python script that starts with a `# comment` heading
+        if self.is_text_guess(magic) {
+            if let Some(&named) = candidates
+                .iter()
+                .find(|&&c| glob::refines(self, c, "text/plain"))
+            {
+                return Ok(named);
+            }
+        }
+
+        // Break ties with a sibling the contents match as well, as long as
+        // its rules are no less sure than the ones that matched
+        let graph = &self.types.graph;
+        let parents = |m: &str| {
+            self.types
+                .hash
+                .get(m)
+                .into_iter()
+                .flat_map(|&n| graph.neighbors_directed(n, Incoming))
+        };
+        let priority = |m: &str| self.checker(m).map_or(0, |c| c.priority(m));
+        let siblings = |c: &str| {
+            parents(c)
+                .any(|p| parents(magic).any(|q| q == p) && (p == node || matchfn(graph[p], &input)))
+        };
+        let tied = candidates.iter().find(|&&c| {
+            c != magic && priority(c) >= priority(magic) && siblings(c) && matchfn(c, &input)
+        });
+
+        Ok(tied.copied().unwrap_or(magic))
+    }
+
+    /// Checks if the data read from a stream matches the given MIME type.
+    /// See [`match_reader`](crate::match_reader).
+    pub fn match_reader<R: Read>(&self, mimetype: &str, reader: &mut R) -> bool {
+        let mimetype = self.get_alias(mimetype);
+        let Ok(mut bytes) = read_bytes(&mut *reader, BUFFER_LEN) else {
+            return false;
+        };
+        if self.match_u8_noalias(mimetype, &bytes) {
+            return true;
+        }
+
+        // Only read on if the stream has more and the checker could use it
+        let limit = self
+            .checker(mimetype)
+            .map_or(0, |c| c.scan_len().min(MAX_BUFFER_LEN));
+        if bytes.len() < BUFFER_LEN || limit <= bytes.len() {
+            return false;
+        }
+        let more = (limit - bytes.len()) as u64;
+        reader.take(more).read_to_end(&mut bytes).is_ok()
+            && self.match_u8_noalias(mimetype, &bytes)
+    }
+
+    /// Gets the MIME type of the data read from a stream.
+    /// See [`from_read`](crate::from_read).
+    pub fn from_read<R: Read>(&self, reader: &mut R) -> Option<Mime> {
+        let node = self.root()?;
+        let bytes = read_bytes(reader, BUFFER_LEN).ok()?;
+        self.from_u8_node(node, &bytes)
+    }
+}
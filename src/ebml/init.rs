@@ -0,0 +1,18 @@
+use crate::Mime;
+use std::collections::HashMap;
+
+pub fn get_supported() -> Vec<Mime> {
+    super::FORMATS.iter().map(|f| f.mime).collect()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(Mime, Mime)> {
+    super::FORMATS
+        .iter()
+        .filter_map(|f| Some((f.parent?, f.mime)))
+        .collect()
+}
+
+pub fn get_aliaslist() -> HashMap<Mime, Mime> {
+    HashMap::default()
+}
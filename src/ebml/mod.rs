@@ -0,0 +1,109 @@
+//! Tells apart the formats built on EBML, like Matroska and WebM, by the
+//! `DocType` in their header
+use crate::Mime;
+
+pub mod check;
+pub mod init;
+
+/// ID of the header every EBML file starts with
+const HEADER_ID: u64 = 0x1A45_DFA3;
+
+/// ID of the header element that names the format
+const DOC_TYPE_ID: u64 = 0x4282;
+
+/// Longest header read. Real ones are a few dozen bytes.
+const MAX_HEADER_LEN: usize = 1024;
+
+/// A format and the `DocType` that identifies it
+struct Format {
+    mime: Mime,
+    parent: Option<Mime>,
+    doc_type: &'static [u8],
+}
+
+// The DocType doesn't say whether there's any video, so audio-only Matroska
+// files are reported as video too
+static FORMATS: &[Format] = &[
+    Format {
+        mime: "application/x-matroska",
+        parent: None,
+        doc_type: b"matroska",
+    },
+    Format {
+        mime: "video/x-matroska",
+        parent: Some("application/x-matroska"),
+        doc_type: b"matroska",
+    },
+    Format {
+        mime: "video/webm",
+        parent: None,
+        doc_type: b"webm",
+    },
+];
+
+/// Reads a variable-length integer from the start of `bytes`, returning it
+/// and how many bytes it took. The number of leading zero bits in the first
+/// byte gives the length, and the one bit after them is only kept for IDs.
+fn vint(bytes: &[u8], is_id: bool) -> Option<(u64, usize)> {
+    let first = *bytes.first()?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > 8 {
+        return None;
+    }
+    let first = match is_id {
+        true => first as u64,
+        false => first as u64 & (0xFF >> len),
+    };
+    let val = bytes
+        .get(1..len)?
+        .iter()
+        .fold(first, |val, &b| (val << 8) | b as u64);
+    Some((val, len))
+}
+
+/// Reads an element's ID and size, returning them and where its data starts
+fn element_header(bytes: &[u8]) -> Option<(u64, usize, usize)> {
+    let (id, id_len) = vint(bytes, true)?;
+    let (size, size_len) = vint(bytes.get(id_len..)?, false)?;
+    // Sizes too big for memory, including the "unknown" size of all ones,
+    // just run past the end of the input
+    let size = usize::try_from(size).unwrap_or(usize::MAX);
+    Some((id, size, id_len + size_len))
+}
+
+/// Returns the `DocType` from the EBML header at the start of `bytes`, or
+/// `None` if there isn't one or it's cut off
+fn doc_type(bytes: &[u8]) -> Option<&[u8]> {
+    let (id, size, start) = element_header(bytes)?;
+    if id != HEADER_ID {
+        return None;
+    }
+    let header = &bytes[start..start.saturating_add(size).min(bytes.len())];
+
+    let mut pos = 0;
+    while pos < header.len() {
+        let (id, size, data_start) = element_header(&header[pos..])?;
+        let data_start = pos + data_start;
+        let data_end = data_start.checked_add(size)?;
+        if id == DOC_TYPE_ID {
+            let doc_type = header.get(data_start..data_end)?;
+            // Strings may be padded with zeros
+            let len = doc_type.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+            return Some(&doc_type[..len]);
+        }
+        pos = data_end;
+    }
+    None
+}
+
+fn format(mimetype: &str) -> Option<&'static Format> {
+    FORMATS.iter().find(|f| f.mime == mimetype)
+}
+
+/// Checks whether `bytes` is `mimetype`
+fn matches(bytes: &[u8], mimetype: &str) -> bool {
+    let Some(format) = format(mimetype) else {
+        return false;
+    };
+    doc_type(bytes) == Some(format.doc_type)
+}
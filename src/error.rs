@@ -0,0 +1,100 @@
+//! Errors from loading a MIME database or detecting a type
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// An error loading a MIME database.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A database file couldn't be read.
+    Io { path: PathBuf, source: io::Error },
+    /// A database file isn't in the format `shared-mime-info` produces.
+    Parse { path: PathBuf, message: String },
+    /// No database was found in any of the places searched for one.
+    NotFound { searched: Vec<PathBuf> },
+}
+
+impl Error {
+    /// A copy of a cached error, since `io::Error` can't be cloned
+    pub(crate) fn duplicate(&self) -> Error {
+        match self {
+            Error::Io { path, source } => Error::Io {
+                path: path.clone(),
+                source: io::Error::new(source.kind(), source.to_string()),
+            },
+            Error::Parse { path, message } => Error::Parse {
+                path: path.clone(),
+                message: message.clone(),
+            },
+            Error::NotFound { searched } => Error::NotFound {
+                searched: searched.clone(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "couldn't read {}: {source}", path.display()),
+            Error::Parse { path, message } => {
+                write!(f, "couldn't parse {}: {message}", path.display())
+            }
+            Error::NotFound { searched } => {
+                f.write_str("no MIME database found in ")?;
+                for (i, path) in searched.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", path.display())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Parse { .. } | Error::NotFound { .. } => None,
+        }
+    }
+}
+
+/// An error detecting the type of a file.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DetectError {
+    /// The file couldn't be opened or read.
+    Io(io::Error),
+    /// The file was read, but no type matched it.
+    NoMatch,
+}
+
+impl fmt::Display for DetectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DetectError::Io(e) => e.fmt(f),
+            DetectError::NoMatch => f.write_str("no matching MIME type"),
+        }
+    }
+}
+
+impl std::error::Error for DetectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DetectError::Io(e) => Some(e),
+            DetectError::NoMatch => None,
+        }
+    }
+}
+
+impl From<io::Error> for DetectError {
+    fn from(e: io::Error) -> Self {
+        DetectError::Io(e)
+    }
+}
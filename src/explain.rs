@@ -0,0 +1,55 @@
+//! Reports of why some input was given a type
+
+use crate::Mime;
+
+/// A byte pattern that matched, as reported by
+/// [`Checker::explain_bytes`](crate::Checker::explain_bytes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleMatch {
+    /// Where in the input the pattern was found
+    pub offset: usize,
+    /// The bytes the rule looks for
+    pub pattern: Vec<u8>,
+    /// What the input is ANDed with before it's compared, if anything
+    pub mask: Option<Vec<u8>>,
+}
+
+/// Why some input matched a type, from [`explain`](crate::explain).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MatchExplanation {
+    /// The canonical name of the type that matched
+    pub mimetype: Mime,
+    /// The [name](crate::Checker::name) of the checker that handles the type
+    pub checker: &'static str,
+    /// The rules that matched, outermost first. This is empty for checkers
+    /// that don't work from byte patterns, like the text/binary check.
+    pub rules: Vec<RuleMatch>,
+}
+
+/// How [`from_u8`](crate::from_u8) arrived at its answer, from
+/// [`explain_detection`](crate::explain_detection).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DetectionExplanation {
+    /// Each type matched on the way down the type graph, from the root to the
+    /// detected type
+    pub chain: Vec<Mime>,
+    /// Why the detected type matched
+    pub leaf: MatchExplanation,
+}
+
+/// What [`from_u8`](crate::from_u8) did to find its answer, from
+/// [`from_u8_with_stats`](crate::from_u8_with_stats).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct DetectionStats {
+    /// How many nodes of the type graph had their children checked, counting
+    /// the root
+    pub nodes_visited: usize,
+    /// How many times a checker was asked whether the input matched a type
+    pub match_calls: usize,
+    /// The [name](crate::Checker::name) of the checker that handles the
+    /// detected type, or `"none"` if nothing was detected
+    pub checker: &'static str,
+}
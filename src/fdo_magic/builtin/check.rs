@@ -1,32 +1,120 @@
-use super::ALL_RULES;
-use crate::{fdo_magic::check::from_u8_walker, read_bytes, Mime};
+use super::{MagicEntry, LOADED_RULES};
+use crate::{fdo_magic::check::from_u8_walker, read_bytes, Mime, RuleMatch};
 use std::collections::HashMap;
 use petgraph::prelude::*;
 use std::fs::File;
 
-pub(crate) struct FdoMagic;
+pub(crate) struct FdoMagic {
+    rules: &'static HashMap<Mime, MagicEntry<'static>>,
+    aliases: HashMap<Mime, Mime>,
+    subclasses: Vec<(Mime, Mime)>,
+}
+
+impl FdoMagic {
+    /// The database loaded at runtime, or embedded with `with-gpl-data`
+    pub fn builtin() -> FdoMagic {
+        FdoMagic {
+            rules: &LOADED_RULES.0,
+            aliases: super::init::get_aliaslist(),
+            subclasses: super::init::get_subclasses(),
+        }
+    }
+
+    /// A database loaded from somewhere else, with subclasses given as
+    /// parent -> child links
+    pub fn new(
+        rules: &'static HashMap<Mime, MagicEntry<'static>>,
+        aliases: HashMap<Mime, Mime>,
+        subclasses: Vec<(Mime, Mime)>,
+    ) -> FdoMagic {
+        FdoMagic {
+            rules,
+            aliases,
+            subclasses,
+        }
+    }
+
+    /// Whether this has magic for any type
+    #[cfg(not(feature = "with-gpl-data"))]
+    pub fn has_rules(&self) -> bool {
+        !self.rules.is_empty()
+    }
+
+    /// Add the types from `other` to this database, with `other` winning when
+    /// both have rules or aliases for the same type
+    pub fn overlay(mut self, other: FdoMagic) -> FdoMagic {
+        let mut rules = self.rules.clone();
+        rules.extend(other.rules.iter().map(|(&m, graph)| (m, graph.clone())));
+        self.aliases.extend(other.aliases);
+
+        // Either side's links may use the other's aliases
+        let aliases = &self.aliases;
+        let canonical = |m: Mime| aliases.get(m).copied().unwrap_or(m);
+        let mut subclasses: Vec<(Mime, Mime)> = self
+            .subclasses
+            .iter()
+            .chain(&other.subclasses)
+            .map(|&(parent, child)| (canonical(parent), canonical(child)))
+            .collect();
+        subclasses.sort_unstable();
+        subclasses.dedup();
+
+        FdoMagic {
+            rules: Box::leak(Box::new(rules)),
+            aliases: self.aliases,
+            subclasses,
+        }
+    }
+}
 
 impl crate::Checker for FdoMagic {
     fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
         // Get magic ruleset
-        let Some(graph) = ALL_RULES.get(mimetype) else {
+        let Some(entry) = self.rules.get(mimetype) else {
             return false;
         };
+        let graph = &entry.rules;
 
         // Check all rulesets
         graph
             .externals(Incoming)
-            .any(|node| from_u8_walker(bytes, graph, node, true))
+            .any(|node| from_u8_walker(bytes, graph, node, &mut |_, _| {}))
+    }
+
+    fn explain_bytes(&self, bytes: &[u8], mimetype: &str) -> Vec<RuleMatch> {
+        let Some(entry) = self.rules.get(mimetype) else {
+            return Vec::new();
+        };
+        let graph = &entry.rules;
+
+        let mut found = Vec::new();
+        let mut record = |node: NodeIndex, offset| {
+            let rule = &graph[node];
+            found.push(RuleMatch {
+                offset,
+                pattern: rule.val.to_vec(),
+                mask: rule.mask.as_deref().map(<[u8]>::to_vec),
+            });
+        };
+        if graph
+            .externals(Incoming)
+            .any(|root| from_u8_walker(bytes, graph, root, &mut record))
+        {
+            // The walker reports the innermost rule first
+            found.reverse();
+        }
+        found
     }
 
     fn match_file(&self, file: &File, mimetype: &str) -> bool {
         // Get magic ruleset
-        let Some(magic_rules) = ALL_RULES.get(mimetype) else {
+        let Some(entry) = self.rules.get(mimetype) else {
             return false;
         };
 
         // Get # of bytes to read
-        let scanlen = magic_rules
+        let scanlen = entry
+            .rules
             .node_weights()
             .map(|rule| rule.scan_len())
             .max()
@@ -39,15 +127,32 @@ impl crate::Checker for FdoMagic {
         self.match_bytes(&bytes, mimetype)
     }
 
+    fn name(&self) -> &'static str {
+        "fdo_magic"
+    }
+
+    fn scan_len(&self) -> usize {
+        self.rules
+            .values()
+            .flat_map(|entry| entry.rules.node_weights())
+            .map(|rule| rule.scan_len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn priority(&self, mimetype: &str) -> u32 {
+        self.rules.get(mimetype).map_or(50, |entry| entry.priority)
+    }
+
     fn get_supported(&self) -> Vec<Mime> {
-        super::init::get_supported()
+        self.rules.keys().cloned().collect()
     }
 
     fn get_subclasses(&self) -> Vec<(Mime, Mime)> {
-        super::init::get_subclasses()
+        self.subclasses.clone()
     }
 
     fn get_aliaslist(&self) -> HashMap<Mime, Mime> {
-        super::init::get_aliaslist()
+        self.aliases.clone()
     }
 }
@@ -0,0 +1,124 @@
+//! Load the magic database files from a given directory
+
+use std::fs::read;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use std::collections::HashMap;
+
+use super::check::FdoMagic;
+use super::init::{pairs, subclass_links};
+use crate::fdo_magic::ruleset;
+use crate::{Error, Mime};
+
+/// Directories searched for the database at runtime, from lowest to highest
+/// precedence, as in the XDG standard
+pub fn system_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![
+        PathBuf::from("/usr/share/mime"),
+        PathBuf::from("/usr/local/share/mime"),
+    ];
+    if let Some(home) = home::home_dir() {
+        dirs.push(home.join(".local/share/mime"));
+    }
+    dirs
+}
+
+/// Read a file from the directory, keeping it around for the rest of the program
+/// since the types parsed out of it are `&'static str`. Missing files are only
+/// an error if they're `required`.
+fn load(dir: &Path, filename: &str, required: bool) -> Result<&'static [u8], Error> {
+    let path = dir.join(filename);
+    match read(&path) {
+        Ok(bytes) => Ok(Box::leak(bytes.into_boxed_slice())),
+        Err(e) if e.kind() == ErrorKind::NotFound && !required => Ok(&[]),
+        Err(source) => Err(Error::Io { path, source }),
+    }
+}
+
+/// Parse an `aliases` or `subclasses` file into pairs of types
+fn load_pairs(dir: &Path, filename: &str) -> Result<Vec<(Mime, Mime)>, Error> {
+    let parse_error = |message: String| Error::Parse {
+        path: dir.join(filename),
+        message,
+    };
+
+    let text =
+        std::str::from_utf8(load(dir, filename, false)?).map_err(|e| parse_error(e.to_string()))?;
+    pairs(text)
+        .collect::<Result<_, _>>()
+        .map_err(|line| parse_error(format!("expected two types on line {line}")))
+}
+
+/// Load the `magic`, `aliases`, and `subclasses` files from a directory laid
+/// out like `/usr/share/mime`. Only `magic` has to exist.
+pub fn from_dir(dir: &Path) -> Result<FdoMagic, Error> {
+    load_dir(dir, true)
+}
+
+/// Load a lone `magic` file, which has no aliases or subclasses to go with it
+pub fn from_magic_file(path: &Path) -> Result<FdoMagic, Error> {
+    let magic = read(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let magic = Box::leak(magic.into_boxed_slice());
+    let rules = ruleset::from_u8(magic).map_err(|message| Error::Parse {
+        path: path.to_path_buf(),
+        message,
+    })?;
+    Ok(FdoMagic::new(
+        Box::leak(Box::new(rules)),
+        HashMap::new(),
+        Vec::new(),
+    ))
+}
+
+/// The database in the system directories, read afresh, with later
+/// directories winning over earlier ones
+#[cfg(not(feature = "with-gpl-data"))]
+pub fn from_system_dirs() -> Result<FdoMagic, Error> {
+    let mut found = false;
+    let mut magic = FdoMagic::new(Box::leak(Box::default()), HashMap::new(), Vec::new());
+    for dir in system_dirs() {
+        let layer = load_dir(&dir, false)?;
+        found |= layer.has_rules();
+        magic = magic.overlay(layer);
+    }
+    match found {
+        true => Ok(magic),
+        false => Err(Error::NotFound {
+            searched: system_dirs().iter().map(|dir| dir.join("magic")).collect(),
+        }),
+    }
+}
+
+/// The embedded database, overlaid with anything found in the system directories
+#[cfg(feature = "with-gpl-data")]
+pub fn embedded_plus_system() -> Result<FdoMagic, Error> {
+    let mut magic = FdoMagic::builtin();
+    for dir in system_dirs() {
+        magic = magic.overlay(load_dir(&dir, false)?);
+    }
+    Ok(magic)
+}
+
+fn load_dir(dir: &Path, require_magic: bool) -> Result<FdoMagic, Error> {
+    let magic = load(dir, "magic", require_magic)?;
+    let rules = match magic {
+        [] => HashMap::new(),
+        magic => ruleset::from_u8(magic).map_err(|message| Error::Parse {
+            path: dir.join("magic"),
+            message,
+        })?,
+    };
+
+    let aliases: HashMap<Mime, Mime> = load_pairs(dir, "aliases")?.into_iter().collect();
+    let subclasses = subclass_links(load_pairs(dir, "subclasses")?, &aliases);
+
+    Ok(FdoMagic::new(
+        Box::leak(Box::new(rules)),
+        aliases,
+        subclasses,
+    ))
+}
@@ -1,9 +1,20 @@
 use crate::Mime;
 use std::collections::HashMap;
 
+#[cfg(feature = "with-gpl-data")]
+use once_cell::sync::Lazy;
+
 #[cfg(not(feature = "with-gpl-data"))]
 use super::runtime;
 
+#[cfg(feature = "with-gpl-data")]
+static DESCRIPTIONS: Lazy<HashMap<Mime, &str>> = Lazy::new(|| {
+    tree_magic_db::descriptions()
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .collect()
+});
+
 fn aliases() -> &'static str {
     #[cfg(feature = "with-gpl-data")]
     return tree_magic_db::aliases();
@@ -18,39 +29,58 @@ fn subclasses() -> &'static str {
     return runtime::subclasses();
 }
 
-pub fn get_aliaslist() -> HashMap<Mime, Mime> {
-    aliases()
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let mut parts = line.split_whitespace();
-            let a = parts.next().unwrap();
-            let b = parts.next().unwrap();
-            (a, b)
-        })
-        .collect()
+/// File name patterns, used by the glob checker
+pub fn globs2() -> &'static str {
+    #[cfg(feature = "with-gpl-data")]
+    return tree_magic_db::globs2();
+    #[cfg(not(feature = "with-gpl-data"))]
+    return runtime::globs2();
 }
 
-/// Get list of supported MIME types
-pub fn get_supported() -> Vec<Mime> {
-    super::ALL_RULES.keys().cloned().collect()
+/// English description of a type, if the database has one
+pub fn description(mimetype: &str) -> Option<&'static str> {
+    #[cfg(feature = "with-gpl-data")]
+    return DESCRIPTIONS.get(mimetype).copied();
+    #[cfg(not(feature = "with-gpl-data"))]
+    return runtime::description(mimetype);
 }
 
-/// Get list of parent -> child subclass links
-pub fn get_subclasses() -> Vec<(Mime, Mime)> {
-    subclasses()
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
+/// Split each line of an `aliases` or `subclasses` file into its two types,
+/// or the line number of a line that isn't a pair of types
+pub fn pairs(text: &str) -> impl Iterator<Item = Result<(&str, &str), usize>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
             let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(a), Some(b), None) => Ok((a, b)),
+                _ => Err(i + 1),
+            }
+        })
+}
 
-            let child = parts.next().unwrap();
-            let child = super::ALIASES.get(child).copied().unwrap_or(child);
-
-            let parent = parts.next().unwrap();
-            let parent = super::ALIASES.get(parent).copied().unwrap_or(parent);
-
+/// Turn `subclasses` pairs of child and parent into parent -> child links,
+/// resolving aliases on both ends
+pub fn subclass_links(
+    pairs: impl IntoIterator<Item = (Mime, Mime)>,
+    aliases: &HashMap<Mime, Mime>,
+) -> Vec<(Mime, Mime)> {
+    pairs
+        .into_iter()
+        .map(|(child, parent)| {
+            let child = aliases.get(child).copied().unwrap_or(child);
+            let parent = aliases.get(parent).copied().unwrap_or(parent);
             (parent, child)
         })
         .collect()
 }
+
+pub fn get_aliaslist() -> HashMap<Mime, Mime> {
+    pairs(aliases()).filter_map(Result::ok).collect()
+}
+
+/// Get list of parent -> child subclass links
+pub fn get_subclasses() -> Vec<(Mime, Mime)> {
+    subclass_links(pairs(subclasses()).filter_map(Result::ok), &super::ALIASES)
+}
@@ -1,12 +1,12 @@
 //! Read magic file bundled in crate
 
-use super::MagicRule;
-use crate::Mime;
+use super::MagicEntry;
+use crate::{Error, Mime};
 use std::collections::HashMap;
 use once_cell::sync::Lazy;
-use petgraph::prelude::*;
 
 pub mod check;
+pub mod dir;
 pub mod init;
 
 #[cfg(not(feature = "with-gpl-data"))]
@@ -15,10 +15,24 @@ mod runtime;
 /// Preload alias list
 static ALIASES: Lazy<HashMap<Mime, Mime>> = Lazy::new(init::get_aliaslist);
 
-/// Load magic file before anything else.
-static ALL_RULES: Lazy<HashMap<Mime, DiGraph<MagicRule<'static>, u32>>> = Lazy::new(|| {
+/// Load magic file before anything else, keeping what went wrong for `init`.
+static LOADED_RULES: Lazy<(HashMap<Mime, MagicEntry<'static>>, Option<Error>)> = Lazy::new(|| {
     #[cfg(feature = "with-gpl-data")]
-    return super::ruleset::from_u8(tree_magic_db::magic()).unwrap_or_default();
+    return match super::ruleset::from_u8(tree_magic_db::magic()) {
+        Ok(rules) => (rules, None),
+        Err(message) => (
+            HashMap::new(),
+            Some(Error::Parse {
+                path: "tree_magic_db/magic".into(),
+                message,
+            }),
+        ),
+    };
     #[cfg(not(feature = "with-gpl-data"))]
-    return runtime::rules().unwrap_or_default();
+    return runtime::rules();
 });
+
+/// Why the default database couldn't be loaded, if it couldn't
+pub fn load_error() -> Option<&'static Error> {
+    LOADED_RULES.1.as_ref()
+}
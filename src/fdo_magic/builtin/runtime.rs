@@ -1,34 +1,50 @@
 //! Enable loading the magic database files at runtime rather than embedding the GPLed database
 
 use std::fs::{read, read_to_string};
+use std::io::ErrorKind;
 use std::path::PathBuf;
 
 use std::collections::HashMap;
-use once_cell::sync::OnceCell;
-use petgraph::prelude::DiGraph;
+use std::sync::Mutex;
+use once_cell::sync::{Lazy, OnceCell};
 
-use super::MagicRule;
+use super::dir::system_dirs;
+use super::MagicEntry;
 use crate::fdo_magic::ruleset;
-use crate::Mime;
+use crate::{Error, Mime};
 
 fn search_paths(filename: &str) -> Vec<PathBuf> {
-    let mut search_paths = vec![
-        PathBuf::from("/usr/share/mime").join(filename),
-        PathBuf::from("/usr/local/share/mime").join(filename),
-    ];
-    if let Some(home) = home::home_dir() {
-        search_paths.push(home.join(".local/share/mime").join(filename));
-    }
-    search_paths
+    system_dirs()
+        .into_iter()
+        .map(|dir| dir.join(filename))
+        .collect()
 }
 
+/// Magic files and where they were read from, with the first error from a
+/// file that couldn't be read
+type MagicFiles = (Vec<(PathBuf, Vec<u8>)>, Option<Error>);
+
 /// Load the magic database from the predefined locations in the XDG standard
-fn load_xdg_shared_magic() -> Vec<Vec<u8>> {
-    search_paths("magic")
-        .iter()
-        .map(read)
-        .filter_map(Result::ok)
-        .collect()
+fn load_xdg_shared_magic() -> MagicFiles {
+    let mut files = Vec::new();
+    let mut error = None;
+    let searched = search_paths("magic");
+    for path in &searched {
+        match read(path) {
+            Ok(bytes) => files.push((path.clone(), bytes)),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(source) => {
+                error.get_or_insert(Error::Io {
+                    path: path.clone(),
+                    source,
+                });
+            }
+        }
+    }
+    if files.is_empty() && error.is_none() {
+        error = Some(Error::NotFound { searched });
+    }
+    (files, error)
 }
 
 /// Load a number of files at `paths` and concatenate them together with a newline
@@ -51,8 +67,61 @@ pub fn subclasses() -> &'static str {
     SUBCLASS_STRING.get_or_init(|| load_concat_strings("subclasses"))
 }
 
-pub fn rules() -> Result<HashMap<Mime, DiGraph<MagicRule<'static>, u32>>, String> {
-    static RUNTIME_RULES: OnceCell<Vec<Vec<u8>>> = OnceCell::new();
-    let files = RUNTIME_RULES.get_or_init(load_xdg_shared_magic);
-    ruleset::from_multiple(files)
+pub fn globs2() -> &'static str {
+    static GLOBS2_STRING: OnceCell<String> = OnceCell::new();
+    GLOBS2_STRING.get_or_init(|| load_concat_strings("globs2"))
+}
+
+/// Get the English description of a type from its entry in the database, like
+/// `image/png.xml`. Each type's file is only read once.
+///
+/// Types without one aren't remembered, so callers passing along whatever
+/// names they're given can't fill the cache with them. Only as many types as
+/// the database has files for can be.
+pub fn description(mimetype: &str) -> Option<&'static str> {
+    static DESCRIPTIONS: Lazy<Mutex<HashMap<String, &'static str>>> = Lazy::new(Default::default);
+
+    // Don't let the type wander off to other files
+    if mimetype.contains("..") || mimetype.split('/').count() != 2 {
+        return None;
+    }
+
+    let mut cache = DESCRIPTIONS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(&found) = cache.get(mimetype) {
+        return Some(found);
+    }
+
+    // Files in the user's directory take precedence
+    let found = search_paths(&format!("{mimetype}.xml"))
+        .iter()
+        .rev()
+        .find_map(|path| read_to_string(path).ok())
+        .and_then(|xml| english_comment(&xml))
+        .map(|comment| &*Box::leak(comment.into_boxed_str()))?;
+    cache.insert(mimetype.to_string(), found);
+    Some(found)
+}
+
+/// Pull the untranslated `<comment>` out of a type's XML
+fn english_comment(xml: &str) -> Option<String> {
+    let start = xml.find("<comment>")? + "<comment>".len();
+    let len = xml[start..].find("</comment>")?;
+    let comment = xml[start..start + len]
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    Some(comment)
+}
+
+/// The rules from every magic file that could be read, and the first thing
+/// that went wrong loading them. A file that can't be parsed leaves no rules.
+pub fn rules() -> (HashMap<Mime, MagicEntry<'static>>, Option<Error>) {
+    static RUNTIME_RULES: OnceCell<MagicFiles> = OnceCell::new();
+    let (files, error) = RUNTIME_RULES.get_or_init(load_xdg_shared_magic);
+    match ruleset::from_multiple(files) {
+        Ok(rules) => (rules, error.as_ref().map(Error::duplicate)),
+        Err(e) => (HashMap::new(), Some(e)),
+    }
 }
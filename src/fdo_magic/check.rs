@@ -2,70 +2,54 @@ use petgraph::prelude::*;
 use std::cmp::min;
 use std::iter::zip;
 
-fn from_u8_singlerule(bytes: &[u8], rule: &super::MagicRule) -> bool {
+/// Where in `bytes` the rule matches, if it does
+fn from_u8_singlerule(bytes: &[u8], rule: &super::MagicRule) -> Option<usize> {
     // Check if we're even in bounds
     let bound_min = rule.start_off as usize;
     if bound_min > bytes.len() {
-        return false;
+        return None;
     }
-    let bound_max = rule.start_off as usize + rule.val.len() + rule.region_len as usize;
-    let bound_max = min(bound_max, bytes.len());
+    // Only search as much of the range as was read
+    let bound_max = min(rule.scan_len(), bytes.len());
 
     let testarea = &bytes[bound_min..bound_max];
+    // A rule with nothing to compare can't be slid along the input
+    if rule.val.is_empty() {
+        return Some(bound_min);
+    }
 
-    testarea.windows(rule.val.len()).any(|window| {
-        // Apply mask to value
-        match rule.mask {
-            None => rule.val == window,
-            Some(mask) => {
-                assert_eq!(window.len(), mask.len());
-                let masked = zip(window, mask).map(|(a, b)| a & b);
-                rule.val.iter().copied().eq(masked)
-            }
+    let pos = testarea.windows(rule.val.len()).position(|window| {
+        // Only the bits in the mask are compared, on both sides
+        match rule.mask.as_deref() {
+            None => *rule.val == *window,
+            Some(mask) => zip(zip(&*rule.val, window), mask).all(|((v, w), m)| v & m == w & m),
         }
-    })
+    })?;
+    Some(bound_min + pos)
 }
 
-/// Test every given rule by walking graph
-/// TODO: Not loving the code duplication here.
+/// Whether a rule matches, along with at least one of the rules nested under
+/// it if it has any. Every offset is from the start of the input, at any
+/// level.
+///
+/// `found` is called with each rule on the first branch that matched and
+/// where it matched, innermost first. Rules on branches that fail further
+/// down aren't reported.
 pub fn from_u8_walker(
     bytes: &[u8],
     graph: &DiGraph<super::MagicRule, u32>,
     node: NodeIndex,
-    isroot: bool,
+    found: &mut impl FnMut(NodeIndex, usize),
 ) -> bool {
-    let n = graph.neighbors_directed(node, Outgoing);
-
-    if isroot {
-        let rule = &graph[node];
-
-        // Check root
-        if !from_u8_singlerule(bytes, rule) {
-            return false;
-        }
-
-        // Return if that was the only test
-        if n.clone().count() == 0 {
-            return true;
-        }
-
-        // Otherwise next indent level is lower, so continue
-    }
-
-    // Check subrules recursively
-    for y in n {
-        let rule = &graph[y];
+    let Some(offset) = from_u8_singlerule(bytes, &graph[node]) else {
+        return false;
+    };
 
-        if from_u8_singlerule(bytes, rule) {
-            // Check next indent level if needed
-            if graph.neighbors_directed(y, Outgoing).count() != 0 {
-                return from_u8_walker(bytes, graph, y, false);
-            // Next indent level is lower, so this must be it
-            } else {
-                return true;
-            }
-        }
+    let mut children = graph.neighbors_directed(node, Outgoing).peekable();
+    let matched = children.peek().is_none()
+        || children.any(|child| from_u8_walker(bytes, graph, child, found));
+    if matched {
+        found(node, offset);
     }
-
-    false
+    matched
 }
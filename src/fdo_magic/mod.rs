@@ -2,18 +2,30 @@
 
 pub mod builtin;
 
+use std::borrow::Cow;
+
 #[derive(Debug, Clone)]
 pub struct MagicRule<'a> {
     indent_level: u32,
     start_off: u32,
-    val: &'a [u8],
-    mask: Option<&'a [u8]>,
-    region_len: u32,
+    /// The bytes to look for, in the order they appear in the input
+    val: Cow<'a, [u8]>,
+    mask: Option<Cow<'a, [u8]>>,
+    /// How many offsets, from `start_off` on, the value may be found at
+    range_len: u32,
+}
+
+/// The magic rules for one type, and how much a match on them is preferred
+/// over matches on other types, from 0 to 100
+#[derive(Debug, Clone)]
+pub struct MagicEntry<'a> {
+    pub priority: u32,
+    pub rules: petgraph::graph::DiGraph<MagicRule<'a>, u32>,
 }
 
 impl MagicRule<'_> {
     fn scan_len(&self) -> usize {
-        self.start_off as usize + self.val.len() + self.region_len as usize
+        self.start_off as usize + self.val.len() + self.range_len as usize - 1
     }
 }
 
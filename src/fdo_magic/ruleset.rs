@@ -1,4 +1,4 @@
-use super::MagicRule;
+use super::{MagicEntry, MagicRule};
 
 use std::collections::HashMap;
 use nom::{
@@ -7,10 +7,11 @@ use nom::{
     combinator::{map, map_res, opt},
     multi::many0,
     number::complete::be_u16,
-    sequence::{delimited, preceded, terminated, tuple},
+    sequence::{preceded, terminated, tuple},
     IResult,
 };
 use petgraph::prelude::*;
+use std::borrow::Cow;
 use std::str;
 
 // Singular magic ruleset
@@ -27,7 +28,7 @@ fn magic_rules(input: &[u8]) -> IResult<&[u8], MagicRule<'_>> {
         be_u16,
     ))(input)?;
 
-    let (input, (val, mask, _word_len, region_len)) = terminated(
+    let (input, (val, mask, word_len, range_len)) = terminated(
         tuple((
             take(val_len),
             opt(preceded(tag("&"), take(val_len))),
@@ -42,30 +43,50 @@ fn magic_rules(input: &[u8]) -> IResult<&[u8], MagicRule<'_>> {
         MagicRule {
             indent_level,
             start_off,
-            val,
-            mask,
-            region_len: region_len.unwrap_or(0),
+            val: host_order(val, word_len),
+            mask: mask.map(|mask| host_order(mask, word_len)),
+            range_len: range_len.unwrap_or(1).max(1),
         },
     ))
 }
 
+/// Puts a value into the byte order it has in files made on this machine.
+///
+/// Values of rules with a word size, like `host16` and `host32` in the
+/// database source, are stored big-endian, so on little-endian machines each
+/// word is reversed. Values that aren't a whole number of words are left as
+/// they are.
+fn host_order(val: &[u8], word_len: Option<u32>) -> Cow<'_, [u8]> {
+    let len = word_len.unwrap_or(1) as usize;
+    if cfg!(target_endian = "big") || len < 2 || !val.len().is_multiple_of(len) {
+        return Cow::Borrowed(val);
+    }
+    val.chunks_exact(len)
+        .flat_map(|word| word.iter().rev())
+        .copied()
+        .collect()
+}
+
+/// A ruleset's priority, MIME type, and rules, before they're made into a graph
+type RawEntry<'a> = (u32, &'a str, Vec<MagicRule<'a>>);
+
 /// Converts a magic file given as a &[u8] array
-/// to a vector of MagicEntry structs
-fn ruleset(input: &[u8]) -> IResult<&[u8], Vec<(&str, Vec<MagicRule<'_>>)>> {
-    // Parse the MIME type from "[priority: mime]"
-    let mime = map_res(
-        terminated(
-            delimited(
-                delimited(tag("["), is_not(":"), tag(":")), // priority
-                is_not("]"),                                // mime
-                tag("]"),
-            ),
-            tag("\n"),
-        ),
-        str::from_utf8,
+/// to a vector of RawEntry tuples
+fn ruleset(input: &[u8]) -> IResult<&[u8], Vec<RawEntry<'_>>> {
+    // Parse the priority and MIME type from "[priority:mime]"
+    let priority = map_res(map_res(is_not(":"), str::from_utf8), str::parse);
+    let mime = map_res(is_not("]"), str::from_utf8);
+    let header = terminated(
+        tuple((
+            preceded(tag("["), terminated(priority, tag(":"))),
+            terminated(mime, tag("]")),
+        )),
+        tag("\n"),
     );
 
-    let magic_entry = tuple((mime, many0(magic_rules)));
+    let magic_entry = map(tuple((header, many0(magic_rules))), |((priority, mime), rules)| {
+        (priority, mime, rules)
+    });
     preceded(tag("MIME-Magic\0\n"), many0(magic_entry))(input)
 }
 
@@ -98,28 +119,46 @@ fn gen_graph(magic_rules: Vec<MagicRule<'_>>) -> DiGraph<MagicRule<'_>, u32> {
     graph
 }
 
-#[cfg(feature = "with-gpl-data")]
-pub fn from_u8(b: &[u8]) -> Result<HashMap<&str, DiGraph<MagicRule<'_>, u32>>, String> {
-    let tuplevec = ruleset(b).map_err(|e| e.to_string())?.1;
-    let res = tuplevec
+/// Build each type's graph, merging the rulesets of types that have several,
+/// like a type with rules at two different priorities
+fn gen_entries(tuplevec: Vec<RawEntry<'_>>) -> HashMap<&str, MagicEntry<'_>> {
+    let mut merged = HashMap::<&str, (u32, Vec<MagicRule>)>::default();
+    for (priority, mime, rules) in tuplevec {
+        let (max_priority, all_rules) = merged.entry(mime).or_insert((priority, Vec::new()));
+        *max_priority = priority.max(*max_priority);
+        all_rules.extend(rules);
+    }
+
+    merged
         .into_iter()
-        .map(|x| (x.0, gen_graph(x.1)))
-        .collect();
-    Ok(res)
+        .map(|(mime, (priority, rules))| {
+            let rules = gen_graph(rules);
+            (mime, MagicEntry { priority, rules })
+        })
+        .collect()
+}
+
+/// Parse a single magic file, which must be entirely made of rulesets
+pub fn from_u8(b: &[u8]) -> Result<HashMap<&str, MagicEntry<'_>>, String> {
+    let (rest, tuplevec) = ruleset(b).map_err(|e| e.to_string())?;
+    if !rest.is_empty() {
+        return Err(format!("unexpected data at byte {}", b.len() - rest.len()));
+    }
+    Ok(gen_entries(tuplevec))
 }
 
 #[cfg(not(feature = "with-gpl-data"))]
 /// Parse multiple ruleset magic files and aggregate the tuples into a single graph
 pub fn from_multiple(
-    files: &[Vec<u8>],
-) -> Result<HashMap<&str, DiGraph<MagicRule<'_>, u32>>, String> {
+    files: &[(std::path::PathBuf, Vec<u8>)],
+) -> Result<HashMap<&str, MagicEntry<'_>>, crate::Error> {
     let mut tuplevec = vec![];
-    for slice in files {
-        tuplevec.append(&mut ruleset(slice.as_ref()).map_err(|e| e.to_string())?.1);
+    for (path, slice) in files {
+        let (_, mut rules) = ruleset(slice.as_ref()).map_err(|e| crate::Error::Parse {
+            path: path.clone(),
+            message: e.to_string(),
+        })?;
+        tuplevec.append(&mut rules);
     }
-    let res = tuplevec
-        .into_iter()
-        .map(|x| (x.0, gen_graph(x.1)))
-        .collect();
-    Ok(res)
+    Ok(gen_entries(tuplevec))
 }
@@ -0,0 +1,169 @@
+//! Handles file name patterns from the shared MIME database's `globs2` file
+//!
+//! Matching a file name can't tell whether the contents are actually of that
+//! type, so this is only used to break ties and refine generic magic results.
+use crate::{Detector, Mime};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+#[derive(Debug)]
+struct Glob {
+    weight: u32,
+    mime: Mime,
+    pattern: &'static str,
+    case_sensitive: bool,
+}
+
+/// Lowercased copies of patterns are leaked once, like the database itself
+static GLOBS: Lazy<Vec<Glob>> = Lazy::new(|| {
+    crate::fdo_magic::builtin::init::globs2()
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            // weight:mimetype:glob[:flags]
+            let mut parts = line.splitn(4, ':');
+            let weight = parts.next()?.parse().ok()?;
+            let mime = parts.next()?;
+            let pattern = parts.next()?;
+            let case_sensitive = parts.next().is_some_and(|flags| flags.contains("cs"));
+            let pattern = match case_sensitive {
+                true => pattern,
+                false => Box::leak(pattern.to_lowercase().into_boxed_str()),
+            };
+            Some(Glob {
+                weight,
+                mime,
+                pattern,
+                case_sensitive,
+            })
+        })
+        .collect()
+});
+
+/// Parents of every type in the database, including ones with no magic
+static PARENTS: Lazy<HashMap<Mime, Vec<Mime>>> = Lazy::new(|| {
+    let mut out = HashMap::<Mime, Vec<Mime>>::default();
+    for (parent, child) in crate::fdo_magic::builtin::init::get_subclasses() {
+        out.entry(child).or_default().push(parent);
+    }
+    out
+});
+
+/// Loads the patterns and subclass list now rather than on first use
+pub fn warm_up() {
+    Lazy::force(&GLOBS);
+    Lazy::force(&PARENTS);
+}
+
+/// Checks if a type matched by name is a subclass of a type matched by magic.
+///
+/// Many types only have globs, so they aren't in the type graph. Those are
+/// followed up through the database's subclass list until the graph is reached.
+pub fn refines(detector: &Detector, candidate: Mime, magic: Mime) -> bool {
+    refines_depth(detector, candidate, magic, 0)
+}
+
+fn refines_depth(detector: &Detector, candidate: Mime, magic: Mime, depth: usize) -> bool {
+    // Guard against cycles in a broken database
+    if depth > 16 {
+        return false;
+    }
+    if detector.types.hash.contains_key(candidate) {
+        return detector.is_subclass(candidate, magic);
+    }
+
+    match PARENTS.get(candidate) {
+        Some(parents) => parents
+            .iter()
+            .any(|&parent| refines_depth(detector, parent, magic, depth + 1)),
+        // Same implicit parents the graph uses for types with no listed parent
+        None if candidate.starts_with("text/") => detector.is_subclass("text/plain", magic),
+        None => detector.is_subclass("application/octet-stream", magic),
+    }
+}
+
+/// Gets every type whose patterns match the file name, best match first.
+///
+/// Higher weights win, then longer patterns, as in the shared MIME info spec.
+pub fn matches(filename: &str) -> Vec<Mime> {
+    let lowercase = filename.to_lowercase();
+
+    let mut found: Vec<&Glob> = GLOBS
+        .iter()
+        .filter(|glob| {
+            let name = match glob.case_sensitive {
+                true => filename,
+                false => &lowercase,
+            };
+            glob_match(glob.pattern.as_bytes(), name.as_bytes())
+        })
+        .collect();
+    found.sort_by(|a, b| {
+        b.weight
+            .cmp(&a.weight)
+            .then(b.pattern.len().cmp(&a.pattern.len()))
+    });
+
+    let mut out = Vec::<Mime>::with_capacity(found.len());
+    for glob in found {
+        if !out.contains(&glob.mime) {
+            out.push(glob.mime);
+        }
+    }
+    out
+}
+
+/// Gets every pattern for a type, highest weight first
+pub fn patterns(mimetype: &str) -> Vec<&'static str> {
+    let mut found: Vec<&Glob> = GLOBS.iter().filter(|glob| glob.mime == mimetype).collect();
+    found.sort_by_key(|glob| std::cmp::Reverse(glob.weight));
+
+    let mut out = Vec::<&'static str>::with_capacity(found.len());
+    for glob in found {
+        if !out.contains(&glob.pattern) {
+            out.push(glob.pattern);
+        }
+    }
+    out
+}
+
+/// Matches shell-style patterns with `*`, `?`, and `[...]`
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|i| glob_match(rest, &name[i..])),
+        Some((b'?', rest)) => !name.is_empty() && glob_match(rest, &name[1..]),
+        Some((b'[', rest)) => {
+            let Some(end) = rest.iter().position(|&c| c == b']') else {
+                // Unterminated class, so it's just a literal bracket
+                return name.first() == Some(&b'[') && glob_match(rest, &name[1..]);
+            };
+            let Some((&c, name_rest)) = name.split_first() else {
+                return false;
+            };
+            class_match(&rest[..end], c) && glob_match(&rest[end + 1..], name_rest)
+        }
+        Some((&p, rest)) => name.first() == Some(&p) && glob_match(rest, &name[1..]),
+    }
+}
+
+/// Matches a single byte against the inside of a `[...]` class
+fn class_match(class: &[u8], c: u8) -> bool {
+    let (negate, class) = match class.split_first() {
+        Some((b'!', rest)) => (true, rest),
+        _ => (false, class),
+    };
+
+    let mut found = false;
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == b'-' {
+            found |= (class[i]..=class[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            found |= class[i] == c;
+            i += 1;
+        }
+    }
+    found != negate
+}
@@ -0,0 +1,32 @@
+//! The gzip (RFC 1952) header, which can be read without decompressing
+//! anything
+
+const FLAG_EXTRA: u8 = 0x04;
+const FLAG_NAME: u8 = 0x08;
+
+/// Returns the header flags, and where the optional fields after the extra
+/// field start
+fn fields(bytes: &[u8]) -> Option<(u8, usize)> {
+    let header = bytes.get(..10)?;
+    if header[..3] != [0x1f, 0x8b, 8] {
+        return None;
+    }
+    let flags = header[3];
+
+    let mut pos = 10;
+    if flags & FLAG_EXTRA != 0 {
+        let len = bytes.get(pos..pos + 2)?;
+        pos += 2 + u16::from_le_bytes([len[0], len[1]]) as usize;
+    }
+    Some((flags, pos))
+}
+
+/// Returns the original file name, without its NUL terminator
+pub(crate) fn original_name(bytes: &[u8]) -> Option<&[u8]> {
+    let (flags, pos) = fields(bytes)?;
+    if flags & FLAG_NAME == 0 {
+        return None;
+    }
+    let name = bytes.get(pos..)?;
+    Some(&name[..memchr::memchr(0, name)?])
+}
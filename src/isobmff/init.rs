@@ -0,0 +1,22 @@
+use crate::Mime;
+use std::collections::HashMap;
+
+pub fn get_supported() -> Vec<Mime> {
+    std::iter::once(super::ISOBMFF)
+        .chain(super::FORMATS.iter().map(|f| f.mime))
+        .collect()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(Mime, Mime)> {
+    super::FORMATS.iter().map(|f| (f.parent, f.mime)).collect()
+}
+
+pub fn get_aliaslist() -> HashMap<Mime, Mime> {
+    HashMap::from([
+        ("image/heic", "image/heif"),
+        ("image/heic-sequence", "image/heif"),
+        ("image/heif-sequence", "image/heif"),
+        ("image/avif-sequence", "image/avif"),
+    ])
+}
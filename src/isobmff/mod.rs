@@ -0,0 +1,160 @@
+//! Tells apart the formats built on the ISO base media file format, such as
+//! MP4, QuickTime and HEIF, by the brands in their `ftyp` box
+use crate::Mime;
+
+pub mod check;
+pub mod init;
+
+/// Parent of every format here, for files whose brands aren't known
+const ISOBMFF: Mime = "application/x-isobmff";
+
+/// Longest `ftyp` box read. Real ones list a handful of brands.
+const MAX_FTYP_LEN: usize = 1024;
+
+/// Boxes that old QuickTime files start with, from before `ftyp` existed
+const QUICKTIME_BOXES: &[&[u8; 4]] = &[b"moov", b"mdat", b"wide", b"free", b"skip", b"pnot"];
+
+/// Brands that only say which version of a base format is used. Other brands
+/// are more specific, wherever they are in the box.
+const GENERIC_BRANDS: &[&[u8]] = &[b"iso", b"mif1", b"msf1", b"miaf"];
+
+/// A format and the brand prefixes that identify it
+struct Format {
+    mime: Mime,
+    parent: Mime,
+    brands: &'static [&'static [u8]],
+}
+
+static FORMATS: &[Format] = &[
+    Format {
+        mime: "video/mp4",
+        parent: ISOBMFF,
+        brands: &[
+            b"iso", b"mp41", b"mp42", b"mp71", b"avc1", b"dash", b"MSNV", b"M4V", b"f4v ", b"mmp4",
+        ],
+    },
+    Format {
+        mime: "video/3gpp",
+        parent: "video/mp4",
+        brands: &[b"3gp", b"3ge", b"3gg", b"3gr", b"3gs"],
+    },
+    Format {
+        mime: "video/3gpp2",
+        parent: ISOBMFF,
+        brands: &[b"3g2"],
+    },
+    Format {
+        mime: "video/quicktime",
+        parent: ISOBMFF,
+        brands: &[b"qt  "],
+    },
+    Format {
+        mime: "audio/mp4",
+        parent: ISOBMFF,
+        brands: &[b"M4A ", b"M4P ", b"f4a "],
+    },
+    Format {
+        mime: "audio/x-m4b",
+        parent: "audio/mp4",
+        brands: &[b"M4B ", b"f4b "],
+    },
+    Format {
+        mime: "audio/x-m4r",
+        parent: "audio/mp4",
+        brands: &[b"M4R "],
+    },
+    Format {
+        mime: "audio/vnd.audible.aax",
+        parent: ISOBMFF,
+        brands: &[b"aax "],
+    },
+    Format {
+        mime: "image/avif",
+        parent: ISOBMFF,
+        // Still images, sequences, intra-only sequences, and the profiles
+        brands: &[b"avif", b"avis", b"avio", b"MA1B", b"MA1A"],
+    },
+    // HEIC, HEIF with HEVC coded images, has no type of its own: image/heic
+    // is an alias
+    Format {
+        mime: "image/heif",
+        parent: ISOBMFF,
+        brands: &[
+            b"heic", b"heix", b"heim", b"heis", b"hevc", b"hevx", b"hevm", b"hevs", b"mif1",
+            b"msf1",
+        ],
+    },
+];
+
+fn format_of_brand(brand: &[u8]) -> Option<&'static Format> {
+    FORMATS
+        .iter()
+        .find(|f| f.brands.iter().any(|prefix| brand.starts_with(prefix)))
+}
+
+/// Returns the brands in the `ftyp` box at the start of `bytes`, major brand
+/// first, or `None` if it doesn't start with one. A box cut off by the end
+/// of `bytes` gives the brands it has so far.
+fn ftyp_brands(bytes: &[u8]) -> Option<Vec<&[u8]>> {
+    let size = u32::from_be_bytes(bytes.get(..4)?.try_into().ok()?) as usize;
+    if bytes.get(4..8)? != b"ftyp" {
+        return None;
+    }
+    // A size of 1 means a 64-bit size follows the type, and 0 means the box
+    // runs to the end of the file
+    let (header_len, size) = match size {
+        0 => (8, bytes.len()),
+        1 => {
+            let size = u64::from_be_bytes(bytes.get(8..16)?.try_into().ok()?);
+            (16, usize::try_from(size).ok()?)
+        }
+        size => (8, size),
+    };
+    // Major brand and minor version
+    if size < header_len + 8 || size > MAX_FTYP_LEN {
+        return None;
+    }
+
+    let data = bytes.get(header_len..size.min(bytes.len()))?;
+    let major = data.get(..4)?;
+    if !major.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        return None;
+    }
+    let compatible = data.get(8..).unwrap_or_default().chunks_exact(4);
+    Some(std::iter::once(major).chain(compatible).collect())
+}
+
+/// Works out which format `bytes` is, or `None` if it isn't ISO base media
+fn classify(bytes: &[u8]) -> Option<Mime> {
+    let Some(brands) = ftyp_brands(bytes) else {
+        let is_quicktime = bytes
+            .get(4..8)
+            .is_some_and(|kind| QUICKTIME_BOXES.iter().any(|b| b[..] == *kind));
+        return is_quicktime.then_some("video/quicktime");
+    };
+
+    let is_generic = |brand: &&[u8]| GENERIC_BRANDS.iter().any(|g| brand.starts_with(g));
+    let specific = brands.iter().filter(|b| !is_generic(b));
+    let generic = brands.iter().filter(|b| is_generic(b));
+    let format = specific
+        .chain(generic)
+        .find_map(|brand| format_of_brand(brand));
+    Some(format.map_or(ISOBMFF, |f| f.mime))
+}
+
+fn is_supported(mimetype: &str) -> bool {
+    mimetype == ISOBMFF || FORMATS.iter().any(|f| f.mime == mimetype)
+}
+
+/// Checks whether `bytes` is `mimetype` or one of its subclasses here
+fn matches(bytes: &[u8], mimetype: &str) -> bool {
+    let Some(mime) = classify(bytes) else {
+        return false;
+    };
+    if mimetype == ISOBMFF || mime == mimetype {
+        return true;
+    }
+    FORMATS
+        .iter()
+        .any(|f| f.mime == mime && f.parent == mimetype)
+}
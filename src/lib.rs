@@ -39,6 +39,25 @@
 //! **As the magic database files themselves are licensed under the GPL, you must
 //! make sure your project uses a compatible license if you enable this behaviour.**
 //!
+//! ## Compressed tarballs
+//!
+//! With the `decompress` feature, the start of gzip, bzip2, xz and zstd
+//! streams is decompressed to tell compressed tarballs (like
+//! `application/x-compressed-tar`) from other compressed files. The
+//! decompressed bytes are only checked for a tar header; other formats inside
+//! a compressed stream aren't detected. At most 512 bytes are decompressed
+//! from any one stream. bzip2 and zstd can't produce any output until they've
+//! read a whole block, so up to 32 KiB of those files is read, and their
+//! tarballs are only recognized when the first block fits in that.
+//!
+//! ## CSV and TSV
+//!
+//! With the `csv` feature, which is on by default, text whose first few lines
+//! all have the same number of commas or tabs is detected as `text/csv` or
+//! `text/tab-separated-values`. This is a heuristic, and is tuned to miss some
+//! files rather than mistake prose or code for them. Turn off default features
+//! to get `text/plain` for these instead.
+//!
 //! # Example
 //! ```rust
 //! // Load a GIF file
@@ -53,21 +72,41 @@
 //! assert_eq!(result, true);
 //! ```
 
-use std::collections::HashMap;
-use std::collections::HashSet;
 use once_cell::sync::Lazy;
-use petgraph::prelude::*;
 use std::fs::File;
 use std::io::prelude::*;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
 
 mod basetype;
+mod cfb;
+mod checker;
+#[cfg(feature = "decompress")]
+mod compressed;
+mod detector;
+mod ebml;
+mod error;
+mod explain;
 mod fdo_magic;
+mod glob;
+mod gzip;
+mod isobmff;
+mod mime_type;
+mod ogg;
+mod signature;
+mod text;
+mod web;
+mod zip;
+
+pub use checker::Checker;
+pub use detector::{DatabaseSource, DatabaseStats, Detector};
+pub use error::{DetectError, Error};
+pub use explain::{DetectionExplanation, DetectionStats, MatchExplanation, RuleMatch};
+pub use mime_type::MimeType;
 
 type Mime = &'static str;
 
-/// Check these types first
-/// TODO: Poll these from the checkers? Feels a bit arbitrary
+/// Check these types first, unless a `Detector` is given its own priority list
 const TYPEORDER: [&str; 6] = [
     "image/png",
     "image/jpeg",
@@ -77,187 +116,333 @@ const TYPEORDER: [&str; 6] = [
     "application/pdf",
 ];
 
-trait Checker: Send + Sync {
-    fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool;
-    fn match_file(&self, file: &File, mimetype: &str) -> bool;
-    fn get_supported(&self) -> Vec<Mime>;
-    fn get_subclasses(&self) -> Vec<(Mime, Mime)>;
-    fn get_aliaslist(&self) -> HashMap<Mime, Mime>;
+/// Number of bytes read from streams before running the byte checkers
+const BUFFER_LEN: usize = 2048;
+
+/// Most bytes ever read from a file by default, however deep the magic rules look
+const MAX_BUFFER_LEN: usize = 32 * 1024;
+
+/// The checkers used by the default `Detector`, reading magic from `magic`.
+///
+/// Later checkers take over types also supported by earlier ones, so the
+/// database's magic is used instead of the fallback text heuristics where it
+/// has any.
+fn default_checkers(magic: fdo_magic::builtin::check::FdoMagic) -> Vec<Box<dyn Checker>> {
+    let mut checkers: Vec<Box<dyn Checker>> = vec![
+        Box::new(text::check::Text::fallback()),
+        Box::new(magic),
+        Box::new(text::check::Text::preferred()),
+        Box::new(signature::check::Signature),
+        Box::new(zip::check::Zip),
+        Box::new(isobmff::check::IsoBmff),
+        Box::new(ebml::check::Ebml),
+        Box::new(ogg::check::Ogg),
+        Box::new(cfb::check::Cfb),
+    ];
+    #[cfg(feature = "decompress")]
+    checkers.push(Box::new(compressed::check::Compressed));
+    checkers.push(Box::new(basetype::check::BaseType));
+    checkers
 }
 
-static CHECKERS: &[&'static dyn Checker] = &[
-    &fdo_magic::builtin::check::FdoMagic,
-    &basetype::check::BaseType,
-];
+/// The Detector autogenerated at library init, and used by the free functions.
+///
+/// It's only ever replaced whole, so a call that's already running carries on
+/// with the one it started with.
+static DETECTOR: Lazy<RwLock<Arc<Detector>>> =
+    Lazy::new(|| RwLock::new(Arc::new(Detector::new())));
 
-// Mappings between modules and supported mimes
+fn detector() -> Arc<Detector> {
+    // Nothing can panic while the lock is held
+    DETECTOR
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
 
-static CHECKER_SUPPORT: Lazy<HashMap<Mime, &'static dyn Checker>> = Lazy::new(|| {
-    let mut out = HashMap::<Mime, &'static dyn Checker>::default();
-    for &c in CHECKERS {
-        for m in c.get_supported() {
-            out.insert(m, c);
-        }
-    }
-    out
-});
+/// Replaces the `Detector` used by the free functions in this crate.
+///
+/// Calls already in progress on other threads finish with the old one, and
+/// every call after this returns sees the new one. This lets tests use a
+/// database of their own through the free functions, and lets programs that
+/// built their own [`Detector`] share it with libraries that call them.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+/// use tree_magic_mini::Detector;
+///
+/// let custom = Detector::from_database_dir(Path::new("tests/database/custom")).unwrap();
+/// tree_magic_mini::set_detector(custom);
+/// assert_eq!(tree_magic_mini::from_u8(b"TREEMAGIC data"), "application/x-tree-magic");
+/// ```
+pub fn set_detector(detector: Detector) {
+    *DETECTOR.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(detector);
+}
 
-static ALIASES: Lazy<HashMap<Mime, Mime>> = Lazy::new(|| {
-    let mut out = HashMap::<Mime, Mime>::default();
-    for &c in CHECKERS {
-        out.extend(c.get_aliaslist());
+/// Reads the default database again, and uses it for the free functions from
+/// then on.
+///
+/// Long-running programs can call this after the database in the standard
+/// locations has been updated, without restarting. Like [`set_detector`],
+/// calls already in progress finish with the old database. File name patterns
+/// and type descriptions are still the ones read first.
+///
+/// With the `with-gpl-data` feature, the embedded database is used, just as
+/// it is at startup, so this only undoes any [`set_detector`].
+///
+/// Each database read stays in memory for the rest of the program, since the
+/// types in it are `&'static str`, so avoid reloading it over and over.
+///
+/// # Errors
+///
+/// Returns an error if no `magic` file is installed, or if one can't be read or
+/// parsed. The database in use is kept if so.
+pub fn reload() -> Result<(), Error> {
+    #[cfg(feature = "with-gpl-data")]
+    let detector = Detector::new();
+    #[cfg(not(feature = "with-gpl-data"))]
+    let detector = Detector::with_checker_list(default_checkers(
+        fdo_magic::builtin::dir::from_system_dirs()?,
+    ));
+    set_detector(detector);
+    Ok(())
+}
+
+/// Loads the MIME database now, rather than on the first call that needs it.
+///
+/// The database is parsed and the type graph built the first time a type is
+/// detected or looked up, which can take a few milliseconds. Calling this at
+/// startup moves that cost there, so it doesn't land on whatever happens to
+/// be detected first. File name patterns are loaded too. Type descriptions
+/// are still read when they're first asked for.
+///
+/// This is optional, since everything loads on demand anyway. It's cheap
+/// once the database is loaded, and is safe to call any number of times from
+/// any thread.
+///
+/// # Examples
+/// ```rust
+/// tree_magic_mini::warm_up();
+/// assert_eq!(tree_magic_mini::from_u8(b"GIF89a"), "image/gif");
+/// ```
+pub fn warm_up() {
+    Lazy::force(&DETECTOR);
+    glob::warm_up();
+}
+
+/// Loads the MIME database now, and reports anything that went wrong.
+///
+/// Like [`warm_up`], this does the loading that would otherwise happen on
+/// first use. Detection never fails outright when the database is missing or
+/// broken; it just knows fewer types. This says why instead, so a program can
+/// refuse to start with a clear message. The result is the same every time,
+/// since the database is only loaded once.
+///
+/// Returns [`Error::NotFound`] with the places searched if no database was
+/// found, [`Error::Io`] if a database file couldn't be read, and
+/// [`Error::Parse`] if one is corrupt.
+///
+/// # Examples
+/// ```rust
+/// if let Err(e) = tree_magic_mini::init() {
+///     eprintln!("MIME detection will be limited: {e}");
+/// }
+/// ```
+pub fn init() -> Result<(), Error> {
+    warm_up();
+    match fdo_magic::builtin::load_error() {
+        Some(e) => Err(e.duplicate()),
+        None => Ok(()),
     }
-    out
-});
+}
 
-/// Information about currently loaded MIME types
+/// Resolves an alias to the canonical MIME type it stands for.
 ///
-/// The `graph` contains subclass relations between all given mimes.
-/// (EX: `application/json` -> `text/plain` -> `application/octet-stream`)
-/// This is a `petgraph` DiGraph, so you can walk the tree if needed.
+/// MIME types that are not aliases, including unknown types, are returned
+/// unchanged.
+///
+/// # Examples
+/// ```rust
+/// let result = tree_magic_mini::canonical_mime("application/x-zip-compressed");
+/// assert_eq!(result, "application/zip");
 ///
-/// The `hash` is a mapping between MIME types and nodes on the graph.
-/// The root of the graph is "all/all", so start traversing there unless
-/// you need to jump to a particular node.
-struct TypeStruct {
-    graph: DiGraph<Mime, u32>,
+/// let result = tree_magic_mini::canonical_mime("application/zip");
+/// assert_eq!(result, "application/zip");
+/// ```
+pub fn canonical_mime(mimetype: &str) -> &str {
+    detector().canonical_mime(mimetype)
 }
 
-/// The TypeStruct autogenerated at library init, and used by the library.
-static TYPE: Lazy<TypeStruct> = Lazy::new(|| {
-    let mut graph = DiGraph::<Mime, u32>::new();
-    let mut added_mimes = HashMap::<Mime, NodeIndex>::default();
+/// Checks if the given MIME type is an alias of another type.
+///
+/// # Examples
+/// ```rust
+/// assert!(tree_magic_mini::is_alias("application/x-zip-compressed"));
+/// assert!(!tree_magic_mini::is_alias("application/zip"));
+/// ```
+pub fn is_alias(mimetype: &str) -> bool {
+    detector().is_alias(mimetype)
+}
 
-    // Get list of MIME types and MIME relations
-    let mut mimelist = Vec::<Mime>::new();
-    let mut edgelist_raw = Vec::<(Mime, Mime)>::new();
-    for &c in CHECKERS {
-        mimelist.extend(c.get_supported());
-        edgelist_raw.extend(c.get_subclasses());
-    }
-    mimelist.sort_unstable();
-    mimelist.dedup();
-    let mimelist = mimelist;
-
-    // Create all nodes
-    for mimetype in mimelist.iter() {
-        let node = graph.add_node(mimetype);
-        added_mimes.insert(mimetype, node);
-    }
+/// The name of the checker that detects the given MIME type, after resolving
+/// aliases, or `None` if no checker does.
+///
+/// The names are those from [`Checker::name`], like `"fdo_magic"` for the
+/// database's magic or `"basetype"` for `text/plain`. Types in the type graph
+/// only as parents of other types have no checker.
+///
+/// # Examples
+/// ```rust
+/// assert_eq!(tree_magic_mini::checker_for("text/plain"), Some("basetype"));
+/// assert_eq!(tree_magic_mini::checker_for("application/x-zip-compressed"), Some("signature"));
+/// assert_eq!(tree_magic_mini::checker_for("image/x-does-not-exist"), None);
+/// ```
+pub fn checker_for(mimetype: &str) -> Option<&'static str> {
+    detector().checker_for(mimetype)
+}
 
-    let mut edge_list = HashSet::<(NodeIndex, NodeIndex)>::with_capacity_and_hasher(
-        edgelist_raw.len(),
-        Default::default(),
-    );
-    for (child_raw, parent_raw) in &edgelist_raw {
-        let Some(parent) = added_mimes.get(parent_raw) else {
-            continue;
-        };
-        let Some(child) = added_mimes.get(child_raw) else {
-            continue;
-        };
-        edge_list.insert((*child, *parent));
-    }
+/// Checks if the given MIME type is known to the loaded checkers, either
+/// itself or as an alias.
+///
+/// [`match_u8`] and friends return false both for types that don't match and
+/// for types that can never match, so this tells the two apart.
+///
+/// # Examples
+/// ```rust
+/// assert!(tree_magic_mini::is_known("image/gif"));
+/// assert!(tree_magic_mini::is_known("application/x-zip-compressed"));
+/// assert!(!tree_magic_mini::is_known("image/x-does-not-exist"));
+/// ```
+pub fn is_known(mimetype: &str) -> bool {
+    detector().is_known(mimetype)
+}
 
-    graph.extend_with_edges(&edge_list);
-
-    //Add to applicaton/octet-stream, all/all, or text/plain, depending on top-level
-    //(We'll just do it here because having the graph makes it really nice)
-    let node_text = *added_mimes
-        .entry("text/plain")
-        .or_insert_with(|| graph.add_node("text/plain"));
-
-    let node_octet = *added_mimes
-        .entry("application/octet-stream")
-        .or_insert_with(|| graph.add_node("application/octet-stream"));
-
-    let node_allall = *added_mimes
-        .entry("all/all")
-        .or_insert_with(|| graph.add_node("all/all"));
-
-    let node_allfiles = *added_mimes
-        .entry("all/allfiles")
-        .or_insert_with(|| graph.add_node("all/allfiles"));
-
-    let mut edge_list_2 = HashSet::<(NodeIndex, NodeIndex)>::default();
-    for mimenode in graph.externals(Incoming) {
-        let mimetype = &graph[mimenode];
-        let toplevel = mimetype.split('/').next().unwrap_or("");
-
-        if mimenode == node_text
-            || mimenode == node_octet
-            || mimenode == node_allfiles
-            || mimenode == node_allall
-        {
-            continue;
-        }
-
-        if toplevel == "text" {
-            edge_list_2.insert((node_text, mimenode));
-        } else if toplevel == "inode" {
-            edge_list_2.insert((node_allall, mimenode));
-        } else {
-            edge_list_2.insert((node_octet, mimenode));
-        }
-    }
-    // Don't add duplicate entries
-    graph.extend_with_edges(edge_list_2.difference(&edge_list));
-
-    TypeStruct { graph }
-});
-
-/// Just the part of from_*_node that walks the graph
-fn typegraph_walker<T, F>(parentnode: NodeIndex, input: &T, matchfn: F) -> Option<Mime>
-where
-    T: ?Sized,
-    F: Fn(&str, &T) -> bool,
-{
-    // Pull most common types towards top
-    let mut children: Vec<NodeIndex> = TYPE
-        .graph
-        .neighbors_directed(parentnode, Outgoing)
-        .collect();
-
-    for i in 0..children.len() {
-        let x = children[i];
-        if TYPEORDER.contains(&TYPE.graph[x]) {
-            children.remove(i);
-            children.insert(0, x);
-        }
-    }
+/// Lists every MIME type known to the loaded checkers, sorted.
+///
+/// This reflects whichever database was loaded, embedded or at runtime.
+/// Aliases are not included; see [`aliases_of`] for those.
+///
+/// # Examples
+/// ```rust
+/// let types = tree_magic_mini::supported_types();
+/// assert!(types.contains(&"image/gif"));
+/// assert!(types.contains(&"text/plain"));
+/// ```
+pub fn supported_types() -> Vec<Mime> {
+    detector().supported_types()
+}
 
-    // Walk graph
-    for childnode in children {
-        let mimetype = &TYPE.graph[childnode];
-
-        let result = matchfn(mimetype, input);
-        match result {
-            true => match typegraph_walker(childnode, input, matchfn) {
-                Some(foundtype) => return Some(foundtype),
-                None => return Some(mimetype),
-            },
-            false => continue,
-        }
-    }
+/// Counts the types, subclass links and aliases loaded, and says where the
+/// magic database came from.
+///
+/// This is a cheap look at what's already built, for health checks and
+/// for making sure the expected database was picked up. The free functions
+/// read the embedded database with the `with-gpl-data` feature, and the
+/// installed one otherwise, unless [`set_detector`] was given another.
+///
+/// # Examples
+/// ```rust
+/// let stats = tree_magic_mini::database_stats();
+/// assert!(stats.types > 100);
+/// assert!(stats.subclasses >= stats.types - 1);
+/// ```
+pub fn database_stats() -> DatabaseStats {
+    detector().database_stats()
+}
 
-    None
+/// Checks if one MIME type is a subclass of another.
+///
+/// Both types are resolved through aliases first. A type is considered a
+/// subclass of itself. Returns false if either type is unknown.
+///
+/// # Examples
+/// ```rust
+/// assert!(tree_magic_mini::is_subclass("image/gif", "application/octet-stream"));
+/// assert!(!tree_magic_mini::is_subclass("image/gif", "text/plain"));
+/// ```
+pub fn is_subclass(child: &str, parent: &str) -> bool {
+    detector().is_subclass(child, parent)
 }
 
-/// Transforms an alias into it's real type
-fn get_alias(mimetype: &str) -> &str {
-    match ALIASES.get(mimetype) {
-        Some(x) => x,
-        None => mimetype,
-    }
+/// Lists every type the given MIME type is a subclass of, sorted.
+///
+/// This covers the whole chain up to `all/all`, but not the type itself.
+/// The type is resolved through aliases first. Returns an empty list if the
+/// type is unknown.
+///
+/// # Examples
+/// ```rust
+/// let result = tree_magic_mini::ancestors("text/plain");
+/// assert_eq!(result, ["all/all", "all/allfiles", "application/octet-stream"]);
+/// ```
+pub fn ancestors(mimetype: &str) -> Vec<Mime> {
+    detector().ancestors(mimetype)
 }
 
-/// Internal function. Checks if an alias exists, and if it does,
-/// then runs `match_bytes`.
-fn match_u8_noalias(mimetype: &str, bytes: &[u8]) -> bool {
-    match CHECKER_SUPPORT.get(mimetype) {
-        None => false,
-        Some(y) => y.match_bytes(bytes, mimetype),
-    }
+/// Lists every type that is a subclass of the given MIME type, sorted.
+///
+/// This covers all more specific types, but not the type itself.
+/// The type is resolved through aliases first. Returns an empty list if the
+/// type is unknown.
+///
+/// # Examples
+/// ```rust
+/// let result = tree_magic_mini::descendants("all/allfiles");
+/// assert!(result.contains(&"application/octet-stream"));
+/// assert!(result.contains(&"image/gif"));
+/// ```
+pub fn descendants(mimetype: &str) -> Vec<Mime> {
+    detector().descendants(mimetype)
+}
+
+/// Renders the subclass tree in GraphViz's DOT language.
+///
+/// Each type is a node, with an edge from each type to its subclasses.
+/// Aliases get dashed edges to the types they stand for. Everything is sorted,
+/// so the output only changes when the loaded types do. Pipe it to
+/// `dot -Tsvg` to see why a file walks where it does.
+///
+/// # Examples
+/// ```rust
+/// let dot = tree_magic_mini::subclass_tree_dot();
+/// assert!(dot.starts_with("digraph"));
+/// assert!(dot.contains("\"all/allfiles\" -> \"application/octet-stream\";"));
+/// ```
+pub fn subclass_tree_dot() -> String {
+    detector().subclass_tree_dot()
+}
+
+/// Renders the subclass tree as an indented outline, for a quick look without
+/// GraphViz.
+///
+/// Each type is on a line of its own, starting from `all/all`, with its
+/// subclasses beneath it indented by two more spaces, in the order they're
+/// checked. A type with several parents is only expanded the first time it
+/// comes up, and is marked `(see above)` after that.
+///
+/// # Examples
+/// ```rust
+/// let tree = tree_magic_mini::subclass_tree_text();
+/// assert!(tree.starts_with("all/all\n"));
+/// assert!(tree.contains("\n    application/octet-stream\n"));
+/// ```
+pub fn subclass_tree_text() -> String {
+    detector().subclass_tree_text()
+}
+
+/// Lists every alias of the given canonical MIME type, sorted.
+///
+/// Returns an empty list if the type is unknown, has no aliases, or is itself
+/// an alias.
+///
+/// # Examples
+/// ```rust
+/// let result = tree_magic_mini::aliases_of("application/zip");
+/// assert!(result.contains(&"application/x-zip-compressed"));
+/// ```
+pub fn aliases_of(canonical: &str) -> Vec<Mime> {
+    detector().aliases_of(canonical)
 }
 
 /// Checks if the given bytestream matches the given MIME type.
@@ -276,27 +461,32 @@ fn match_u8_noalias(mimetype: &str, bytes: &[u8]) -> bool {
 /// assert_eq!(result, true);
 /// ```
 pub fn match_u8(mimetype: &str, bytes: &[u8]) -> bool {
-    match_u8_noalias(get_alias(mimetype), bytes)
+    detector().match_u8(mimetype, bytes)
 }
 
-/// Gets the type of a file from a raw bytestream, starting at a certain node
-/// in the type graph.
+/// Checks a bytestream against several MIME types, and returns the first
+/// that it matches.
 ///
-/// Returns MIME as string wrapped in Some if a type matches, or
-/// None if no match is found under the given node.
-/// Retreive the node from the `TYPE.hash` HashMap, using the MIME as the key.
+/// Each candidate is resolved through aliases, so the canonical name of the
+/// matching type is returned. Candidates are tried in order, and the rest are
+/// skipped once one matches. Unknown types never match.
 ///
-/// # Panics
-/// Will panic if the given node is not found in the graph.
-/// As the graph is immutable, this should not happen if the node index comes from
-/// TYPE.hash.
-fn from_u8_node(parentnode: NodeIndex, bytes: &[u8]) -> Option<Mime> {
-    typegraph_walker(parentnode, bytes, match_u8_noalias)
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// let accepted = ["image/png", "image/jpeg", "image/gif"];
+/// assert_eq!(tree_magic_mini::match_any(&accepted, input), Some("image/gif"));
+/// assert_eq!(tree_magic_mini::match_any(&["application/pdf"], input), None);
+/// ```
+pub fn match_any(candidates: &[&str], bytes: &[u8]) -> Option<Mime> {
+    detector().match_any(candidates, bytes)
 }
 
 /// Gets the type of a file from a byte stream.
 ///
-/// Returns MIME as string.
+/// Returns MIME as string. Empty input is `application/x-empty`.
 ///
 /// # Examples
 /// ```rust
@@ -308,11 +498,269 @@ fn from_u8_node(parentnode: NodeIndex, bytes: &[u8]) -> Option<Mime> {
 /// assert_eq!(result, "image/gif");
 /// ```
 pub fn from_u8(bytes: &[u8]) -> Mime {
-    let node = match TYPE.graph.externals(Incoming).next() {
-        Some(foundnode) => foundnode,
-        None => panic!("No filetype definitions are loaded."),
-    };
-    from_u8_node(node, bytes).unwrap()
+    detector().from_u8(bytes)
+}
+
+/// Gets the type of a file from a byte stream, without panicking.
+///
+/// Returns `None` only if no filetype definitions are loaded. If definitions
+/// are loaded but nothing more specific matches, the root of the type graph
+/// (usually `all/all`) is returned instead.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// // Find the MIME type of the GIF
+/// let result = tree_magic_mini::try_from_u8(input);
+/// assert_eq!(result, Some("image/gif"));
+/// ```
+pub fn try_from_u8(bytes: &[u8]) -> Option<Mime> {
+    detector().try_from_u8(bytes)
+}
+
+/// Gets the type of a file from a byte stream, as a [`MimeType`].
+///
+/// This is [`from_u8`] with the result wrapped up.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// let result = tree_magic_mini::from_u8_typed(input);
+/// assert!(result.is_image());
+/// assert_eq!(result.subtype(), "gif");
+/// ```
+pub fn from_u8_typed(bytes: &[u8]) -> MimeType {
+    from_u8(bytes).into()
+}
+
+/// Gets the type of a file from a byte stream, only considering the subclasses
+/// of a type it's already known to be.
+///
+/// Detection starts at `start_mime` in the type graph instead of its root, so
+/// types in other branches aren't checked at all. That's quicker, and nothing
+/// outside the branch can be mistaken for a match. The bytes aren't checked
+/// against `start_mime` itself: if none of its subclasses match, it's
+/// returned as it is. Use [`match_u8`] first if that needs checking too.
+///
+/// Returns None if `start_mime` isn't a known type.
+///
+/// # Examples
+/// ```rust
+/// let docx = include_bytes!(
+///     "../tests/application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+/// );
+/// let result = tree_magic_mini::from_u8_under("application/zip", docx);
+/// assert_eq!(
+///     result,
+///     Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document")
+/// );
+///
+/// let result = tree_magic_mini::from_u8_under("application/x-unknown", docx);
+/// assert_eq!(result, None);
+/// ```
+pub fn from_u8_under(start_mime: &str, bytes: &[u8]) -> Option<Mime> {
+    detector().from_u8_under(start_mime, bytes)
+}
+
+/// Gets every MIME type a byte stream matches.
+///
+/// Where `from_u8` stops at the first match on each level of the type graph,
+/// this checks every branch and returns each of the most specific types that
+/// matched. This is useful for polyglot or ambiguous files.
+///
+/// Results are ordered deepest in the type graph first, then alphabetically.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// // Find every MIME type of the GIF
+/// let result = tree_magic_mini::from_u8_all(input);
+/// assert!(result.contains(&"image/gif"));
+/// ```
+pub fn from_u8_all(bytes: &[u8]) -> Vec<Mime> {
+    detector().from_u8_all(bytes)
+}
+
+/// Gets up to `limit` likely types of a byte stream, with a score for each.
+///
+/// The candidates are the same as for [`from_u8_all`]: the most specific type
+/// matched on each branch of the type graph. A type `n` subclass steps below
+/// `all/all` scores `n / (n + 1)`, so deeper, more specific types score
+/// higher, and every score is between 0 and 1. The results are best first.
+/// Equally deep types are ordered by name, except that [`from_u8`]'s answer
+/// goes first, so the first result is always [`from_u8_best`]'s answer.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// let guesses = tree_magic_mini::sniff(input, 3);
+/// assert_eq!(guesses[0].0, "image/gif");
+/// assert!(guesses[0].1 > 0.5);
+/// ```
+pub fn sniff(bytes: &[u8], limit: usize) -> Vec<(Mime, f32)> {
+    detector().sniff(bytes, limit)
+}
+
+/// Explains why a byte stream matches a MIME type.
+///
+/// The type is resolved through aliases first. Returns None if the type isn't
+/// known or the bytes don't match it. Otherwise the explanation names the
+/// checker that handles the type and, for types recognized by magic, the
+/// rules that matched and where.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// let explanation = tree_magic_mini::explain("image/gif", input).unwrap();
+/// assert_eq!(explanation.checker, "fdo_magic");
+/// assert_eq!(explanation.rules[0].offset, 0);
+/// assert!(explanation.rules[0].pattern.starts_with(b"GIF8"));
+///
+/// assert!(tree_magic_mini::explain("image/png", input).is_none());
+/// ```
+pub fn explain(mimetype: &str, bytes: &[u8]) -> Option<MatchExplanation> {
+    detector().explain(mimetype, bytes)
+}
+
+/// Explains how [`from_u8`] finds the type of a byte stream.
+///
+/// This gives every type matched on the way down the type graph, from
+/// `all/all` to the type `from_u8` returns, along with the [`explain`]ation
+/// for that last type. Returns None if no filetype definitions are loaded.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// let explanation = tree_magic_mini::explain_detection(input).unwrap();
+/// assert_eq!(explanation.chain.first(), Some(&"all/all"));
+/// assert_eq!(explanation.chain.last(), Some(&"image/gif"));
+/// assert_eq!(explanation.leaf.mimetype, "image/gif");
+/// ```
+pub fn explain_detection(bytes: &[u8]) -> Option<DetectionExplanation> {
+    detector().explain_detection(bytes)
+}
+
+/// Gets the type of a file from a byte stream, along with how much work it
+/// took.
+///
+/// The [`DetectionStats`] say how many nodes of the type graph were visited,
+/// how many times a checker was asked about a type, and which checker gave
+/// the answer. The type is the same one [`try_from_u8`] returns.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// let (result, stats) = tree_magic_mini::from_u8_with_stats(input);
+/// assert_eq!(result, Some("image/gif"));
+/// assert!(stats.match_calls >= stats.nodes_visited);
+/// assert_eq!(stats.checker, "fdo_magic");
+/// ```
+pub fn from_u8_with_stats(bytes: &[u8]) -> (Option<Mime>, DetectionStats) {
+    detector().from_u8_with_stats(bytes)
+}
+
+/// Gets the type of a file from a byte stream, with a cap on the work done.
+///
+/// The type graph is walked as in [`from_u8`], but at most `max_nodes` types
+/// are checked against the bytes. If the cap is reached, the deepest type
+/// matched so far is returned, or `application/octet-stream` if none has
+/// matched yet. That bounds the time spent on inputs that match deep into many
+/// branches. [`from_u8_with_stats`] shows how many checks an input needs.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// // Enough of a budget finds the same type as from_u8
+/// let result = tree_magic_mini::from_u8_bounded(input, 1000);
+/// assert_eq!(result, "image/gif");
+///
+/// // Without one nothing is checked
+/// let result = tree_magic_mini::from_u8_bounded(input, 0);
+/// assert_eq!(result, "application/octet-stream");
+/// ```
+pub fn from_u8_bounded(bytes: &[u8], max_nodes: usize) -> Mime {
+    detector().from_u8_bounded(bytes, max_nodes)
+}
+
+/// Gets the type to serve a resource as, following the
+/// [WHATWG MIME Sniffing standard](https://mimesniff.spec.whatwg.org/).
+///
+/// This is for web servers handing out content they didn't write, where the
+/// point is to match what browsers will do with it, not to name it as exactly
+/// as possible. So unlike [`from_u8`], it doesn't walk the type graph:
+///
+/// - Only the standard's short table of signatures is used, on the first
+///   1445 bytes, and the types returned are the standard's names for them
+///   (like `application/x-gzip` and `audio/wave`).
+/// - It never returns a scriptable type, one a browser might run code from:
+///   HTML, XML or PDF. These come out as `text/plain` or
+///   `application/octet-stream`, unless they were supplied.
+/// - A supplied type is kept, except that images, audio and video can be
+///   corrected to another type of the same kind, and the `text/plain` types
+///   old Apache servers sent for everything can be changed to
+///   `application/octet-stream` if the content is binary.
+///
+/// `supplied` is the `Content-Type` the resource came with, if any. Types that
+/// mean "unknown", like `application/unknown`, and invalid ones count as none.
+///
+/// If the resource is served with `X-Content-Type-Options: nosniff`, the
+/// standard says to use the supplied type as it is when there is one, so only
+/// call this without one.
+///
+/// # Examples
+/// ```rust
+/// let png: &[u8] = include_bytes!("../tests/image/png");
+/// assert_eq!(tree_magic_mini::sniff_web(png, None), "image/png");
+/// assert_eq!(tree_magic_mini::sniff_web(png, Some("image/gif")), "image/png");
+///
+/// // Not escalated to text/html
+/// let html = b"<!DOCTYPE html><script>alert(1)</script>";
+/// assert_eq!(tree_magic_mini::sniff_web(html, None), "text/plain");
+/// assert_eq!(tree_magic_mini::from_u8(html), "text/html");
+/// ```
+pub fn sniff_web<'a>(bytes: &[u8], supplied: Option<&'a str>) -> &'a str {
+    web::sniff(bytes, supplied)
+}
+
+/// Gets the most specific type of a file from a byte stream.
+///
+/// [`from_u8`] takes the first type that matches on each level of the type
+/// graph, so when sibling types both match, the answer depends on which is
+/// tried first. This checks every matching branch instead, like
+/// [`from_u8_all`], and returns the match deepest in the graph. If several are
+/// equally deep and `from_u8`'s answer is one of them, that's returned. It's
+/// slower, since it can't skip the branches after a match.
+///
+/// # Panics
+/// Panics if no filetype definitions are loaded.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// // Find the most specific MIME type of the GIF
+/// let result = tree_magic_mini::from_u8_best(input);
+/// assert_eq!(result, "image/gif");
+/// ```
+pub fn from_u8_best(bytes: &[u8]) -> Mime {
+    detector().from_u8_best(bytes)
 }
 
 /// Check if the given file matches the given MIME type.
@@ -329,16 +777,27 @@ pub fn from_u8(bytes: &[u8]) -> Mime {
 /// assert_eq!(result, true);
 /// ```
 pub fn match_file(mimetype: &str, file: &File) -> bool {
-    match_file_noalias(get_alias(mimetype), file)
+    detector().match_file(mimetype, file)
 }
 
-/// Internal function. Checks if an alias exists, and if it does,
-/// then runs `match_file`.
-fn match_file_noalias(mimetype: &str, file: &File) -> bool {
-    match CHECKER_SUPPORT.get(mimetype) {
-        None => false,
-        Some(c) => c.match_file(file, mimetype),
-    }
+/// Checks a file against several MIME types, and returns the first that it
+/// matches.
+///
+/// The start of the file is only read once, however many candidates there
+/// are. See [`match_any`] for how candidates are tried.
+///
+/// # Examples
+/// ```rust
+/// use std::fs::File;
+///
+/// // Get path to a GIF file
+/// let file = File::open("tests/image/gif").unwrap();
+///
+/// let accepted = ["image/png", "image/gif"];
+/// assert_eq!(tree_magic_mini::match_any_file(&accepted, &file), Some("image/gif"));
+/// ```
+pub fn match_any_file(candidates: &[&str], file: &File) -> Option<Mime> {
+    detector().match_any_file(candidates, file)
 }
 
 /// Check if the file at the given path matches the given MIME type.
@@ -358,32 +817,37 @@ fn match_file_noalias(mimetype: &str, file: &File) -> bool {
 /// ```
 #[inline]
 pub fn match_filepath(mimetype: &str, path: &Path) -> bool {
-    let Ok(file) = File::open(path) else {
-        return false;
-    };
-    match_file(mimetype, &file)
+    detector().match_filepath(mimetype, path)
 }
 
-/// Gets the type of a file, starting at a certain node in the type graph.
-fn from_file_node(parentnode: NodeIndex, file: &File) -> Option<Mime> {
-    // We're actually just going to thunk this down to a u8
-    // unless we're checking via basetype for speed reasons.
-
-    // Ensure it's at least a application/octet-stream
-    if !match_file("application/octet-stream", file) {
-        // Check the other base types
-        return typegraph_walker(parentnode, file, match_file_noalias);
-    }
-
-    // Load the first 2K of file and parse as u8
-    // for batch processing like this
-    let bytes = read_bytes(file, 2048).ok()?;
-    from_u8_node(parentnode, &bytes)
+/// Checks the file at the given path against several MIME types, and returns
+/// the first that it matches.
+///
+/// The file is opened and its start read only once, however many candidates
+/// there are. See [`match_any`] for how candidates are tried.
+///
+/// Returns None if the file could not be read or matches none of them.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+///
+/// // Get path to a GIF file
+/// let path: &Path = Path::new("tests/image/gif");
+///
+/// let accepted = ["image/png", "image/gif"];
+/// let result = tree_magic_mini::match_filepath_any(&accepted, path);
+/// assert_eq!(result, Some("image/gif"));
+/// ```
+pub fn match_filepath_any(candidates: &[&str], path: &Path) -> Option<Mime> {
+    detector().match_filepath_any(candidates, path)
 }
 
 /// Gets the MIME type of a file.
 ///
-/// Does not look at file name or extension, just the contents.
+/// Does not look at file name or extension, just the contents. Empty files
+/// are `application/x-empty`. See [`from_file_with_buffer`] for how much of
+/// the file is read.
 ///
 /// # Examples
 /// ```rust
@@ -397,13 +861,85 @@ fn from_file_node(parentnode: NodeIndex, file: &File) -> Option<Mime> {
 /// assert_eq!(result, Some("image/gif"));
 /// ```
 pub fn from_file(file: &File) -> Option<Mime> {
-    let node = TYPE.graph.externals(Incoming).next()?;
-    from_file_node(node, file)
+    detector().from_file(file)
+}
+
+/// Gets the MIME type of a file, reading up to `buffer_len` bytes of it.
+///
+/// [`from_file`] reads as far as the deepest loaded magic rule, up to 32KiB.
+/// Some formats keep their magic further in, and are only detected if
+/// `buffer_len` is large enough to reach it. Larger buffers cost more memory
+/// and more time reading and checking each file.
+///
+/// Binary files are also checked for a ZIP archive with other data in front
+/// of it, like a self-extracting one, which `buffer_len` doesn't cover. That
+/// reads the last 22 bytes, and only if they aren't the end of an archive,
+/// up to 64KiB before them, where an archive comment could have pushed it.
+///
+/// # Examples
+/// ```rust
+/// use std::fs::File;
+///
+/// // Get path to a GIF file
+/// let file = File::open("tests/image/gif").unwrap();
+///
+/// // Find the MIME type of the GIF
+/// let result = tree_magic_mini::from_file_with_buffer(&file, 64 * 1024);
+/// assert_eq!(result, Some("image/gif"));
+/// ```
+pub fn from_file_with_buffer(file: &File, buffer_len: usize) -> Option<Mime> {
+    detector().from_file_with_buffer(file, buffer_len)
+}
+
+/// Gets the MIME type of a file, as a [`MimeType`].
+///
+/// This is [`from_file`] with the result wrapped up.
+///
+/// # Examples
+/// ```rust
+/// use std::fs::File;
+///
+/// // Get path to a GIF file
+/// let file = File::open("tests/image/gif").unwrap();
+///
+/// let result = tree_magic_mini::from_file_typed(&file).unwrap();
+/// assert!(result.is_image());
+/// ```
+pub fn from_file_typed(file: &File) -> Option<MimeType> {
+    from_file(file).map(MimeType::from)
+}
+
+/// Gets the MIME type of a file, reading the whole file if the start of it
+/// isn't enough.
+///
+/// This runs [`from_file`] first. Only if that finds nothing more specific
+/// than `application/octet-stream` is the file read again, in full, so that
+/// rules looking further in than [`from_file`] reads can match. That second
+/// pass loads the whole file into memory, so it costs as much time and memory
+/// as the file is large; it's meant for callers that care more about getting
+/// an answer than about speed. Files whose type is found on the first pass
+/// cost no more than with [`from_file`].
+///
+/// # Examples
+/// ```rust
+/// use std::fs::File;
+///
+/// // Get path to a GIF file
+/// let file = File::open("tests/image/gif").unwrap();
+///
+/// let result = tree_magic_mini::from_file_thorough(&file);
+/// assert_eq!(result, Some("image/gif"));
+/// ```
+pub fn from_file_thorough(file: &File) -> Option<Mime> {
+    detector().from_file_thorough(file)
 }
 
 /// Gets the MIME type of a file.
 ///
-/// Does not look at file name or extension, just the contents.
+/// The contents decide the type, with the file name used to refine generic
+/// results and break ties, as in [`from_filepath_with_hint`]. Use
+/// [`from_file`] to ignore the file name entirely. Directories, FIFOs,
+/// sockets and device nodes get their `inode/*` type without being opened.
 /// Returns None if the file cannot be opened
 /// or if no matching MIME type is found.
 ///
@@ -420,13 +956,329 @@ pub fn from_file(file: &File) -> Option<Mime> {
 /// ```
 #[inline]
 pub fn from_filepath(path: &Path) -> Option<Mime> {
-    let file = File::open(path).ok()?;
-    from_file(&file)
+    detector().from_filepath(path)
+}
+
+/// Gets the MIME type of a file, as a [`MimeType`].
+///
+/// This is [`from_filepath`] with the result wrapped up.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+///
+/// let result = tree_magic_mini::from_filepath_typed(Path::new("tests/image")).unwrap();
+/// assert!(result.is_inode());
+/// assert_eq!(result, "inode/directory");
+/// ```
+pub fn from_filepath_typed(path: &Path) -> Option<MimeType> {
+    from_filepath(path).map(MimeType::from)
+}
+
+/// Gets the type of a file from a byte stream, parsed into a [`mime::Mime`].
+///
+/// Returns `None` if the detected type isn't one the `mime` crate can parse,
+/// which shouldn't happen with a well-formed database.
+///
+/// Only available with the `mime` feature.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// let result = tree_magic_mini::from_u8_mime(input).unwrap();
+/// assert_eq!(result, mime::IMAGE_GIF);
+/// ```
+#[cfg(feature = "mime")]
+pub fn from_u8_mime(bytes: &[u8]) -> Option<mime::Mime> {
+    from_u8(bytes).parse().ok()
+}
+
+/// Gets the MIME type of a file, parsed into a [`mime::Mime`].
+///
+/// Returns `None` if the file couldn't be read or the detected type couldn't
+/// be parsed.
+///
+/// Only available with the `mime` feature.
+///
+/// # Examples
+/// ```rust
+/// use std::fs::File;
+///
+/// // Get path to a GIF file
+/// let file = File::open("tests/image/gif").unwrap();
+///
+/// let result = tree_magic_mini::from_file_mime(&file).unwrap();
+/// assert_eq!(result.type_(), mime::IMAGE);
+/// ```
+#[cfg(feature = "mime")]
+pub fn from_file_mime(file: &File) -> Option<mime::Mime> {
+    from_file(file)?.parse().ok()
+}
+
+/// Gets the MIME type of a file from its path, parsed into a [`mime::Mime`].
+///
+/// Returns `None` if the file couldn't be read or the detected type couldn't
+/// be parsed.
+///
+/// Only available with the `mime` feature.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+///
+/// // Get path to a GIF file
+/// let path: &Path = Path::new("tests/image/gif");
+///
+/// let result = tree_magic_mini::from_filepath_mime(path).unwrap();
+/// assert_eq!(result, mime::IMAGE_GIF);
+/// ```
+#[cfg(feature = "mime")]
+pub fn from_filepath_mime(path: &Path) -> Option<mime::Mime> {
+    from_filepath(path)?.parse().ok()
+}
+
+/// Gets the MIME type a file name suggests, without looking at any contents.
+///
+/// Patterns with a higher weight in the database win, then longer patterns.
+/// Only the last component of a path is considered. Returns None if no
+/// pattern matches.
+///
+/// # Examples
+/// ```rust
+/// let result = tree_magic_mini::from_filename("src/main.rs");
+/// assert_eq!(result, Some("text/rust"));
+///
+/// let result = tree_magic_mini::from_filename("archive.tar.gz");
+/// assert_eq!(result, Some("application/x-compressed-tar"));
+/// ```
+pub fn from_filename(filename: &str) -> Option<Mime> {
+    let name = Path::new(filename).file_name()?.to_str()?;
+    glob::matches(name).first().copied()
+}
+
+/// Gets the original file name stored in a gzip header.
+///
+/// gzip records the name of the file it compressed unless told not to. Passing
+/// it to [`from_filename`] gives a guess at what's inside, without
+/// decompressing anything. The name is Latin-1, as RFC 1952 says.
+///
+/// Returns None if the bytes aren't gzip, the header has no name, or the name
+/// is cut off.
+///
+/// # Examples
+/// ```rust
+/// let bytes = include_bytes!("../tests/application/gzip-named");
+/// let name = tree_magic_mini::gzip_original_name(bytes);
+/// assert_eq!(name.as_deref(), Some("notes.txt"));
+/// ```
+pub fn gzip_original_name(bytes: &[u8]) -> Option<String> {
+    let name = gzip::original_name(bytes)?;
+    Some(name.iter().map(|&b| b as char).collect())
+}
+
+/// Checks if the data read from a stream matches the given MIME type.
+///
+/// The first 2048 bytes are read and checked. If they don't match, and the
+/// type's checker looks deeper than that, more is read, up to 32 KiB in all,
+/// and checked again. Whatever was read is consumed from the reader, so a
+/// reader that can't seek is left somewhere past the start. Wrap it in a
+/// [`std::io::BufReader`] with a big enough buffer and use [`match_u8`] on the
+/// result of `fill_buf` to keep the prefix instead.
+///
+/// Returns false if the stream can't be read.
+///
+/// # Examples
+/// ```rust
+/// let mut input: &[u8] = include_bytes!("../tests/image/png");
+/// assert!(tree_magic_mini::match_reader("image/png", &mut input));
+/// ```
+pub fn match_reader<R: Read>(mimetype: &str, reader: &mut R) -> bool {
+    detector().match_reader(mimetype, reader)
+}
+
+/// Gets the MIME type of the data read from a stream.
+///
+/// Only the first 2048 bytes are read and checked. Those bytes are consumed
+/// from the reader; anything after them is left for the caller. If you need to
+/// keep the prefix, wrap the reader in a [`std::io::BufReader`] and use
+/// [`from_u8`] on the result of `fill_buf` instead.
+///
+/// Returns None if the stream could not be read
+/// or if no matching MIME type is found.
+///
+/// # Examples
+/// ```rust
+/// // Get a reader over a GIF file
+/// let mut input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// // Find the MIME type of the GIF
+/// let result = tree_magic_mini::from_read(&mut input);
+/// assert_eq!(result, Some("image/gif"));
+/// ```
+pub fn from_read<R: Read>(reader: &mut R) -> Option<Mime> {
+    detector().from_read(reader)
+}
+
+/// Gets a human-readable description of a MIME type, in English.
+///
+/// The type is resolved through aliases first. Returns None if the database
+/// has no description for it.
+///
+/// # Examples
+/// ```rust
+/// let result = tree_magic_mini::description("image/png");
+/// assert_eq!(result, Some("PNG image"));
+/// ```
+pub fn description(mimetype: &str) -> Option<&'static str> {
+    fdo_magic::builtin::init::description(detector().canonical_mime(mimetype))
+}
+
+/// Lists the file name patterns associated with a MIME type.
+///
+/// The type is resolved through aliases first. Patterns are ordered highest
+/// weight first. Returns an empty list if the type has no patterns.
+///
+/// # Examples
+/// ```rust
+/// let result = tree_magic_mini::globs("image/png");
+/// assert_eq!(result, ["*.png"]);
+/// ```
+pub fn globs(mimetype: &str) -> Vec<&'static str> {
+    glob::patterns(detector().canonical_mime(mimetype))
+}
+
+/// Lists the file extensions associated with a MIME type, without the dot.
+///
+/// Only simple `*.ext` patterns count as extensions. The type is resolved
+/// through aliases first. Extensions are ordered highest weight first, so the
+/// first one is a good default for naming a file.
+///
+/// # Examples
+/// ```rust
+/// let result = tree_magic_mini::extensions("image/jpeg");
+/// assert!(result.contains(&"jpg"));
+/// ```
+pub fn extensions(mimetype: &str) -> Vec<&'static str> {
+    globs(mimetype)
+        .into_iter()
+        .filter_map(|pattern| pattern.strip_prefix("*."))
+        .filter(|ext| !ext.contains(['*', '?', '[']))
+        .collect()
+}
+
+/// Gets the MIME type of a file, using its name to break ties.
+///
+/// Some types can't be told apart by their contents alone, such as a plain
+/// text file and a Markdown document, or a ZIP archive and a JAR. This runs
+/// normal detection first, then checks the file name against the database's
+/// file name patterns. A pattern's type is only used if it is a more specific
+/// subclass of the detected type, or if it shares a parent with the detected
+/// type, the contents match it too, and its rules are at least as certain. A
+/// more certain match on the contents is never overridden.
+///
+/// [`from_filepath`] does the same.
+///
+/// Returns None if the file cannot be opened
+/// or if no matching MIME type is found.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+///
+/// // Get path to a GIF file with the wrong extension
+/// let path = Path::new("tests/hint/picture.png");
+///
+/// // The contents win over the file name
+/// let result = tree_magic_mini::from_filepath_with_hint(path);
+/// assert_eq!(result, Some("image/gif"));
+/// ```
+pub fn from_filepath_with_hint(path: &Path) -> Option<Mime> {
+    detector().from_filepath_with_hint(path)
+}
+
+/// Gets the MIME types of several files, in order.
+///
+/// Each file is detected as by [`from_filepath`], and paired with its path.
+///
+/// # Examples
+/// ```rust
+/// use std::path::PathBuf;
+///
+/// let paths = [PathBuf::from("tests/image/gif"), PathBuf::from("tests/image/missing")];
+/// let result = tree_magic_mini::from_filepaths(&paths);
+/// assert_eq!(result[0], (paths[0].clone(), Some("image/gif")));
+/// assert_eq!(result[1], (paths[1].clone(), None));
+/// ```
+pub fn from_filepaths(paths: &[PathBuf]) -> Vec<(PathBuf, Option<Mime>)> {
+    detector().from_filepaths(paths)
+}
+
+/// Gets the MIME types of several files, with the work spread across a thread
+/// for each available core.
+///
+/// The results are the same as [`from_filepaths`], in the same order. This
+/// pays off for long lists of files, where waiting on reads dominates; for a
+/// handful, starting the threads costs more than it saves.
+///
+/// # Examples
+/// ```rust
+/// use std::path::PathBuf;
+///
+/// let paths = [PathBuf::from("tests/image/gif"), PathBuf::from("tests/image/png")];
+/// let result = tree_magic_mini::from_filepaths_parallel(&paths);
+/// assert_eq!(result, tree_magic_mini::from_filepaths(&paths));
+/// ```
+pub fn from_filepaths_parallel(paths: &[PathBuf]) -> Vec<(PathBuf, Option<Mime>)> {
+    detector().from_filepaths_parallel(paths)
+}
+
+/// Gets the MIME type of a file, without following a symlink.
+///
+/// The path's own metadata is looked at first, so a symlink is
+/// `inode/symlink` rather than the type of what it points to. Directories,
+/// FIFOs, sockets and device nodes get their `inode/*` type without being
+/// opened. Regular files are detected as in [`from_filepath`].
+///
+/// Returns None if the path doesn't exist, or if no matching MIME type is
+/// found.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+///
+/// let result = tree_magic_mini::from_filepath_nofollow(Path::new("tests/image"));
+/// assert_eq!(result, Some("inode/directory"));
+/// ```
+pub fn from_filepath_nofollow(path: &Path) -> Option<Mime> {
+    detector().from_filepath_nofollow(path)
+}
+
+/// Gets the MIME type of a file, like [`from_filepath`], but says why when
+/// there's no result.
+///
+/// Returns [`DetectError::Io`] if the file cannot be opened or read, and
+/// [`DetectError::NoMatch`] if no matching MIME type is found.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+/// use tree_magic_mini::DetectError;
+///
+/// let result = tree_magic_mini::from_filepath_result(Path::new("tests/image/gif"));
+/// assert_eq!(result.unwrap(), "image/gif");
+///
+/// let result = tree_magic_mini::from_filepath_result(Path::new("tests/image/missing"));
+/// assert!(matches!(result, Err(DetectError::Io(_))));
+/// ```
+pub fn from_filepath_result(path: &Path) -> Result<Mime, DetectError> {
+    detector().from_filepath_result(path)
 }
 
-/// Reads the given number of bytes from a file
-fn read_bytes(file: &File, bytecount: usize) -> Result<Vec<u8>, std::io::Error> {
+/// Reads the given number of bytes from a file or stream
+fn read_bytes<R: Read>(reader: R, bytecount: usize) -> Result<Vec<u8>, std::io::Error> {
     let mut bytes = Vec::<u8>::with_capacity(bytecount);
-    file.take(bytecount as u64).read_to_end(&mut bytes)?;
+    reader.take(bytecount as u64).read_to_end(&mut bytes)?;
     Ok(bytes)
 }
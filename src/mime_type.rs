@@ -0,0 +1,157 @@
+//! A MIME type with its parts split out
+
+use crate::Mime;
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+
+/// Names copied at runtime, so each one is only copied once
+static INTERNED: Lazy<Mutex<HashSet<Mime>>> = Lazy::new(Mutex::default);
+
+/// A `'static` copy of a type name, shared by everything that asks for the
+/// same name
+pub(crate) fn intern(name: &str) -> Mime {
+    let mut interned = INTERNED.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(&known) = interned.get(name) {
+        return known;
+    }
+    let copy: Mime = Box::leak(name.into());
+    interned.insert(copy);
+    copy
+}
+
+/// A detected MIME type, like `image/png`, with helpers for its parts.
+///
+/// This is a thin wrapper around the `&'static str` the rest of the crate
+/// returns, so it's as cheap to copy and compare. Get one from the `_typed`
+/// detection functions like [`from_u8_typed`](crate::from_u8_typed), or from
+/// any type name with `From`.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::MimeType;
+///
+/// let mime = MimeType::from("image/svg+xml");
+/// assert_eq!(mime.toplevel(), "image");
+/// assert_eq!(mime.subtype(), "svg+xml");
+/// assert!(mime.is_image());
+/// assert_eq!(mime.to_string(), "image/svg+xml");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MimeType(Mime);
+
+impl MimeType {
+    /// Makes a type from a name that isn't `'static`, like one read from a
+    /// configuration file, so it can be used alongside the built-in ones.
+    ///
+    /// The name is copied the first time, and that copy is kept for the rest
+    /// of the program and handed out to every later call with the same name.
+    /// [`Checker`](crate::Checker)s for types only known at runtime can use
+    /// this for the names they support.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::MimeType;
+    ///
+    /// let name = String::from("application/x-from-config");
+    /// let mime = MimeType::intern(&name);
+    /// assert_eq!(mime, "application/x-from-config");
+    /// assert!(std::ptr::eq(mime.as_str(), MimeType::intern(&name).as_str()));
+    /// ```
+    pub fn intern(name: &str) -> MimeType {
+        MimeType(intern(name))
+    }
+
+    /// The whole type, like `image/png`
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+
+    /// The part before the slash, like `image` in `image/png`
+    pub fn toplevel(&self) -> &'static str {
+        self.0.split_once('/').map_or(self.0, |(toplevel, _)| toplevel)
+    }
+
+    /// The part after the slash, like `png` in `image/png`, or an empty string
+    /// if there's no slash
+    pub fn subtype(&self) -> &'static str {
+        self.0.split_once('/').map_or("", |(_, subtype)| subtype)
+    }
+
+    /// Whether this is a `text/*` type.
+    ///
+    /// Many text formats have `application/*` types, like
+    /// `application/json`. Use [`is_subclass`](crate::is_subclass) with
+    /// `text/plain` to catch those too.
+    pub fn is_text(&self) -> bool {
+        self.toplevel() == "text"
+    }
+
+    /// Whether this is an `image/*` type
+    pub fn is_image(&self) -> bool {
+        self.toplevel() == "image"
+    }
+
+    /// Whether this is an `audio/*` type
+    pub fn is_audio(&self) -> bool {
+        self.toplevel() == "audio"
+    }
+
+    /// Whether this is a `video/*` type
+    pub fn is_video(&self) -> bool {
+        self.toplevel() == "video"
+    }
+
+    /// Whether this is a `font/*` type
+    pub fn is_font(&self) -> bool {
+        self.toplevel() == "font"
+    }
+
+    /// Whether this is an `application/*` type
+    pub fn is_application(&self) -> bool {
+        self.toplevel() == "application"
+    }
+
+    /// Whether this is an `inode/*` type, for directories and other things
+    /// that aren't regular files
+    pub fn is_inode(&self) -> bool {
+        self.toplevel() == "inode"
+    }
+}
+
+impl From<Mime> for MimeType {
+    fn from(mime: Mime) -> MimeType {
+        MimeType(mime)
+    }
+}
+
+impl From<MimeType> for Mime {
+    fn from(mime: MimeType) -> Mime {
+        mime.0
+    }
+}
+
+impl AsRef<str> for MimeType {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl PartialEq<str> for MimeType {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for MimeType {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for MimeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
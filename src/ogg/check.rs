@@ -0,0 +1,36 @@
+use super::{is_supported, matches};
+use crate::{read_bytes, Mime};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+
+pub(crate) struct Ogg;
+
+impl crate::Checker for Ogg {
+    fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
+        is_supported(mimetype) && matches(bytes, mimetype)
+    }
+
+    fn match_file(&self, mut file: &File, mimetype: &str) -> bool {
+        if !is_supported(mimetype) || file.seek(SeekFrom::Start(0)).is_err() {
+            return false;
+        }
+        read_bytes(file, super::MAX_HEADERS_LEN).is_ok_and(|bytes| matches(&bytes, mimetype))
+    }
+
+    fn name(&self) -> &'static str {
+        "ogg"
+    }
+
+    fn get_supported(&self) -> Vec<Mime> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(Mime, Mime)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> HashMap<Mime, Mime> {
+        super::init::get_aliaslist()
+    }
+}
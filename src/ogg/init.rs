@@ -0,0 +1,22 @@
+use super::{AUDIO, CODECS, OGG, VIDEO};
+use crate::Mime;
+use std::collections::HashMap;
+
+pub fn get_supported() -> Vec<Mime> {
+    [OGG, AUDIO, VIDEO]
+        .into_iter()
+        .chain(CODECS.iter().map(|c| c.mime))
+        .collect()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(Mime, Mime)> {
+    [(OGG, AUDIO), (OGG, VIDEO)]
+        .into_iter()
+        .chain(CODECS.iter().map(|c| (c.parent, c.mime)))
+        .collect()
+}
+
+pub fn get_aliaslist() -> HashMap<Mime, Mime> {
+    HashMap::from([("audio/opus", "audio/x-opus+ogg")])
+}
@@ -0,0 +1,143 @@
+//! Tells apart the codecs in Ogg files by the first packet of each stream
+use crate::Mime;
+
+pub mod check;
+pub mod init;
+
+/// Parent of every type here, for Ogg files whose codecs aren't known
+const OGG: Mime = "application/ogg";
+const AUDIO: Mime = "audio/ogg";
+const VIDEO: Mime = "video/ogg";
+
+/// Set in a page's header type for the first page of each stream
+const FLAG_BOS: u8 = 0x02;
+
+/// Most streams looked at. Each starts with its own page, and those pages
+/// all come before any others.
+const MAX_STREAMS: usize = 8;
+
+/// Most bytes read from a file to find the streams
+const MAX_HEADERS_LEN: usize = 4096;
+
+/// The first packet of a Skeleton stream, which describes the other streams
+/// rather than holding any media itself
+const SKELETON: &[u8] = b"fishead\0";
+
+/// A codec and the starts of the first packet that identify it
+struct Codec {
+    mime: Mime,
+    parent: Mime,
+    magic: &'static [&'static [u8]],
+}
+
+static CODECS: &[Codec] = &[
+    Codec {
+        mime: "audio/x-vorbis+ogg",
+        parent: AUDIO,
+        magic: &[b"\x01vorbis"],
+    },
+    Codec {
+        mime: "audio/x-opus+ogg",
+        parent: AUDIO,
+        magic: &[b"OpusHead"],
+    },
+    Codec {
+        mime: "audio/x-flac+ogg",
+        parent: AUDIO,
+        // The second is from before FLAC had its own mapping
+        magic: &[b"\x7fFLAC", b"fLaC"],
+    },
+    Codec {
+        mime: "audio/x-speex+ogg",
+        parent: AUDIO,
+        magic: &[b"Speex   "],
+    },
+    Codec {
+        mime: "video/x-theora+ogg",
+        parent: VIDEO,
+        magic: &[b"\x80theora"],
+    },
+    Codec {
+        mime: "video/x-ogm+ogg",
+        parent: VIDEO,
+        magic: &[b"\x01video"],
+    },
+];
+
+/// The parts of a page needed to find its streams
+struct Page<'a> {
+    flags: u8,
+    /// The page's packet data, or as much of it as there is
+    body: &'a [u8],
+    /// Where the next page starts
+    len: usize,
+}
+
+/// Reads the page at the start of `bytes`. The header is 27 bytes, then a
+/// table of segment lengths whose sum is the length of the body.
+fn page(bytes: &[u8]) -> Option<Page<'_>> {
+    let header = bytes.get(..27)?;
+    if header[..4] != *b"OggS" || header[4] != 0 {
+        return None;
+    }
+    let body_start = 27 + header[26] as usize;
+    let lacing = bytes.get(27..body_start)?;
+    let len = body_start + lacing.iter().map(|&n| n as usize).sum::<usize>();
+    Some(Page {
+        flags: header[5],
+        body: &bytes[body_start..len.min(bytes.len())],
+        len,
+    })
+}
+
+fn codec_of(packet: &[u8]) -> Option<&'static Codec> {
+    CODECS
+        .iter()
+        .find(|c| c.magic.iter().any(|m| packet.starts_with(m)))
+}
+
+/// Works out which type `bytes` is, or `None` if it isn't Ogg.
+///
+/// A file with video is a video type, even if its audio stream comes first.
+fn classify(bytes: &[u8]) -> Option<Mime> {
+    let mut audio = None;
+    let mut pos = 0;
+    for i in 0..MAX_STREAMS {
+        let page = match bytes.get(pos..).and_then(page) {
+            Some(page) => page,
+            None if i == 0 => return None,
+            None => break,
+        };
+        if page.flags & FLAG_BOS == 0 {
+            break;
+        }
+        if !page.body.starts_with(SKELETON) {
+            match codec_of(page.body) {
+                Some(codec) if codec.parent == VIDEO => return Some(codec.mime),
+                Some(codec) => {
+                    audio.get_or_insert(codec.mime);
+                }
+                None => {}
+            }
+        }
+        pos += page.len;
+    }
+    Some(audio.unwrap_or(OGG))
+}
+
+fn is_supported(mimetype: &str) -> bool {
+    [OGG, AUDIO, VIDEO].contains(&mimetype) || CODECS.iter().any(|c| c.mime == mimetype)
+}
+
+/// Checks whether `bytes` is `mimetype` or one of its subclasses here
+fn matches(bytes: &[u8], mimetype: &str) -> bool {
+    let Some(mime) = classify(bytes) else {
+        return false;
+    };
+    if mimetype == OGG || mime == mimetype {
+        return true;
+    }
+    CODECS
+        .iter()
+        .any(|c| c.mime == mime && c.parent == mimetype)
+}
@@ -0,0 +1,161 @@
+use super::{get_signature, is_sfnt_header, Offset, Rule, Verify};
+use crate::{read_bytes, Mime, RuleMatch};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+
+/// How much of the start of a file is checked for text before looking for a
+/// ZIP end record
+const TEXT_PROBE_LEN: usize = 512;
+
+pub(crate) struct Signature;
+
+impl crate::Checker for Signature {
+    fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
+        let Some(sig) = get_signature(mimetype) else {
+            return false;
+        };
+
+        sig.rules.iter().any(|group| {
+            has_start_rule(group)
+                && start_rules(group).all(|rule| find_bytes_rule(bytes, rule).is_some())
+        })
+    }
+
+    fn explain_bytes(&self, bytes: &[u8], mimetype: &str) -> Vec<RuleMatch> {
+        let Some(sig) = get_signature(mimetype) else {
+            return Vec::new();
+        };
+        sig.rules
+            .iter()
+            .filter(|group| has_start_rule(group))
+            .find_map(|group| {
+                start_rules(group)
+                    .map(|rule| {
+                        Some(RuleMatch {
+                            offset: find_bytes_rule(bytes, rule)?,
+                            pattern: rule.val.to_vec(),
+                            mask: rule.mask.map(<[u8]>::to_vec),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn name(&self) -> &'static str {
+        "signature"
+    }
+
+    fn match_file(&self, file: &File, mimetype: &str) -> bool {
+        let Some(sig) = get_signature(mimetype) else {
+            return false;
+        };
+        let Ok(meta) = file.metadata() else {
+            return false;
+        };
+
+        sig.rules.iter().any(|group| {
+            group
+                .iter()
+                .all(|rule| match_file_rule(file, meta.len(), rule))
+        })
+    }
+
+    fn needs_file(&self, mimetype: &str) -> bool {
+        get_signature(mimetype).is_some_and(|sig| {
+            sig.rules
+                .iter()
+                .flat_map(|group| group.iter())
+                .any(|rule| matches!(rule.offset, Offset::End(_)))
+        })
+    }
+
+    fn priority(&self, mimetype: &str) -> u32 {
+        get_signature(mimetype).map_or(50, |sig| sig.priority)
+    }
+
+    fn get_supported(&self) -> Vec<Mime> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(Mime, Mime)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> HashMap<Mime, Mime> {
+        super::init::get_aliaslist()
+    }
+}
+
+/// We don't know where the end of the file is when given the start of it, so
+/// only rules anchored at the start are checked. Groups without any can't
+/// match at all.
+fn start_rules(group: &[Rule]) -> impl Iterator<Item = &Rule> {
+    group
+        .iter()
+        .filter(|rule| matches!(rule.offset, Offset::Start(_)))
+}
+
+fn has_start_rule(group: &[Rule]) -> bool {
+    start_rules(group).next().is_some()
+}
+
+/// Where a rule matches in the start of a file
+fn find_bytes_rule(bytes: &[u8], rule: &Rule) -> Option<usize> {
+    let Offset::Start(offset) = rule.offset else {
+        return None;
+    };
+    let rest = bytes.get(offset..)?;
+    let window = &rest[..rest.len().min(rule.window_len())];
+    let pos = rule.find(window, |pos| match rule.verify {
+        None => true,
+        Some(Verify::Sfnt) => is_sfnt_header(&rest[pos..]),
+        // End records need more than the bytes, and those are never checked
+        // here
+        Some(Verify::ZipEnd) => false,
+    })?;
+    Some(offset + pos)
+}
+
+/// Reads the window a rule covers straight from the file and checks it
+fn match_file_rule(mut file: &File, file_len: u64, rule: &Rule) -> bool {
+    let start = match rule.offset {
+        Offset::Start(offset) => offset as u64,
+        Offset::End(offset) => file_len.saturating_sub(offset as u64),
+    };
+    if start >= file_len {
+        return false;
+    }
+    if let Some(Verify::ZipEnd) = rule.verify {
+        return match_zip_end(file, file_len);
+    }
+    if file.seek(SeekFrom::Start(start)).is_err() {
+        return false;
+    }
+    let Ok(read) = read_bytes(file, rule.read_len()) else {
+        return false;
+    };
+    let window = &read[..read.len().min(rule.window_len())];
+    rule.find(window, |pos| match rule.verify {
+        None => true,
+        Some(Verify::Sfnt) => is_sfnt_header(&read[pos..]),
+        Some(Verify::ZipEnd) => false,
+    })
+    .is_some()
+}
+
+/// Looks for a ZIP end record at the end of a file. Every file is checked
+/// for one, so text, which nothing puts in front of an archive, is skipped
+/// without reading the end at all.
+fn match_zip_end(mut file: &File, file_len: u64) -> bool {
+    if file.seek(SeekFrom::Start(0)).is_err() {
+        return false;
+    }
+    match read_bytes(file, TEXT_PROBE_LEN) {
+        Ok(head) if !crate::basetype::looks_like_text(&head) => {
+            crate::zip::find_end_record(file, file_len).is_some()
+        }
+        _ => false,
+    }
+}
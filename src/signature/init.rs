@@ -0,0 +1,19 @@
+use crate::Mime;
+use std::collections::HashMap;
+
+pub fn get_supported() -> Vec<Mime> {
+    super::SIGNATURES.iter().map(|sig| sig.mime).collect()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(Mime, Mime)> {
+    super::SIGNATURES
+        .iter()
+        .filter_map(|sig| Some((sig.parent?, sig.mime)))
+        .chain(super::SUBCLASSES.iter().copied())
+        .collect()
+}
+
+pub fn get_aliaslist() -> HashMap<Mime, Mime> {
+    super::ALIASES.iter().copied().collect()
+}
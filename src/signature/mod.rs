@@ -0,0 +1,434 @@
+//! Handles built-in signatures for formats the shared MIME database misses,
+//! including ones that can only be found at the end of a file
+use crate::Mime;
+use std::iter::zip;
+
+pub mod check;
+pub mod init;
+
+/// Where a rule's search window starts
+#[derive(Debug, Clone, Copy)]
+enum Offset {
+    /// Bytes from the start of the file
+    Start(usize),
+    /// Bytes back from the end of the file
+    End(usize),
+}
+
+/// A byte pattern to look for within a window of the file
+#[derive(Debug)]
+struct Rule {
+    offset: Offset,
+    val: &'static [u8],
+    /// Which bits of each byte are compared, if not all of them
+    mask: Option<&'static [u8]>,
+    /// Extra bytes after the offset that the value may start at
+    region_len: usize,
+    /// A closer look at what follows the value, where the value alone is
+    /// too common to go on
+    verify: Option<Verify>,
+}
+
+/// Checks that more of a file than a rule's value agrees with it
+#[derive(Debug, Clone, Copy)]
+enum Verify {
+    /// The value is an sfnt version tag, which the rest of the font's table
+    /// directory header follows
+    Sfnt,
+    /// The value starts a ZIP end of central directory record, which has to
+    /// end the file and point back at a central directory
+    ZipEnd,
+}
+
+impl Rule {
+    const fn at(offset: usize, val: &'static [u8]) -> Rule {
+        Rule {
+            offset: Offset::Start(offset),
+            val,
+            mask: None,
+            region_len: 0,
+            verify: None,
+        }
+    }
+
+    /// Compares only the bits set in `mask`, which must be as long as `val`
+    const fn masked(offset: usize, val: &'static [u8], mask: &'static [u8]) -> Rule {
+        assert!(val.len() == mask.len());
+        Rule {
+            offset: Offset::Start(offset),
+            val,
+            mask: Some(mask),
+            region_len: 0,
+            verify: None,
+        }
+    }
+
+    const fn within_end(len: usize, val: &'static [u8]) -> Rule {
+        Rule {
+            offset: Offset::End(len),
+            val,
+            mask: None,
+            region_len: len.saturating_sub(val.len()),
+            verify: None,
+        }
+    }
+
+    const fn verified(self, verify: Verify) -> Rule {
+        Rule {
+            verify: Some(verify),
+            ..self
+        }
+    }
+
+    /// Where in a window the value first starts, out of the places `accept`
+    /// agrees with
+    fn find(&self, window: &[u8], mut accept: impl FnMut(usize) -> bool) -> Option<usize> {
+        window
+            .windows(self.val.len())
+            .enumerate()
+            .filter(|(_, w)| match self.mask {
+                None => *w == self.val,
+                Some(mask) => zip(zip(self.val, *w), mask).all(|((v, w), m)| v & m == w & m),
+            })
+            .map(|(pos, _)| pos)
+            .find(|&pos| accept(pos))
+    }
+
+    fn window_len(&self) -> usize {
+        self.val.len() + self.region_len
+    }
+
+    /// How much of the file checking the rule reads, which is more than the
+    /// window when there's a header after the value to verify
+    fn read_len(&self) -> usize {
+        match self.verify {
+            Some(Verify::Sfnt) => self.window_len() + SFNT_HEADER_LEN,
+            _ => self.window_len(),
+        }
+    }
+}
+
+/// A MIME type and the rules that identify it.
+///
+/// The type matches if every rule in any one of the groups matches.
+struct Signature {
+    mime: Mime,
+    parent: Option<Mime>,
+    /// Like the priorities in magic files: siblings with higher priorities
+    /// are checked first
+    priority: u32,
+    rules: &'static [&'static [Rule]],
+}
+
+/// The sfnt version tag and the four fields after it that say how many
+/// tables a font has
+const SFNT_HEADER_LEN: usize = 12;
+
+/// Most tables an sfnt font is taken to have. Real ones have a few dozen.
+const MAX_SFNT_TABLES: u16 = 256;
+
+/// Longest ZIP end of central directory record: 22 bytes plus a 64K comment
+const ZIP_EOCD_LEN: usize = 22 + 0xFFFF;
+
+static SIGNATURES: &[Signature] = &[
+    Signature {
+        mime: "application/zip",
+        parent: None,
+        priority: 50,
+        rules: &[
+            &[Rule::at(0, b"PK\x03\x04")],
+            // Self-extracting archives and other prefixed ZIPs. Groups are
+            // tried in order, so archives matched by their start never have
+            // their end read.
+            &[Rule::within_end(ZIP_EOCD_LEN, b"PK\x05\x06").verified(Verify::ZipEnd)],
+        ],
+    },
+    Signature {
+        mime: "application/wasm",
+        parent: None,
+        priority: 50,
+        // Followed by a 4-byte version
+        rules: &[&[Rule::at(0, b"\0asm")]],
+    },
+    Signature {
+        mime: "application/vnd.sqlite3",
+        parent: None,
+        priority: 50,
+        rules: &[&[Rule::at(0, b"SQLite format 3\0")]],
+    },
+    Signature {
+        mime: "application/zstd",
+        parent: None,
+        priority: 50,
+        rules: &[&[Rule::at(0, b"\x28\xb5\x2f\xfd")]],
+    },
+    Signature {
+        mime: "application/x-lz4",
+        parent: None,
+        priority: 50,
+        rules: &[
+            &[Rule::at(0, b"\x04\x22\x4d\x18")],
+            // The legacy format from before frames
+            &[Rule::at(0, b"\x02\x21\x4c\x18")],
+        ],
+    },
+    // Captures start with their byte-order magic, in either byte order, and
+    // the magic differs for nanosecond timestamps. The database only has the
+    // microsecond one, but taking the type over means listing it here too.
+    Signature {
+        mime: "application/vnd.tcpdump.pcap",
+        parent: None,
+        priority: 50,
+        rules: &[
+            &[Rule::at(0, b"\xa1\xb2\xc3\xd4")],
+            &[Rule::at(0, b"\xd4\xc3\xb2\xa1")],
+            &[Rule::at(0, b"\xa1\xb2\x3c\x4d")],
+            &[Rule::at(0, b"\x4d\x3c\xb2\xa1")],
+        ],
+    },
+    // The section header block's type reads the same in both byte orders, so
+    // the byte-order magic after its length says which one the file uses
+    Signature {
+        mime: "application/x-pcapng",
+        parent: None,
+        priority: 50,
+        rules: &[
+            &[
+                Rule::at(0, b"\x0a\x0d\x0d\x0a"),
+                Rule::at(8, b"\x1a\x2b\x3c\x4d"),
+            ],
+            &[
+                Rule::at(0, b"\x0a\x0d\x0d\x0a"),
+                Rule::at(8, b"\x4d\x3c\x2b\x1a"),
+            ],
+        ],
+    },
+    // Parquet files end with their footer's length and the magic again. Only
+    // the start can be checked without the whole file.
+    Signature {
+        mime: "application/vnd.apache.parquet",
+        parent: None,
+        priority: 50,
+        rules: &[&[Rule::at(0, b"PAR1"), Rule::within_end(4, b"PAR1")]],
+    },
+    // Arrow IPC files, which also end with the magic. The streaming format
+    // has none.
+    Signature {
+        mime: "application/vnd.apache.arrow.file",
+        parent: None,
+        priority: 50,
+        rules: &[&[Rule::at(0, b"ARROW1\0\0"), Rule::within_end(6, b"ARROW1")]],
+    },
+    // Git pack files, versions 2 and 3. Quake's PAK archives also start with
+    // `PACK`, with a priority of 80, so this goes first.
+    Signature {
+        mime: "application/x-git-pack",
+        parent: None,
+        priority: 90,
+        rules: &[
+            &[Rule::at(0, b"PACK\0\0\0\x02")],
+            &[Rule::at(0, b"PACK\0\0\0\x03")],
+        ],
+    },
+    // The `.idx` files next to them. Version 1 indexes have no magic.
+    Signature {
+        mime: "application/x-git-pack-index",
+        parent: None,
+        priority: 50,
+        rules: &[&[Rule::at(0, b"\xfftOc\0\0\0\x02")]],
+    },
+    // Thin Mach-O binaries, 32 and 64-bit, in either byte order
+    Signature {
+        mime: "application/x-mach-binary",
+        parent: None,
+        priority: 60,
+        rules: &[
+            &[Rule::at(0, b"\xfe\xed\xfa\xce")],
+            &[Rule::at(0, b"\xce\xfa\xed\xfe")],
+            &[Rule::at(0, b"\xfe\xed\xfa\xcf")],
+            &[Rule::at(0, b"\xcf\xfa\xed\xfe")],
+            // Fat binaries share their magic with Java classes, which have
+            // their version after it instead of an architecture count. Class
+            // versions start at 45, so fewer than 32 architectures means fat.
+            // This type is checked before application/x-java for that reason.
+            &[
+                Rule::at(0, b"\xca\xfe\xba\xbe"),
+                Rule::masked(4, b"\0\0\0\0", b"\xff\xff\xff\xe0"),
+            ],
+            // 64-bit fat binaries have a magic of their own
+            &[Rule::at(0, b"\xca\xfe\xba\xbf")],
+            &[
+                Rule::at(0, b"\xbe\xba\xfe\xca"),
+                Rule::masked(4, b"\0\0\0\0", b"\xe0\xff\xff\xff"),
+            ],
+        ],
+    },
+    // ELF files are split up by their type field, which follows the 16-byte
+    // identification and is in the byte order the identification gives. The
+    // database has rules for the other types, which go under this one below.
+    Signature {
+        mime: "application/x-elf",
+        parent: None,
+        priority: 60,
+        rules: &[&[Rule::at(0, b"\x7fELF")]],
+    },
+    // Position-independent executables have the same type as shared libraries
+    Signature {
+        mime: "application/x-sharedlib",
+        parent: Some("application/x-elf"),
+        priority: 50,
+        rules: &[
+            &[
+                Rule::at(0, b"\x7fELF"),
+                Rule::at(5, b"\x01"),
+                Rule::at(16, b"\x03\0"),
+            ],
+            &[
+                Rule::at(0, b"\x7fELF"),
+                Rule::at(5, b"\x02"),
+                Rule::at(16, b"\0\x03"),
+            ],
+            // 32-bit XCOFF, as the database has it
+            &[
+                Rule::at(0, b"\x83\x01"),
+                Rule::masked(22, b"\0\x20", b"\0\x30"),
+            ],
+        ],
+    },
+    // Bare codestreams, and the container, which always starts with this
+    // signature box rather than the usual ISO base media file type box
+    Signature {
+        mime: "image/jxl",
+        parent: None,
+        priority: 50,
+        rules: &[
+            &[Rule::at(0, b"\xff\x0a")],
+            &[Rule::at(0, b"\0\0\0\x0cJXL \r\n\x87\n")],
+        ],
+    },
+    // RIFF files say what they hold with a form type after the chunk size.
+    // The database's WAV rule only looks for the form type, so it matched
+    // other files with WAVE there too.
+    Signature {
+        mime: "application/x-riff",
+        parent: None,
+        priority: 45,
+        rules: &[&[Rule::at(0, b"RIFF")]],
+    },
+    Signature {
+        mime: "image/webp",
+        parent: Some("application/x-riff"),
+        priority: 50,
+        rules: &[&[Rule::at(0, b"RIFF"), Rule::at(8, b"WEBP")]],
+    },
+    Signature {
+        mime: "audio/x-wav",
+        parent: Some("application/x-riff"),
+        priority: 50,
+        rules: &[&[Rule::at(0, b"RIFF"), Rule::at(8, b"WAVE")]],
+    },
+    Signature {
+        mime: "video/x-msvideo",
+        parent: Some("application/x-riff"),
+        priority: 50,
+        rules: &[
+            &[Rule::at(0, b"RIFF"), Rule::at(8, b"AVI ")],
+            // AVIs from some cameras, which aren't RIFF at the start
+            &[Rule::at(0, b"AVF0")],
+        ],
+    },
+    Signature {
+        mime: "font/woff",
+        parent: None,
+        priority: 50,
+        rules: &[&[Rule::at(0, b"wOFF")]],
+    },
+    Signature {
+        mime: "font/woff2",
+        parent: None,
+        priority: 50,
+        rules: &[&[Rule::at(0, b"wOF2")]],
+    },
+    // TrueType and OpenType fonts share the sfnt container, whose version says
+    // which outlines it has. OpenType is checked as a subclass, so sfnt files of
+    // either kind match font/ttf first.
+    Signature {
+        mime: "font/ttf",
+        parent: None,
+        priority: 50,
+        rules: &[
+            &[Rule::at(0, b"\0\x01\0\0").verified(Verify::Sfnt)],
+            &[Rule::at(0, b"true").verified(Verify::Sfnt)],
+            &[Rule::at(0, b"OTTO").verified(Verify::Sfnt)],
+            // Mac font suitcases
+            &[Rule::at(0, b"FFIL")],
+            &[Rule::at(65, b"FFIL")],
+        ],
+    },
+    Signature {
+        mime: "font/otf",
+        parent: Some("font/ttf"),
+        priority: 50,
+        rules: &[&[Rule::at(0, b"OTTO").verified(Verify::Sfnt)]],
+    },
+];
+
+/// More subclass relations, as (parent, child), for types that have more than
+/// one parent or that other checkers handle
+static SUBCLASSES: &[(Mime, Mime)] = &[
+    ("application/x-elf", "application/x-executable"),
+    ("application/x-elf", "application/x-object"),
+    ("application/x-elf", "application/x-core"),
+    // These also have rules for formats other than ELF
+    ("application/octet-stream", "application/x-executable"),
+    ("application/octet-stream", "application/x-object"),
+    ("application/octet-stream", "application/x-core"),
+    ("application/octet-stream", "application/x-sharedlib"),
+    ("application/octet-stream", "video/x-msvideo"),
+];
+
+/// Other names the types above are known by
+static ALIASES: &[(Mime, Mime)] = &[
+    ("application/x-sqlite3", "application/vnd.sqlite3"),
+    ("application/pcap", "application/vnd.tcpdump.pcap"),
+    ("application/x-pcap", "application/vnd.tcpdump.pcap"),
+    ("application/vnd.tcpdump.pcapng", "application/x-pcapng"),
+    ("application/x-coredump", "application/x-core"),
+    ("audio/wav", "audio/x-wav"),
+    ("audio/vnd.wave", "audio/x-wav"),
+    ("video/avi", "video/x-msvideo"),
+    // Older databases use these names
+    ("application/font-woff", "font/woff"),
+    ("application/x-font-ttf", "font/ttf"),
+    ("application/x-font-otf", "font/otf"),
+];
+
+/// Whether `bytes`, from an sfnt version tag on, have a table directory header
+/// whose search fields agree with its table count, as every font's do. Text
+/// that happens to start with `true` or `OTTO` goes on with more text.
+fn is_sfnt_header(bytes: &[u8]) -> bool {
+    let Some(header) = bytes.get(..SFNT_HEADER_LEN) else {
+        return false;
+    };
+    let field = |offset: usize| u16::from_be_bytes([header[offset], header[offset + 1]]);
+    if header[4..]
+        .iter()
+        .all(|&b| b.is_ascii_graphic() || b.is_ascii_whitespace())
+    {
+        return false;
+    }
+
+    let num_tables = field(4);
+    if num_tables == 0 || num_tables > MAX_SFNT_TABLES {
+        return false;
+    }
+    let entry_selector = num_tables.ilog2() as u16;
+    let search_range = 16 << entry_selector;
+    field(6) == search_range
+        && field(8) == entry_selector
+        && field(10) == num_tables * 16 - search_range
+}
+
+fn get_signature(mimetype: &str) -> Option<&'static Signature> {
+    SIGNATURES.iter().find(|sig| sig.mime == mimetype)
+}
@@ -0,0 +1,65 @@
+use super::{TextType, FALLBACK_TYPES, PREFERRED_TYPES};
+use crate::{read_bytes, Mime, BUFFER_LEN};
+use std::collections::HashMap;
+use std::fs::File;
+
+pub(crate) struct Text {
+    types: &'static [TextType],
+}
+
+impl Text {
+    /// Heuristics for types the database may have better magic for
+    pub fn fallback() -> Text {
+        Text {
+            types: FALLBACK_TYPES,
+        }
+    }
+
+    /// Heuristics that should win over the database's magic
+    pub fn preferred() -> Text {
+        Text {
+            types: PREFERRED_TYPES,
+        }
+    }
+
+    fn get_type(&self, mimetype: &str) -> Option<&'static TextType> {
+        self.types.iter().find(|t| t.mime == mimetype)
+    }
+}
+
+impl crate::Checker for Text {
+    fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
+        // None of the heuristics care about a UTF-8 byte order mark, and it
+        // would get in the way of checking how the text starts
+        let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+        self.get_type(mimetype)
+            .is_some_and(|t| (t.matches)(bytes, t.mime))
+    }
+
+    fn match_file(&self, file: &File, mimetype: &str) -> bool {
+        let Ok(bytes) = read_bytes(file, BUFFER_LEN) else {
+            return false;
+        };
+        self.match_bytes(&bytes, mimetype)
+    }
+
+    fn priority(&self, mimetype: &str) -> u32 {
+        self.get_type(mimetype).map_or(50, |t| t.priority)
+    }
+
+    fn name(&self) -> &'static str {
+        "text"
+    }
+
+    fn get_supported(&self) -> Vec<Mime> {
+        super::init::get_supported(self.types)
+    }
+
+    fn get_subclasses(&self) -> Vec<(Mime, Mime)> {
+        super::init::get_subclasses(self.types)
+    }
+
+    fn get_aliaslist(&self) -> HashMap<Mime, Mime> {
+        super::init::get_aliaslist()
+    }
+}
@@ -0,0 +1,143 @@
+//! Comma and tab separated values, told apart from other text by having the
+//! same number of fields on each line.
+//!
+//! This is only a heuristic: a few lines of prose can have a comma each, and
+//! plenty of CSV has a ragged line or two. It's tuned to miss some CSV rather
+//! than take other text for it.
+
+use crate::Mime;
+
+/// Most records checked
+const MAX_RECORDS: usize = 16;
+
+/// Fewest complete records that have to agree
+const MIN_RECORDS: usize = 3;
+
+/// Fewest fields in each record. Lines of prose and code often have one
+/// comma, and fewer have two.
+const MIN_FIELDS: usize = 3;
+
+/// Most spaces in an unquoted comma separated field. Fields with more are
+/// more likely to be sentences.
+const MAX_FIELD_SPACES: usize = 3;
+
+/// Checks if this separates fields with commas or tabs, whichever
+/// `mimetype` is for
+pub fn matches(bytes: &[u8], mimetype: Mime) -> bool {
+    let delimiter = match mimetype {
+        "text/tab-separated-values" => b'\t',
+        _ => b',',
+    };
+
+    let mut fields = None;
+    let mut count = 0;
+    for record in Records::new(bytes, delimiter).take(MAX_RECORDS) {
+        // The last record may have been cut off
+        let Some(record) = record else {
+            break;
+        };
+        if record.blank {
+            continue;
+        }
+        if !record.plausible || *fields.get_or_insert(record.fields) != record.fields {
+            return false;
+        }
+        count += 1;
+    }
+    count >= MIN_RECORDS && fields.is_some_and(|n| n >= MIN_FIELDS)
+}
+
+/// What's known about one record
+struct Record {
+    fields: usize,
+    blank: bool,
+    /// Whether its fields look like data rather than prose or code
+    plausible: bool,
+}
+
+/// Splits the input into records, which end at newlines outside quotes.
+///
+/// Yields `None` for a last record that doesn't end in a newline, since
+/// there's no telling if it's complete.
+struct Records<'a> {
+    bytes: &'a [u8],
+    delimiter: u8,
+}
+
+impl<'a> Records<'a> {
+    fn new(bytes: &'a [u8], delimiter: u8) -> Records<'a> {
+        Records { bytes, delimiter }
+    }
+}
+
+impl Iterator for Records<'_> {
+    type Item = Option<Record>;
+
+    fn next(&mut self) -> Option<Option<Record>> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let mut record = Record {
+            fields: 1,
+            blank: true,
+            plausible: true,
+        };
+        let (mut quoted, mut at_field_start, mut spaces) = (false, true, 0);
+        // How many more brackets the field has opened than closed
+        let mut depth = 0i32;
+        // The last unquoted byte other than whitespace
+        let mut last = 0;
+        let mut bytes = self.bytes.iter().enumerate().peekable();
+        while let Some((i, &c)) = bytes.next() {
+            if quoted {
+                // Quotes inside are doubled
+                if c == b'"' && bytes.next_if(|&(_, &next)| next == b'"').is_none() {
+                    quoted = false;
+                }
+                continue;
+            }
+            match c {
+                b'\n' => {
+                    // Lines of prose end sentences, and lines of code go on
+                    // with the next one after a trailing comma
+                    if matches!(last, b'.' | b'!' | b'?' | b':' | b';')
+                        || (at_field_start && record.fields > 1)
+                        || depth != 0
+                    {
+                        record.plausible = false;
+                    }
+                    self.bytes = &self.bytes[i + 1..];
+                    return Some(Some(record));
+                }
+                b'\r' => continue,
+                b'"' if at_field_start => quoted = true,
+                _ if c == self.delimiter => {
+                    // Tab indented lines are code, not a leading empty field,
+                    // and commas inside brackets separate arguments
+                    if (record.blank && c == b'\t') || depth != 0 {
+                        record.plausible = false;
+                    }
+                    record.fields += 1;
+                    (at_field_start, spaces, depth) = (true, 0, 0);
+                    (record.blank, last) = (false, c);
+                    continue;
+                }
+                b' ' if self.delimiter == b',' => {
+                    spaces += 1;
+                    if spaces > MAX_FIELD_SPACES {
+                        record.plausible = false;
+                    }
+                }
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => depth -= 1,
+                _ => {}
+            }
+            at_field_start = false;
+            if !c.is_ascii_whitespace() {
+                (record.blank, last) = (false, c);
+            }
+        }
+        self.bytes = &[];
+        Some(None)
+    }
+}
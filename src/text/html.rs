@@ -0,0 +1,45 @@
+//! HTML documents, by the tags the WHATWG MIME Sniffing standard looks for
+//! at the start
+
+use crate::Mime;
+
+/// Tags and markup that start HTML documents, in uppercase, since they're
+/// compared ignoring case. Each one must be followed by a space or a `>`.
+static PATTERNS: &[&[u8]] = &[
+    b"<!DOCTYPE HTML",
+    b"<HTML",
+    b"<HEAD",
+    b"<SCRIPT",
+    b"<IFRAME",
+    b"<H1",
+    b"<DIV",
+    b"<FONT",
+    b"<TABLE",
+    b"<A",
+    b"<STYLE",
+    b"<TITLE",
+    b"<B",
+    b"<BODY",
+    b"<BR",
+    b"<P",
+    b"<!--",
+];
+
+/// The whitespace the standard skips; ASCII whitespace without vertical tab
+fn is_whitespace(c: u8) -> bool {
+    matches!(c, b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+pub fn matches(bytes: &[u8], _mimetype: Mime) -> bool {
+    let start = bytes
+        .iter()
+        .position(|&c| !is_whitespace(c))
+        .unwrap_or(bytes.len());
+    let bytes = &bytes[start..];
+    PATTERNS.iter().any(|pattern| {
+        bytes
+            .get(..pattern.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(pattern))
+            && matches!(bytes.get(pattern.len()), Some(b' ' | b'>'))
+    })
+}
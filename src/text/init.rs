@@ -0,0 +1,24 @@
+use super::{TextType, SUBCLASSES};
+use crate::Mime;
+use std::collections::HashMap;
+
+pub(super) fn get_supported(types: &[TextType]) -> Vec<Mime> {
+    types.iter().map(|t| t.mime).collect()
+}
+
+/// Returns Vec of parent->child relations
+pub(super) fn get_subclasses(types: &[TextType]) -> Vec<(Mime, Mime)> {
+    let extra = SUBCLASSES
+        .iter()
+        .copied()
+        .filter(|&(_, child)| types.iter().any(|t| t.mime == child));
+    types
+        .iter()
+        .map(|t| (t.parent, t.mime))
+        .chain(extra)
+        .collect()
+}
+
+pub fn get_aliaslist() -> HashMap<Mime, Mime> {
+    HashMap::default()
+}
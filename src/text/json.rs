@@ -0,0 +1,224 @@
+//! JSON documents, and streams of them with one per line
+
+use crate::Mime;
+
+/// Most lines of a stream checked
+const MAX_LINES: usize = 8;
+
+/// Deepest nesting checked, so hostile input can't overflow the stack
+const MAX_DEPTH: usize = 64;
+
+/// Why a value couldn't be parsed
+#[derive(Debug, PartialEq)]
+enum Stop {
+    /// The input ended partway through, and may have been cut off
+    Truncated,
+    /// The input isn't JSON
+    Invalid,
+}
+
+/// Checks if this is a JSON object or array.
+///
+/// Input that's cut off partway through is accepted as long as at least one
+/// value inside it was complete, since only the start of a file is checked.
+pub fn matches(bytes: &[u8], _mimetype: Mime) -> bool {
+    let bytes = content(bytes);
+    if !matches!(bytes.first(), Some(b'{' | b'[')) {
+        return false;
+    }
+
+    let mut complete = 0;
+    match value(bytes, 0, 0, &mut complete) {
+        Ok(end) => bytes[end..].trim_ascii().is_empty(),
+        Err(Stop::Truncated) => complete > 0,
+        Err(Stop::Invalid) => false,
+    }
+}
+
+/// Checks if this is newline-delimited JSON, with an object or array on each
+/// of its first few lines.
+///
+/// A single line is just a JSON document. The last line may be cut off, and
+/// lines after the first few aren't checked.
+pub fn matches_lines(bytes: &[u8], _mimetype: Mime) -> bool {
+    let bytes = content(bytes);
+    let mut lines = bytes
+        .split(|&c| c == b'\n')
+        .map(|line| line.trim_ascii())
+        .filter(|line| !line.is_empty())
+        .take(MAX_LINES)
+        .peekable();
+
+    let mut complete = 0;
+    while let Some(line) = lines.next() {
+        if !matches!(line.first(), Some(b'{' | b'[')) {
+            return false;
+        }
+        match value(line, 0, 0, &mut 0) {
+            Ok(end) if end == line.len() => complete += 1,
+            // Only the last line can be cut off
+            Err(Stop::Truncated) if lines.peek().is_none() => {}
+            _ => return false,
+        }
+    }
+    complete >= 2
+}
+
+/// The input after any leading whitespace
+fn content(bytes: &[u8]) -> &[u8] {
+    bytes.trim_ascii_start()
+}
+
+fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+    while bytes
+        .get(i)
+        .is_some_and(|c| matches!(c, b' ' | b'\t' | b'\n' | b'\r'))
+    {
+        i += 1;
+    }
+    i
+}
+
+/// Parse the value at `i`, returning where it ends. `complete` counts the
+/// values finished inside containers.
+fn value(bytes: &[u8], i: usize, depth: usize, complete: &mut usize) -> Result<usize, Stop> {
+    if depth > MAX_DEPTH {
+        return Err(Stop::Invalid);
+    }
+    match bytes.get(i).ok_or(Stop::Truncated)? {
+        b'{' => object(bytes, i + 1, depth + 1, complete),
+        b'[' => array(bytes, i + 1, depth + 1, complete),
+        b'"' => string(bytes, i + 1),
+        b'-' | b'0'..=b'9' => number(bytes, i),
+        b't' => literal(bytes, i, b"true"),
+        b'f' => literal(bytes, i, b"false"),
+        b'n' => literal(bytes, i, b"null"),
+        _ => Err(Stop::Invalid),
+    }
+}
+
+fn object(bytes: &[u8], i: usize, depth: usize, complete: &mut usize) -> Result<usize, Stop> {
+    let mut i = skip_whitespace(bytes, i);
+    if bytes.get(i) == Some(&b'}') {
+        return Ok(i + 1);
+    }
+    loop {
+        match bytes.get(i).ok_or(Stop::Truncated)? {
+            b'"' => i = string(bytes, i + 1)?,
+            _ => return Err(Stop::Invalid),
+        }
+        i = skip_whitespace(bytes, i);
+        match bytes.get(i).ok_or(Stop::Truncated)? {
+            b':' => i = skip_whitespace(bytes, i + 1),
+            _ => return Err(Stop::Invalid),
+        }
+        i = value(bytes, i, depth, complete)?;
+        *complete += 1;
+        i = skip_whitespace(bytes, i);
+        match bytes.get(i).ok_or(Stop::Truncated)? {
+            b',' => i = skip_whitespace(bytes, i + 1),
+            b'}' => return Ok(i + 1),
+            _ => return Err(Stop::Invalid),
+        }
+    }
+}
+
+fn array(bytes: &[u8], i: usize, depth: usize, complete: &mut usize) -> Result<usize, Stop> {
+    let mut i = skip_whitespace(bytes, i);
+    if bytes.get(i) == Some(&b']') {
+        return Ok(i + 1);
+    }
+    loop {
+        i = value(bytes, i, depth, complete)?;
+        *complete += 1;
+        i = skip_whitespace(bytes, i);
+        match bytes.get(i).ok_or(Stop::Truncated)? {
+            b',' => i = skip_whitespace(bytes, i + 1),
+            b']' => return Ok(i + 1),
+            _ => return Err(Stop::Invalid),
+        }
+    }
+}
+
+/// Parse the rest of a string, starting after its opening quote
+fn string(bytes: &[u8], mut i: usize) -> Result<usize, Stop> {
+    loop {
+        match *bytes.get(i).ok_or(Stop::Truncated)? {
+            b'"' => return Ok(i + 1),
+            b'\\' => {
+                i += 1;
+                match bytes.get(i).ok_or(Stop::Truncated)? {
+                    b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => i += 1,
+                    b'u' => {
+                        for _ in 0..4 {
+                            i += 1;
+                            if !bytes.get(i).ok_or(Stop::Truncated)?.is_ascii_hexdigit() {
+                                return Err(Stop::Invalid);
+                            }
+                        }
+                        i += 1;
+                    }
+                    _ => return Err(Stop::Invalid),
+                }
+            }
+            0..=0x1f => return Err(Stop::Invalid),
+            _ => i += 1,
+        }
+    }
+}
+
+fn number(bytes: &[u8], mut i: usize) -> Result<usize, Stop> {
+    let digits = |i: usize| bytes[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+
+    if bytes[i] == b'-' {
+        i += 1;
+    }
+    match digits(i) {
+        0 => return Err(end_or(bytes, i, Stop::Invalid)),
+        // No leading zeros
+        n if n > 1 && bytes[i] == b'0' => return Err(Stop::Invalid),
+        n => i += n,
+    }
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        match digits(i) {
+            0 => return Err(end_or(bytes, i, Stop::Invalid)),
+            n => i += n,
+        }
+    }
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        match digits(i) {
+            0 => return Err(end_or(bytes, i, Stop::Invalid)),
+            n => i += n,
+        }
+    }
+
+    // More digits might have followed
+    match i >= bytes.len() {
+        true => Err(Stop::Truncated),
+        false => Ok(i),
+    }
+}
+
+fn literal(bytes: &[u8], i: usize, expected: &[u8]) -> Result<usize, Stop> {
+    let rest = &bytes[i..];
+    if rest.starts_with(expected) {
+        Ok(i + expected.len())
+    } else if expected.starts_with(rest) {
+        Err(Stop::Truncated)
+    } else {
+        Err(Stop::Invalid)
+    }
+}
+
+/// `Truncated` if the input ends at `i`, or else `otherwise`
+fn end_or(bytes: &[u8], i: usize, otherwise: Stop) -> Stop {
+    match i >= bytes.len() {
+        true => Stop::Truncated,
+        false => otherwise,
+    }
+}
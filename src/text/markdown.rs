@@ -0,0 +1,47 @@
+//! Markdown, which has no magic at all. File names are a much better guide,
+//! so this only takes text for Markdown if it starts with a heading and has
+//! some other syntax that's rare outside Markdown.
+
+use crate::Mime;
+use memchr::memmem;
+
+/// Most lines checked after the heading
+const MAX_LINES: usize = 64;
+
+pub fn matches(bytes: &[u8], _mimetype: Mime) -> bool {
+    let mut lines = bytes
+        .split(|&c| c == b'\n')
+        .map(|line| line.trim_ascii_end());
+    if !lines.find(|line| !line.is_empty()).is_some_and(is_heading) {
+        return false;
+    }
+    // Scripts start with comments like this too, so one more thing is needed
+    lines.take(MAX_LINES).any(has_markup)
+}
+
+/// An ATX heading, like `## Usage`
+fn is_heading(line: &[u8]) -> bool {
+    let level = line.iter().take_while(|&&c| c == b'#').count();
+    (1..=6).contains(&level)
+        && line.get(level) == Some(&b' ')
+        && !line[level..].trim_ascii().is_empty()
+}
+
+/// A code fence, or a link or image
+fn has_markup(line: &[u8]) -> bool {
+    line.starts_with(b"```") || line.starts_with(b"~~~") || has_link(line)
+}
+
+/// Link text in brackets followed by the target in parentheses. The text has
+/// to start a word, since indexing and calling, as in `a[b](c)`, looks the
+/// same in code.
+fn has_link(line: &[u8]) -> bool {
+    memmem::find_iter(line, b"](").any(|end| {
+        line[..end]
+            .iter()
+            .rposition(|&c| c == b'[')
+            .is_some_and(|start| {
+                start == 0 || matches!(line[start - 1], b' ' | b'\t' | b'(' | b'!')
+            })
+    })
+}
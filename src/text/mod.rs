@@ -0,0 +1,124 @@
+//! Handles built-in heuristics for text formats. Most are only used when the
+//! shared MIME database doesn't have magic for a type, but some look deeper
+//! than the database's magic can, and are used instead of it.
+use crate::Mime;
+
+pub mod check;
+#[cfg(feature = "csv")]
+mod delimited;
+mod html;
+pub mod init;
+mod json;
+mod markdown;
+mod plist;
+mod shebang;
+mod xml;
+
+/// A text type, and the type it's a subclass of
+struct TextType {
+    mime: Mime,
+    parent: Mime,
+    /// Checks the start of a file for this type
+    matches: fn(&[u8], Mime) -> bool,
+    /// Priority over sibling types, as in the database's magic
+    priority: u32,
+    /// Whether the heuristic is only a guess, which a file name saying
+    /// otherwise should win over
+    guess: bool,
+}
+
+impl TextType {
+    const fn new(mime: Mime, parent: Mime, matches: fn(&[u8], Mime) -> bool) -> TextType {
+        TextType {
+            mime,
+            parent,
+            matches,
+            priority: 50,
+            guess: false,
+        }
+    }
+
+    const fn priority(self, priority: u32) -> TextType {
+        TextType { priority, ..self }
+    }
+
+    const fn guess(self) -> TextType {
+        TextType {
+            guess: true,
+            ..self
+        }
+    }
+}
+
+/// Types the database's magic is used for instead, if it has any
+static FALLBACK_TYPES: &[TextType] = &[
+    TextType::new("application/x-shellscript", "text/plain", shebang::matches),
+    TextType::new(
+        "application/x-csh",
+        "application/x-shellscript",
+        shebang::matches,
+    ),
+    TextType::new("text/x-python", "text/plain", shebang::matches),
+    TextType::new("text/x-python3", "text/x-python", shebang::matches),
+    TextType::new("application/x-perl", "text/plain", shebang::matches),
+    TextType::new("application/x-ruby", "text/plain", shebang::matches),
+    TextType::new("application/x-php", "text/plain", shebang::matches),
+    TextType::new("application/javascript", "text/plain", shebang::matches),
+    TextType::new("text/x-lua", "text/plain", shebang::matches),
+    TextType::new("text/tcl", "text/plain", shebang::matches),
+    TextType::new("application/x-awk", "text/plain", shebang::matches),
+    TextType::new("application/json", "text/plain", json::matches),
+    // Checked after JSON, since one line of it is a JSON document
+    TextType::new("application/x-ndjson", "text/plain", json::matches_lines).priority(45),
+    // Checked after most other types, since lots of text has the same number
+    // of commas or tabs on a few lines
+    #[cfg(feature = "csv")]
+    TextType::new(
+        "text/tab-separated-values",
+        "text/plain",
+        delimited::matches,
+    )
+    .priority(41)
+    .guess(),
+    #[cfg(feature = "csv")]
+    TextType::new("text/csv", "text/plain", delimited::matches)
+        .priority(40)
+        .guess(),
+    // Last of all, since it's little more than a guess
+    TextType::new("text/markdown", "text/plain", markdown::matches)
+        .priority(35)
+        .guess(),
+];
+
+/// Types these heuristics are used for even if the database has magic.
+///
+/// XML is checked before HTML, so XHTML with an XML declaration isn't taken
+/// for HTML.
+static PREFERRED_TYPES: &[TextType] = &[
+    TextType::new("application/xml", "text/plain", xml::matches).priority(55),
+    TextType::new("image/svg+xml", "application/xml", xml::matches).priority(80),
+    TextType::new("application/rss+xml", "application/xml", xml::matches).priority(70),
+    TextType::new("application/atom+xml", "application/xml", xml::matches).priority(70),
+    TextType::new("application/xhtml+xml", "application/xml", xml::matches).priority(60),
+    TextType::new("application/x-plist", "application/xml", plist::matches).priority(70),
+    TextType::new("text/html", "text/plain", html::matches),
+];
+
+/// More subclass relations, as (parent, child), for types with more than one
+/// parent
+static SUBCLASSES: &[(Mime, Mime)] = &[
+    // Property lists also have a binary form
+    ("application/octet-stream", "application/x-plist"),
+];
+
+/// Whether a match on this type is only a guess from the contents
+pub fn is_guess(mimetype: &str) -> bool {
+    get_type(mimetype).is_some_and(|t| t.guess)
+}
+
+fn get_type(mimetype: &str) -> Option<&'static TextType> {
+    FALLBACK_TYPES
+        .iter()
+        .chain(PREFERRED_TYPES)
+        .find(|t| t.mime == mimetype)
+}
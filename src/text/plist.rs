@@ -0,0 +1,11 @@
+//! Apple property lists, in either their XML or their binary form
+
+use crate::Mime;
+
+/// Start of the binary form. The digits are its version, which is always 00
+/// in practice.
+const BINARY_MAGIC: &[u8] = b"bplist00";
+
+pub fn matches(bytes: &[u8], mimetype: Mime) -> bool {
+    bytes.starts_with(BINARY_MAGIC) || super::xml::matches(bytes, mimetype)
+}
@@ -0,0 +1,74 @@
+//! Scripts, by the interpreter named on their `#!` line
+
+use crate::Mime;
+
+/// Interpreters and the types of scripts they run. Names also match with a
+/// version after them, like `python3.12` or `perl5`, so more specific names
+/// come first.
+static INTERPRETERS: &[(&str, Mime)] = &[
+    ("sh", "application/x-shellscript"),
+    ("bash", "application/x-shellscript"),
+    ("dash", "application/x-shellscript"),
+    ("ash", "application/x-shellscript"),
+    ("ksh", "application/x-shellscript"),
+    ("mksh", "application/x-shellscript"),
+    ("zsh", "application/x-shellscript"),
+    ("csh", "application/x-csh"),
+    ("tcsh", "application/x-csh"),
+    ("python3", "text/x-python3"),
+    ("python", "text/x-python"),
+    ("pypy3", "text/x-python3"),
+    ("pypy", "text/x-python"),
+    ("perl", "application/x-perl"),
+    ("ruby", "application/x-ruby"),
+    ("php", "application/x-php"),
+    ("node", "application/javascript"),
+    ("nodejs", "application/javascript"),
+    ("lua", "text/x-lua"),
+    ("tclsh", "text/tcl"),
+    ("wish", "text/tcl"),
+    ("awk", "application/x-awk"),
+    ("gawk", "application/x-awk"),
+    ("mawk", "application/x-awk"),
+    ("nawk", "application/x-awk"),
+];
+
+/// Longest `#!` line worth reading
+const MAX_LINE_LEN: usize = 256;
+
+/// Checks if the script's interpreter runs `mimetype`, or a subclass of it
+pub fn matches(bytes: &[u8], mimetype: Mime) -> bool {
+    let Some(found) = script_type(bytes) else {
+        return false;
+    };
+    found == mimetype || super::get_type(found).is_some_and(|t| t.parent == mimetype)
+}
+
+/// The type of script named by the `#!` line, if it's an interpreter we know
+fn script_type(bytes: &[u8]) -> Option<Mime> {
+    let line = bytes.strip_prefix(b"#!")?;
+    let line = &line[..line.len().min(MAX_LINE_LEN)];
+    let end = memchr::memchr(b'\n', line).unwrap_or(line.len());
+    let line = std::str::from_utf8(&line[..end]).ok()?;
+
+    let mut words = line.split_whitespace();
+    let mut program = basename(words.next()?);
+
+    // Look through `env`, and any options and variables given to it
+    if program == "env" {
+        program = basename(words.find(|w| !w.starts_with('-') && !w.contains('='))?);
+    }
+
+    INTERPRETERS
+        .iter()
+        .find(|(name, _)| {
+            program
+                .strip_prefix(name)
+                .is_some_and(|version| version.chars().all(|c| c.is_ascii_digit() || c == '.'))
+        })
+        .map(|&(_, mime)| mime)
+}
+
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
@@ -0,0 +1,108 @@
+//! XML documents, and the formats told apart by their root element
+
+use crate::Mime;
+use memchr::memmem;
+
+/// Root elements of formats built on XML
+static ROOTS: &[(&[u8], Mime)] = &[
+    (b"svg", "image/svg+xml"),
+    (b"rss", "application/rss+xml"),
+    (b"feed", "application/atom+xml"),
+    (b"html", "application/xhtml+xml"),
+    (b"plist", "application/x-plist"),
+];
+
+/// Checks if this is an XML document, and for formats built on XML, if its
+/// root element is the format's
+pub fn matches(bytes: &[u8], mimetype: Mime) -> bool {
+    let root = root_element(bytes);
+    if mimetype == "application/xml" {
+        return root.is_some() || has_declaration(bytes);
+    }
+    root.is_some_and(|root| {
+        ROOTS
+            .iter()
+            .any(|&(name, mime)| mime == mimetype && name == root)
+    })
+}
+
+/// The input after any leading whitespace
+fn content(bytes: &[u8]) -> &[u8] {
+    bytes.trim_ascii_start()
+}
+
+fn has_declaration(bytes: &[u8]) -> bool {
+    content(bytes)
+        .strip_prefix(b"<?xml")
+        .and_then(|after| after.first())
+        .is_some_and(|&c| c == b'?' || c.is_ascii_whitespace())
+}
+
+/// The local name of the root element, if this looks like an XML document:
+/// one with an XML declaration, or with a namespace declared on its root
+fn root_element(bytes: &[u8]) -> Option<&[u8]> {
+    let declared = has_declaration(bytes);
+    let mut rest = content(bytes);
+
+    // Skip the prolog: the declaration, processing instructions, comments,
+    // and the DOCTYPE
+    loop {
+        rest = rest.trim_ascii_start();
+        if let Some(pi) = rest.strip_prefix(b"<?") {
+            rest = after(pi, b"?>")?;
+        } else if let Some(comment) = rest.strip_prefix(b"<!--") {
+            rest = after(comment, b"-->")?;
+        } else if let Some(doctype) = rest.strip_prefix(b"<!DOCTYPE") {
+            rest = after_doctype(doctype)?;
+        } else {
+            break;
+        }
+    }
+
+    let tag = rest.strip_prefix(b"<")?;
+    let name_len = tag
+        .iter()
+        .position(|&c| c.is_ascii_whitespace() || c == b'>' || c == b'/')
+        .unwrap_or(tag.len());
+    let name = &tag[..name_len];
+    if !name.first().is_some_and(|&c| is_name_start(c)) {
+        return None;
+    }
+
+    // Lots of text starts with a tag, so insist on a namespace without a
+    // declaration. HTML often has one too, but is rarely meant as XML.
+    if !declared {
+        let end = memchr::memchr(b'>', tag)?;
+        memmem::find(&tag[name_len..end], b"xmlns")?;
+        if name.eq_ignore_ascii_case(b"html") {
+            return None;
+        }
+    }
+
+    // Drop any namespace prefix, like in `<svg:svg>`
+    let local = match memchr::memrchr(b':', name) {
+        Some(colon) => &name[colon + 1..],
+        None => name,
+    };
+    Some(local)
+}
+
+/// The rest of the input after `end`
+fn after<'a>(bytes: &'a [u8], end: &[u8]) -> Option<&'a [u8]> {
+    let start = memmem::find(bytes, end)?;
+    Some(&bytes[start + end.len()..])
+}
+
+/// The rest of the input after a DOCTYPE, which may have an internal subset
+/// in brackets
+fn after_doctype(bytes: &[u8]) -> Option<&[u8]> {
+    let close = memchr::memchr(b'>', bytes)?;
+    match memchr::memchr(b'[', &bytes[..close]) {
+        Some(open) => after(after(&bytes[open..], b"]")?, b">"),
+        None => Some(&bytes[close + 1..]),
+    }
+}
+
+fn is_name_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_' || c == b':' || c >= 0x80
+}
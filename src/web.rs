@@ -0,0 +1,267 @@
+//! The WHATWG MIME Sniffing standard's rules for computing the type of a
+//! resource fetched over HTTP, for `sniff_web`
+//!
+//! <https://mimesniff.spec.whatwg.org/>
+
+/// Only this much of a resource is ever looked at
+const RESOURCE_HEADER_LEN: usize = 1445;
+
+/// A byte pattern, and the mask the input is ANDed with before it's compared
+struct Pattern {
+    pattern: &'static [u8],
+    mask: &'static [u8],
+    mime: &'static str,
+}
+
+/// Matches `pattern` exactly. The mask is longer than any of the patterns.
+const fn exact(pattern: &'static [u8], mime: &'static str) -> Pattern {
+    Pattern {
+        pattern,
+        mask: &[0xff; 16],
+        mime,
+    }
+}
+
+/// Matches `pattern` where `mask` is 0xff, and anything where it's 0
+const fn masked(pattern: &'static [u8], mask: &'static [u8], mime: &'static str) -> Pattern {
+    Pattern {
+        pattern,
+        mask,
+        mime,
+    }
+}
+
+const RIFF_MASK: &[u8] = b"\xff\xff\xff\xff\0\0\0\0\xff\xff\xff\xff";
+
+static IMAGE_PATTERNS: &[Pattern] = &[
+    exact(b"\0\0\x01\0", "image/x-icon"),
+    exact(b"\0\0\x02\0", "image/x-icon"),
+    exact(b"BM", "image/bmp"),
+    exact(b"GIF87a", "image/gif"),
+    exact(b"GIF89a", "image/gif"),
+    masked(
+        b"RIFF\0\0\0\0WEBPVP",
+        b"\xff\xff\xff\xff\0\0\0\0\xff\xff\xff\xff\xff\xff",
+        "image/webp",
+    ),
+    exact(b"\x89PNG\r\n\x1a\n", "image/png"),
+    exact(b"\xff\xd8\xff", "image/jpeg"),
+];
+
+static AUDIO_VIDEO_PATTERNS: &[Pattern] = &[
+    masked(b"FORM\0\0\0\0AIFF", RIFF_MASK, "audio/aiff"),
+    exact(b"ID3", "audio/mpeg"),
+    exact(b"OggS\0", "application/ogg"),
+    exact(b"MThd\0\0\0\x06", "audio/midi"),
+    masked(b"RIFF\0\0\0\0AVI ", RIFF_MASK, "video/avi"),
+    masked(b"RIFF\0\0\0\0WAVE", RIFF_MASK, "audio/wave"),
+];
+
+static ARCHIVE_PATTERNS: &[Pattern] = &[
+    exact(b"\x1f\x8b\x08", "application/x-gzip"),
+    exact(b"PK\x03\x04", "application/zip"),
+    exact(b"Rar!\x1a\x07\0", "application/x-rar-compressed"),
+];
+
+/// Byte order marks, which only text starts with
+static BOMS: &[&[u8]] = &[b"\xfe\xff", b"\xff\xfe", b"\xef\xbb\xbf"];
+
+/// Supplied types that old Apache servers sent for everything, so they say
+/// nothing about whether the resource is text
+static APACHE_TEXT_TYPES: &[&str] = &[
+    "text/plain",
+    "text/plain; charset=ISO-8859-1",
+    "text/plain; charset=iso-8859-1",
+    "text/plain; charset=UTF-8",
+];
+
+/// Supplied types that mean the server doesn't know either
+static UNKNOWN_TYPES: &[&str] = &["unknown/unknown", "application/unknown", "*/*"];
+
+fn pattern_match(bytes: &[u8], patterns: &[Pattern]) -> Option<&'static str> {
+    patterns
+        .iter()
+        .find(|p| {
+            bytes.len() >= p.pattern.len()
+                && p.pattern
+                    .iter()
+                    .zip(p.mask)
+                    .zip(bytes)
+                    .all(|((&pattern, &mask), &byte)| byte & mask == pattern)
+        })
+        .map(|p| p.mime)
+}
+
+fn image_type(bytes: &[u8]) -> Option<&'static str> {
+    pattern_match(bytes, IMAGE_PATTERNS)
+}
+
+fn audio_video_type(bytes: &[u8]) -> Option<&'static str> {
+    if let Some(mime) = pattern_match(bytes, AUDIO_VIDEO_PATTERNS) {
+        return Some(mime);
+    }
+    if is_mp4(bytes) {
+        return Some("video/mp4");
+    }
+    if is_webm(bytes) {
+        return Some("video/webm");
+    }
+    if is_mp3(bytes) {
+        return Some("audio/mpeg");
+    }
+    None
+}
+
+/// An `ftyp` box with an `mp4` brand, major or compatible
+fn is_mp4(bytes: &[u8]) -> bool {
+    let Some(header) = bytes.get(..12) else {
+        return false;
+    };
+    let box_size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    if bytes.len() < box_size || !box_size.is_multiple_of(4) || &header[4..8] != b"ftyp" {
+        return false;
+    }
+    if &header[8..11] == b"mp4" {
+        return true;
+    }
+    // Skip the minor version to get to the compatible brands
+    (16..box_size)
+        .step_by(4)
+        .any(|pos| bytes.get(pos..pos + 3) == Some(b"mp4"))
+}
+
+/// An EBML header with a `webm` DocType, near the start
+fn is_webm(bytes: &[u8]) -> bool {
+    if !bytes.starts_with(b"\x1a\x45\xdf\xa3") {
+        return false;
+    }
+    let mut pos = 4;
+    while pos < bytes.len() && pos < 38 {
+        if bytes.get(pos..pos + 2) == Some(b"\x42\x82") {
+            pos += 2;
+            if pos >= bytes.len() {
+                return false;
+            }
+            pos += vint_len(bytes, pos);
+            if pos + 4 > bytes.len() {
+                return false;
+            }
+            // The DocType is padded out with NULs
+            let rest = &bytes[pos..];
+            let start = rest.iter().position(|&b| b != 0).unwrap_or(rest.len());
+            if rest[start..].starts_with(b"webm") {
+                return true;
+            }
+        }
+        pos += 1;
+    }
+    false
+}
+
+/// How many bytes the EBML variable length integer at `pos` takes up
+fn vint_len(bytes: &[u8], pos: usize) -> usize {
+    let first = bytes[pos];
+    (first.leading_zeros() as usize + 1).min(8)
+}
+
+const MP3_RATES: [u32; 15] = [
+    0, 32000, 40000, 48000, 56000, 64000, 80000, 96000, 112000, 128000, 160000, 192000, 224000,
+    256000, 320000,
+];
+const MP25_RATES: [u32; 15] = [
+    0, 8000, 16000, 24000, 32000, 40000, 48000, 56000, 64000, 80000, 96000, 112000, 128000, 144000,
+    160000,
+];
+const SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+/// Two MPEG audio frame headers in a row, where the first says the second is
+fn is_mp3(bytes: &[u8]) -> bool {
+    let Some(size) = mp3_frame_len(bytes, 0) else {
+        return false;
+    };
+    if size < 4 || size > bytes.len() {
+        return false;
+    }
+    mp3_frame_len(bytes, size).is_some()
+}
+
+/// The length of the frame whose header is at `pos`, if there's a valid one
+fn mp3_frame_len(bytes: &[u8], pos: usize) -> Option<usize> {
+    let header = bytes.get(pos..pos + 4)?;
+    if header[0] != 0xff || header[1] & 0xe0 != 0xe0 {
+        return None;
+    }
+    let layer = (header[1] & 0x06) >> 1;
+    let bitrate = ((header[2] & 0xf0) >> 4) as usize;
+    let sample_rate = ((header[2] & 0x0c) >> 2) as usize;
+    if layer == 0 || bitrate == 15 || sample_rate == 3 {
+        return None;
+    }
+
+    let version = (header[1] & 0x18) >> 3;
+    let bitrate = match version {
+        3 => MP3_RATES[bitrate],
+        _ => MP25_RATES[bitrate],
+    };
+    let scale = if version == 1 { 72 } else { 144 };
+    let padding = ((header[2] & 0x02) >> 1) as usize;
+    Some((bitrate * scale / SAMPLE_RATES[sample_rate]) as usize + padding)
+}
+
+fn is_binary_byte(byte: u8) -> bool {
+    matches!(byte, 0x00..=0x08 | 0x0b | 0x0e..=0x1a | 0x1c..=0x1f)
+}
+
+fn text_or_binary(bytes: &[u8]) -> &'static str {
+    if BOMS.iter().any(|bom| bytes.starts_with(bom)) || !bytes.iter().any(|&b| is_binary_byte(b)) {
+        "text/plain"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+/// The rules for a resource with no type, leaving out the scriptable types
+/// (HTML, XML and PDF), which are never sniffed
+fn unknown_type(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(b"%!PS-Adobe-") {
+        return "application/postscript";
+    }
+    if BOMS.iter().any(|bom| bytes.starts_with(bom)) {
+        return "text/plain";
+    }
+    image_type(bytes)
+        .or_else(|| audio_video_type(bytes))
+        .or_else(|| pattern_match(bytes, ARCHIVE_PATTERNS))
+        .unwrap_or_else(|| text_or_binary(bytes))
+}
+
+/// The supplied type without parameters, in lowercase, if it's a valid type
+fn essence(supplied: &str) -> Option<String> {
+    let essence = supplied.split(';').next()?.trim().to_ascii_lowercase();
+    let (top, sub) = essence.split_once('/')?;
+    (!top.is_empty() && !sub.is_empty()).then_some(essence)
+}
+
+pub(crate) fn sniff<'a>(bytes: &[u8], supplied: Option<&'a str>) -> &'a str {
+    let bytes = &bytes[..bytes.len().min(RESOURCE_HEADER_LEN)];
+    let (supplied, essence) = match supplied.and_then(|s| Some((s, essence(s)?))) {
+        Some((supplied, essence)) if !UNKNOWN_TYPES.contains(&essence.as_str()) => {
+            (supplied, essence)
+        }
+        _ => return unknown_type(bytes),
+    };
+
+    if APACHE_TEXT_TYPES.contains(&supplied) {
+        return text_or_binary(bytes);
+    }
+    // XML types are kept even when they're images, like SVG
+    if essence.ends_with("+xml") || essence == "text/xml" || essence == "application/xml" {
+        return supplied;
+    }
+    let sniffed = match essence.split_once('/') {
+        Some(("image", _)) => image_type(bytes),
+        Some(("audio" | "video", _)) => audio_video_type(bytes),
+        _ => None,
+    };
+    sniffed.unwrap_or(supplied)
+}
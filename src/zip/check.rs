@@ -0,0 +1,45 @@
+use super::{get_container, read_file, Archive};
+use crate::Mime;
+use std::collections::HashMap;
+use std::fs::File;
+
+pub(crate) struct Zip;
+
+impl crate::Checker for Zip {
+    fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
+        let Some(container) = get_container(mimetype) else {
+            return false;
+        };
+        Archive::from_bytes(bytes)
+            .is_some_and(|archive| (container.matches)(&archive, container.mime))
+    }
+
+    fn match_file(&self, file: &File, mimetype: &str) -> bool {
+        let Some(container) = get_container(mimetype) else {
+            return false;
+        };
+        read_file(file).is_some_and(|archive| (container.matches)(&archive, container.mime))
+    }
+
+    /// The central directory at the end of the archive lists every entry,
+    /// where the start of the file only has the first few
+    fn needs_file(&self, mimetype: &str) -> bool {
+        get_container(mimetype).is_some()
+    }
+
+    fn name(&self) -> &'static str {
+        "zip"
+    }
+
+    fn get_supported(&self) -> Vec<Mime> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(Mime, Mime)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> HashMap<Mime, Mime> {
+        super::init::get_aliaslist()
+    }
+}
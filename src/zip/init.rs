@@ -0,0 +1,18 @@
+use crate::Mime;
+use std::collections::HashMap;
+
+pub fn get_supported() -> Vec<Mime> {
+    super::CONTAINERS.iter().map(|c| c.mime).collect()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(Mime, Mime)> {
+    super::CONTAINERS
+        .iter()
+        .map(|c| ("application/zip", c.mime))
+        .collect()
+}
+
+pub fn get_aliaslist() -> HashMap<Mime, Mime> {
+    super::ODF_ALIASES.iter().copied().collect()
+}
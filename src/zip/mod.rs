@@ -0,0 +1,432 @@
+//! Looks inside ZIP archives to tell apart the document formats built on them
+use crate::{read_bytes, Mime, BUFFER_LEN};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+pub mod check;
+pub mod init;
+
+const LOCAL_HEADER_SIG: &[u8] = b"PK\x03\x04";
+const CENTRAL_HEADER_SIG: &[u8] = b"PK\x01\x02";
+const DATA_DESCRIPTOR_SIG: &[u8] = b"PK\x07\x08";
+const EOCD_SIG: &[u8] = b"PK\x05\x06";
+const ZIP64_LOCATOR_SIG: &[u8] = b"PK\x06\x07";
+
+/// Length of the ZIP64 end of central directory locator, which comes right
+/// before the usual record
+const ZIP64_LOCATOR_LEN: u64 = 20;
+
+/// Shortest end of central directory record, with no comment
+const EOCD_MIN_LEN: u64 = 22;
+
+/// Longest end of central directory record: 22 bytes plus a 64K comment
+const EOCD_LEN: u64 = EOCD_MIN_LEN + 0xFFFF;
+
+/// Most entry names read from one archive
+const MAX_ENTRIES: usize = 1024;
+
+/// Most bytes of central directory read from one archive
+const MAX_CENTRAL_DIR_LEN: u64 = 1024 * 1024;
+
+/// Longest `mimetype` entry read
+const MAX_MIMETYPE_LEN: usize = 128;
+
+/// Set in the local header flags when the sizes follow the entry's data
+const FLAG_DATA_DESCRIPTOR: u16 = 0x08;
+
+fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    let b = bytes.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    let b = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// The entries of a ZIP archive, as far as they could be read
+#[derive(Debug, Default)]
+struct Archive {
+    names: Vec<String>,
+    /// Contents of a stored `mimetype` entry at the start of the archive
+    mimetype: Option<String>,
+}
+
+impl Archive {
+    /// Walks the local file headers at the start of an archive, stopping at
+    /// the end of the buffer or at an entry whose size isn't known up front.
+    fn from_bytes(bytes: &[u8]) -> Option<Archive> {
+        if !bytes.starts_with(LOCAL_HEADER_SIG) {
+            return None;
+        }
+
+        let mut archive = Archive {
+            mimetype: first_mimetype(bytes),
+            ..Archive::default()
+        };
+        let mut pos = 0;
+        while archive.names.len() < MAX_ENTRIES {
+            let Some(header) = bytes.get(pos..pos + 30) else {
+                break;
+            };
+            if !header.starts_with(LOCAL_HEADER_SIG) {
+                break;
+            }
+            let (Some(flags), Some(data_len), Some(name_len), Some(extra_len)) = (
+                u16_at(header, 6),
+                u32_at(header, 18),
+                u16_at(header, 26),
+                u16_at(header, 28),
+            ) else {
+                break;
+            };
+
+            let name_start = pos + 30;
+            let Some(name) = bytes.get(name_start..name_start + name_len as usize) else {
+                break;
+            };
+            archive
+                .names
+                .push(String::from_utf8_lossy(name).into_owned());
+
+            // Streamed entries and ZIP64 entries don't say how long they are
+            let streamed = flags & FLAG_DATA_DESCRIPTOR != 0;
+            if (streamed && data_len == 0) || data_len == u32::MAX {
+                break;
+            }
+            pos = name_start + name_len as usize + extra_len as usize + data_len as usize;
+            if streamed {
+                let has_sig = bytes
+                    .get(pos..)
+                    .is_some_and(|rest| rest.starts_with(DATA_DESCRIPTOR_SIG));
+                pos += if has_sig { 16 } else { 12 };
+            }
+        }
+        Some(archive)
+    }
+
+    /// Reads the names in the central directory at the end of an archive,
+    /// falling back to the local headers in `head` if there isn't one.
+    fn from_file(mut file: &File, file_len: u64, head: &[u8]) -> Option<Archive> {
+        let Some(Some((dir_start, dir_len))) = find_end_record(file, file_len) else {
+            return Archive::from_bytes(head);
+        };
+        file.seek(SeekFrom::Start(dir_start)).ok()?;
+        let dir = read_bytes(file, dir_len.min(MAX_CENTRAL_DIR_LEN as u32) as usize).ok()?;
+
+        let mut archive = Archive {
+            mimetype: first_mimetype(head),
+            ..Archive::default()
+        };
+        let mut pos = 0;
+        while archive.names.len() < MAX_ENTRIES {
+            let Some(header) = dir.get(pos..pos + 46) else {
+                break;
+            };
+            if !header.starts_with(CENTRAL_HEADER_SIG) {
+                break;
+            }
+            let (Some(name_len), Some(extra_len), Some(comment_len)) =
+                (u16_at(header, 28), u16_at(header, 30), u16_at(header, 32))
+            else {
+                break;
+            };
+
+            let name_start = pos + 46;
+            let Some(name) = dir.get(name_start..name_start + name_len as usize) else {
+                break;
+            };
+            archive
+                .names
+                .push(String::from_utf8_lossy(name).into_owned());
+            pos = name_start + name_len as usize + extra_len as usize + comment_len as usize;
+        }
+        Some(archive)
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n == name)
+    }
+
+    fn contains_dir(&self, dir: &str) -> bool {
+        self.names.iter().any(|n| n.starts_with(dir))
+    }
+}
+
+/// Finds a file's end of central directory record, and gives where the
+/// central directory is, as [`end_record_at`] does.
+///
+/// Most archives have no comment, so only the last 22 bytes are read at
+/// first. The rest of the 64K a comment could take up is only read if those
+/// aren't a record.
+pub(crate) fn find_end_record(mut file: &File, file_len: u64) -> Option<Option<(u64, u32)>> {
+    let short_start = file_len.checked_sub(EOCD_MIN_LEN)?;
+    file.seek(SeekFrom::Start(short_start)).ok()?;
+    let short = read_bytes(file, EOCD_MIN_LEN as usize).ok()?;
+    if let Some(dir) = end_record_at(file, file_len, short_start, &short) {
+        return Some(dir);
+    }
+
+    let tail_start = file_len.saturating_sub(EOCD_LEN);
+    file.seek(SeekFrom::Start(tail_start)).ok()?;
+    let mut tail = read_bytes(file, (short_start - tail_start) as usize).ok()?;
+    tail.extend_from_slice(&short);
+    tail.windows(EOCD_SIG.len())
+        .enumerate()
+        .rev()
+        .filter(|(_, w)| *w == EOCD_SIG)
+        .find_map(|(pos, _)| end_record_at(file, file_len, tail_start + pos as u64, &tail[pos..]))
+}
+
+/// Checks that `record`, found at `offset` in a file and running to its end,
+/// is the archive's end of central directory record, and gives where the
+/// central directory is. That's `None` for ZIP64 archives, whose record only
+/// points at another one.
+///
+/// The central directory ends where the record starts. Counting back from
+/// there works for archives with data prepended to them too, whose recorded
+/// offset is short by the length of that data.
+pub(crate) fn end_record_at(
+    mut file: &File,
+    file_len: u64,
+    offset: u64,
+    record: &[u8],
+) -> Option<Option<(u64, u32)>> {
+    let comment_len = u16_at(record, 20)?;
+    if !record.starts_with(EOCD_SIG) || offset + 22 + comment_len as u64 != file_len {
+        return None;
+    }
+    let (entries, dir_len, dir_offset) = (
+        u16_at(record, 10)?,
+        u32_at(record, 12)?,
+        u32_at(record, 16)?,
+    );
+
+    if entries == u16::MAX || dir_len == u32::MAX || dir_offset == u32::MAX {
+        let locator_start = offset.checked_sub(ZIP64_LOCATOR_LEN)?;
+        file.seek(SeekFrom::Start(locator_start)).ok()?;
+        let locator = read_bytes(file, ZIP64_LOCATOR_SIG.len()).ok()?;
+        return (locator == ZIP64_LOCATOR_SIG).then_some(None);
+    }
+
+    let dir_start = offset.checked_sub(dir_len as u64)?;
+    if dir_offset as u64 > dir_start {
+        return None;
+    }
+    if entries == 0 {
+        return (dir_len == 0).then_some(Some((dir_start, dir_len)));
+    }
+    file.seek(SeekFrom::Start(dir_start)).ok()?;
+    let first = read_bytes(file, CENTRAL_HEADER_SIG.len()).ok()?;
+    (first == CENTRAL_HEADER_SIG).then_some(Some((dir_start, dir_len)))
+}
+
+/// The archive in the file being detected, once it's been read
+struct Current {
+    /// Where the file is, which is only compared while it's borrowed
+    file: *const File,
+    archive: Option<Option<Rc<Archive>>>,
+}
+
+thread_local! {
+    /// Every type under `application/zip` is checked against the same file
+    /// in turn, so this saves reading the central directory again for each
+    /// of them.
+    static CURRENT: RefCell<Option<Current>> = const { RefCell::new(None) };
+}
+
+/// Keeps the archive read from a file for the rest of one detection, and
+/// forgets it when dropped
+pub(crate) struct Scope<'a> {
+    previous: Option<Current>,
+    _file: PhantomData<&'a File>,
+}
+
+impl<'a> Scope<'a> {
+    pub fn new(file: &'a File) -> Scope<'a> {
+        let current = Current {
+            file,
+            archive: None,
+        };
+        Scope {
+            previous: CURRENT.replace(Some(current)),
+            _file: PhantomData,
+        }
+    }
+}
+
+impl Drop for Scope<'_> {
+    fn drop(&mut self) {
+        CURRENT.set(self.previous.take());
+    }
+}
+
+/// Reads the archive in a file, or reuses it if it's the file being detected
+/// and it's been read already
+fn read_file(file: &File) -> Option<Rc<Archive>> {
+    let is_current = |current: &Current| std::ptr::eq(current.file, file);
+    let cached = CURRENT.with_borrow(|current| {
+        current
+            .as_ref()
+            .filter(|c| is_current(c))
+            .and_then(|c| c.archive.clone())
+    });
+    if let Some(archive) = cached {
+        return archive;
+    }
+
+    let archive = parse_file(file).map(Rc::new);
+    CURRENT.with_borrow_mut(|current| {
+        if let Some(current) = current.as_mut().filter(|c| is_current(c)) {
+            current.archive = Some(archive.clone());
+        }
+    });
+    archive
+}
+
+fn parse_file(mut file: &File) -> Option<Archive> {
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    let head = read_bytes(file, BUFFER_LEN).ok()?;
+    Archive::from_file(file, len, &head)
+}
+
+/// Reads the `mimetype` entry OpenDocument and EPUB files start with. It's
+/// stored uncompressed, so its contents are right after the local header.
+fn first_mimetype(bytes: &[u8]) -> Option<String> {
+    let header = bytes
+        .get(..30)
+        .filter(|h| h.starts_with(LOCAL_HEADER_SIG))?;
+    let method = u16_at(header, 8)?;
+    let data_len = u32_at(header, 18)? as usize;
+    let name_len = u16_at(header, 26)? as usize;
+    let extra_len = u16_at(header, 28)? as usize;
+    if method != 0 || data_len > MAX_MIMETYPE_LEN || bytes.get(30..30 + name_len)? != b"mimetype" {
+        return None;
+    }
+
+    let start = 30 + name_len + extra_len;
+    let contents = bytes.get(start..start + data_len)?;
+    String::from_utf8(contents.to_vec()).ok()
+}
+
+/// A format stored in a ZIP archive, and how to recognize its entries
+struct Container {
+    mime: Mime,
+    matches: fn(&Archive, Mime) -> bool,
+}
+
+impl Container {
+    const fn new(mime: Mime, matches: fn(&Archive, Mime) -> bool) -> Container {
+        Container { mime, matches }
+    }
+}
+
+/// Office Open XML documents keep their parts in a directory named after the
+/// application, next to a `[Content_Types].xml` listing them
+fn is_ooxml(archive: &Archive, mime: Mime) -> bool {
+    let dir = match mime {
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "word/",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xl/",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation" => "ppt/",
+        _ => return false,
+    };
+    archive.contains("[Content_Types].xml") && archive.contains_dir(dir)
+}
+
+/// OpenDocument files name their type in a `mimetype` entry
+fn is_odf(archive: &Archive, mime: Mime) -> bool {
+    archive
+        .mimetype
+        .as_deref()
+        .is_some_and(|m| canonical_odf(m) == mime)
+}
+
+/// Databases give the name their type is registered under in
+/// `shared-mime-info` as an alias
+fn canonical_odf(mime: &str) -> &str {
+    ODF_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == mime)
+        .map_or(mime, |(_, canonical)| canonical)
+}
+
+static ODF_ALIASES: &[(Mime, Mime)] = &[
+    (
+        "application/vnd.oasis.opendocument.base",
+        "application/vnd.oasis.opendocument.database",
+    ),
+    (
+        "application/vnd.sun.xml.base",
+        "application/vnd.oasis.opendocument.database",
+    ),
+];
+
+/// Java archives describe themselves in a manifest
+fn is_jar(archive: &Archive, _: Mime) -> bool {
+    archive.contains("META-INF/MANIFEST.MF")
+}
+
+/// EPUBs start with a `mimetype` entry, like OpenDocument files
+fn is_epub(archive: &Archive, mime: Mime) -> bool {
+    archive.mimetype.as_deref() == Some(mime)
+}
+
+/// Android packages have a binary manifest, and code or compiled resources
+fn is_apk(archive: &Archive, _: Mime) -> bool {
+    archive.contains("AndroidManifest.xml")
+        && (archive.contains("classes.dex") || archive.contains("resources.arsc"))
+}
+
+static CONTAINERS: &[Container] = &[
+    Container::new(
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        is_ooxml,
+    ),
+    Container::new(
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        is_ooxml,
+    ),
+    Container::new(
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        is_ooxml,
+    ),
+    Container::new("application/vnd.oasis.opendocument.text", is_odf),
+    Container::new("application/vnd.oasis.opendocument.text-template", is_odf),
+    Container::new("application/vnd.oasis.opendocument.text-web", is_odf),
+    Container::new("application/vnd.oasis.opendocument.text-master", is_odf),
+    Container::new("application/vnd.oasis.opendocument.spreadsheet", is_odf),
+    Container::new(
+        "application/vnd.oasis.opendocument.spreadsheet-template",
+        is_odf,
+    ),
+    Container::new("application/vnd.oasis.opendocument.presentation", is_odf),
+    Container::new(
+        "application/vnd.oasis.opendocument.presentation-template",
+        is_odf,
+    ),
+    Container::new("application/vnd.oasis.opendocument.graphics", is_odf),
+    Container::new(
+        "application/vnd.oasis.opendocument.graphics-template",
+        is_odf,
+    ),
+    Container::new("application/vnd.oasis.opendocument.chart", is_odf),
+    Container::new("application/vnd.oasis.opendocument.chart-template", is_odf),
+    Container::new("application/vnd.oasis.opendocument.formula", is_odf),
+    Container::new(
+        "application/vnd.oasis.opendocument.formula-template",
+        is_odf,
+    ),
+    Container::new("application/vnd.oasis.opendocument.image", is_odf),
+    Container::new("application/vnd.oasis.opendocument.database", is_odf),
+    Container::new("application/x-java-archive", is_jar),
+    Container::new("application/epub+zip", is_epub),
+    Container::new("application/vnd.android.package-archive", is_apk),
+];
+
+fn get_container(mimetype: &str) -> Option<&'static Container> {
+    CONTAINERS.iter().find(|c| c.mime == mimetype)
+}
@@ -0,0 +1,101 @@
+mod canonical_mime {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn alias_resolves() {
+        assert_eq!(
+            tree_magic::canonical_mime("application/x-zip-compressed"),
+            "application/zip"
+        );
+    }
+
+    #[test]
+    fn canonical_unchanged() {
+        assert_eq!(tree_magic::canonical_mime("image/png"), "image/png");
+    }
+
+    #[test]
+    fn unknown_unchanged() {
+        assert_eq!(
+            tree_magic::canonical_mime("application/x-does-not-exist"),
+            "application/x-does-not-exist"
+        );
+    }
+}
+
+mod is_alias {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn alias() {
+        assert!(tree_magic::is_alias("application/x-zip-compressed"));
+    }
+
+    #[test]
+    fn canonical() {
+        assert!(!tree_magic::is_alias("application/zip"));
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(!tree_magic::is_alias("application/x-does-not-exist"));
+    }
+}
+
+mod is_known {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn canonical() {
+        assert!(tree_magic::is_known("application/zip"));
+    }
+
+    #[test]
+    fn alias() {
+        assert!(tree_magic::is_known("application/x-zip-compressed"));
+    }
+
+    // Known types that just don't match
+    #[test]
+    fn no_match() {
+        assert!(tree_magic::is_known("image/png"));
+        assert!(!tree_magic::match_u8("image/png", b"GIF89a"));
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(!tree_magic::is_known("application/x-does-not-exist"));
+        assert!(!tree_magic::is_known(""));
+    }
+
+    #[test]
+    fn added_to_detector() {
+        let mut detector = tree_magic::Detector::new();
+        assert!(!detector.is_known("application/x-my-zip"));
+        detector.add_alias("application/x-my-zip", "application/zip");
+        assert!(detector.is_known("application/x-my-zip"));
+    }
+}
+
+mod aliases_of {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn canonical() {
+        let aliases = tree_magic::aliases_of("application/zip");
+        assert!(aliases.contains(&"application/x-zip-compressed"));
+        assert!(aliases
+            .iter()
+            .all(|a| tree_magic::canonical_mime(a) == "application/zip"));
+    }
+
+    #[test]
+    fn alias_is_not_canonical() {
+        assert!(tree_magic::aliases_of("application/x-zip-compressed").is_empty());
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(tree_magic::aliases_of("application/x-does-not-exist").is_empty());
+    }
+}
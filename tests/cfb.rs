@@ -0,0 +1,90 @@
+mod cfb {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    const CFB: &str = "application/x-ole-storage";
+    const FORMATS: [&str; 4] = [
+        "application/msword",
+        "application/vnd.ms-excel",
+        "application/vnd.ms-powerpoint",
+        "application/x-msi",
+    ];
+
+    fn path(mime: &str) -> std::path::PathBuf {
+        Path::new("tests/application").join(mime.strip_prefix("application/").unwrap())
+    }
+
+    #[test]
+    fn header_only() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-ole-storage")),
+            CFB
+        );
+        assert_eq!(tree_magic::from_filepath(&path(CFB)), Some(CFB));
+        assert_eq!(tree_magic::checker_for(CFB), Some("cfb"));
+    }
+
+    #[test]
+    fn from_filepath() {
+        for mime in FORMATS {
+            assert_eq!(tree_magic::from_filepath(&path(mime)), Some(mime));
+        }
+    }
+
+    #[test]
+    fn from_u8() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/msword")),
+            "application/msword"
+        );
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-msi")),
+            "application/x-msi"
+        );
+    }
+
+    // The directory is past the first 2048 bytes, so it's only found when
+    // the file is read
+    #[test]
+    fn late_directory() {
+        let bytes = std::fs::read("tests/application/vnd.ms-powerpoint").unwrap();
+        assert_eq!(tree_magic::from_u8(&bytes[..2048]), CFB);
+        assert_eq!(tree_magic::from_u8(&bytes), "application/vnd.ms-powerpoint");
+    }
+
+    // The directory goes on in a sector further along, found through the FAT
+    #[test]
+    fn directory_chain() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.ms-excel")),
+            "application/vnd.ms-excel"
+        );
+    }
+
+    // Only the root storage counts, not the ones inside it
+    #[test]
+    fn embedded_document() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-ole-storage-embedded")),
+            CFB
+        );
+    }
+
+    #[test]
+    fn subclass_of_cfb() {
+        for mime in FORMATS {
+            assert!(tree_magic::is_subclass(mime, CFB));
+        }
+    }
+
+    #[test]
+    fn corrupt() {
+        let mut bytes = include_bytes!("application/msword").to_vec();
+        // Point the directory past the end
+        bytes[0x30..0x34].copy_from_slice(&1000u32.to_le_bytes());
+        assert_eq!(tree_magic::from_u8(&bytes), CFB);
+
+        bytes[0x1e] = 7;
+        assert_ne!(tree_magic::from_u8(&bytes), CFB);
+    }
+}
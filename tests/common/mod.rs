@@ -0,0 +1,11 @@
+use std::path::Path;
+use tree_magic_mini::Detector;
+
+/// A detector with the magic file from `tests/database/<name>` added
+pub fn magic_detector(name: &str) -> Detector {
+    let mut detector = Detector::new();
+    detector
+        .add_magic_file(&Path::new("tests/database").join(name).join("magic"))
+        .unwrap();
+    detector
+}
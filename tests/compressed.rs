@@ -0,0 +1,146 @@
+#[cfg(feature = "decompress")]
+mod compressed {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn gzip_tar() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-compressed-tar")),
+            "application/x-compressed-tar"
+        );
+    }
+
+    #[test]
+    fn bzip2_tar() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-bzip-compressed-tar")),
+            "application/x-bzip-compressed-tar"
+        );
+    }
+
+    #[test]
+    fn xz_tar() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-xz-compressed-tar")),
+            "application/x-xz-compressed-tar"
+        );
+    }
+
+    #[test]
+    fn zstd_tar() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-zstd-compressed-tar")),
+            "application/x-zstd-compressed-tar"
+        );
+    }
+
+    // Only the start of the stream is needed, not its trailer
+    #[test]
+    fn cut_off() {
+        for (mime, bytes) in [
+            (
+                "application/x-compressed-tar",
+                &include_bytes!("application/x-compressed-tar")[..],
+            ),
+            (
+                "application/x-xz-compressed-tar",
+                &include_bytes!("application/x-xz-compressed-tar")[..],
+            ),
+        ] {
+            assert_eq!(tree_magic::from_u8(&bytes[..bytes.len() - 8]), mime);
+        }
+    }
+
+    #[test]
+    fn from_filepath() {
+        let path = Path::new("tests/application/x-compressed-tar");
+        assert_eq!(
+            tree_magic::from_filepath(path),
+            Some("application/x-compressed-tar")
+        );
+    }
+
+    #[test]
+    fn not_tar() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/gzip")),
+            "application/gzip"
+        );
+    }
+
+    // 10 MB of zeros, of which only the start is ever decompressed
+    #[test]
+    fn bomb() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/gzip-bomb")),
+            "application/gzip"
+        );
+    }
+
+    #[test]
+    fn subclasses() {
+        assert!(tree_magic::is_subclass(
+            "application/x-compressed-tar",
+            "application/gzip"
+        ));
+        assert!(tree_magic::is_subclass(
+            "application/x-xz-compressed-tar",
+            "application/x-xz"
+        ));
+        assert!(tree_magic::is_subclass(
+            "application/x-zstd-compressed-tar",
+            "application/zstd"
+        ));
+    }
+
+    // A header whose optional fields claim more bytes than there are
+    #[test]
+    fn truncated_header() {
+        let extra = b"\x1f\x8b\x08\x04\0\0\0\0\0\x03\xff\xff";
+        assert_eq!(tree_magic::from_u8(extra), "application/gzip");
+        let crc = b"\x1f\x8b\x08\x02\0\0\0\0\0\x03\x00";
+        assert_eq!(tree_magic::from_u8(crc), "application/gzip");
+    }
+}
+
+mod gzip_original_name {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn named() {
+        assert_eq!(
+            tree_magic::gzip_original_name(include_bytes!("application/gzip-named")).as_deref(),
+            Some("notes.txt")
+        );
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/gzip-named")),
+            "application/gzip"
+        );
+    }
+
+    #[test]
+    fn unnamed() {
+        assert_eq!(
+            tree_magic::gzip_original_name(include_bytes!("application/gzip")),
+            None
+        );
+    }
+
+    // The name comes after the extra field, and isn't UTF-8
+    #[test]
+    fn after_extra() {
+        let bytes = b"\x1f\x8b\x08\x0c\0\0\0\0\0\x03\x02\0abcaf\xe9.txt\0";
+        assert_eq!(
+            tree_magic::gzip_original_name(bytes).as_deref(),
+            Some("caf\u{e9}.txt")
+        );
+    }
+
+    #[test]
+    fn cut_off() {
+        let bytes = include_bytes!("application/gzip-named");
+        assert_eq!(tree_magic::gzip_original_name(&bytes[..15]), None);
+        assert_eq!(tree_magic::gzip_original_name(b"notes.txt\0"), None);
+    }
+}
@@ -0,0 +1,34 @@
+mod description {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn image_png() {
+        assert_eq!(tree_magic::description("image/png"), Some("PNG image"));
+    }
+
+    #[test]
+    fn application_pdf() {
+        assert_eq!(
+            tree_magic::description("application/pdf"),
+            Some("PDF document")
+        );
+    }
+
+    #[test]
+    fn resolves_aliases() {
+        assert_eq!(
+            tree_magic::description("application/x-zip-compressed"),
+            tree_magic::description("application/zip")
+        );
+        assert!(tree_magic::description("application/zip").is_some());
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(
+            tree_magic::description("application/x-does-not-exist"),
+            None
+        );
+        assert_eq!(tree_magic::description("../../etc/passwd"), None);
+    }
+}
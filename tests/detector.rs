@@ -0,0 +1,377 @@
+mod detector {
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::io::Read;
+    use std::path::Path;
+    use tree_magic_mini::{self as tree_magic, Checker, Detector};
+
+    #[test]
+    fn from_u8() {
+        let detector = Detector::new();
+        assert_eq!(detector.from_u8(include_bytes!("image/gif")), "image/gif");
+    }
+
+    #[test]
+    fn match_u8() {
+        let detector = Detector::new();
+        assert!(detector.match_u8("image/png", include_bytes!("image/png")));
+        assert!(!detector.match_u8("image/png", include_bytes!("image/gif")));
+    }
+
+    #[test]
+    fn from_file() {
+        let detector = Detector::new();
+        let file = File::open("tests/image/gif").unwrap();
+        assert_eq!(detector.from_file(&file), Some("image/gif"));
+    }
+
+    #[test]
+    fn from_filepath() {
+        let detector = Detector::new();
+        assert_eq!(
+            detector.from_filepath(Path::new("tests/hint/notes.md")),
+            Some("text/markdown")
+        );
+    }
+
+    #[test]
+    fn matches_free_functions() {
+        let detector = Detector::default();
+        assert_eq!(detector.supported_types(), tree_magic::supported_types());
+        assert_eq!(
+            detector.ancestors("text/plain"),
+            tree_magic::ancestors("text/plain")
+        );
+        assert_eq!(
+            detector.canonical_mime("application/x-zip-compressed"),
+            "application/zip"
+        );
+    }
+
+    #[test]
+    fn from_database_dir() {
+        let dir = Path::new("tests/database/custom");
+        let detector = Detector::from_database_dir(dir).unwrap();
+        assert_eq!(
+            detector.from_u8(b"TREEMAGIC data"),
+            "application/x-tree-magic"
+        );
+        assert_eq!(
+            detector.from_u8(b"TREEMAGIC2 data"),
+            "application/x-tree-magic-v2"
+        );
+        assert_eq!(detector.from_u8(b"plain text"), "text/plain");
+        assert!(detector.is_subclass("application/x-tree-magic-v2", "application/x-tmagic"));
+        assert!(!detector.supported_types().contains(&"image/gif"));
+    }
+
+    #[test]
+    fn from_database_dir_missing() {
+        let dir = Path::new("tests/database/missing");
+        assert!(matches!(
+            Detector::from_database_dir(dir),
+            Err(tree_magic::Error::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn from_database_dir_broken() {
+        let dir = Path::new("tests/database/broken");
+        assert!(matches!(
+            Detector::from_database_dir(dir),
+            Err(tree_magic::Error::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn add_magic_file() {
+        let mut detector = Detector::new();
+        detector
+            .add_magic_file(Path::new("tests/database/custom/magic"))
+            .unwrap();
+        assert_eq!(
+            detector.from_u8(b"TREEMAGIC data"),
+            "application/x-tree-magic"
+        );
+        // The rest of the database is still there
+        assert_eq!(detector.from_u8(include_bytes!("image/gif")), "image/gif");
+    }
+
+    #[test]
+    fn add_magic_file_errors() {
+        let mut detector = Detector::new();
+        assert!(matches!(
+            detector.add_magic_file(Path::new("tests/database/missing/magic")),
+            Err(tree_magic::Error::Io { .. })
+        ));
+        assert!(matches!(
+            detector.add_magic_file(Path::new("tests/database/broken/magic")),
+            Err(tree_magic::Error::Parse { .. })
+        ));
+        assert_eq!(
+            detector.supported_types(),
+            Detector::new().supported_types()
+        );
+    }
+
+    #[cfg(feature = "with-gpl-data")]
+    #[test]
+    fn embedded_plus_system() {
+        let detector = Detector::embedded_plus_system().unwrap();
+        let embedded = Detector::new();
+        let supported = detector.supported_types();
+        for mimetype in embedded.supported_types() {
+            // The system database may have renamed some of them, which leaves
+            // the old names as aliases of types that are still detected
+            let listed = if detector.is_alias(mimetype) {
+                detector.canonical_mime(mimetype)
+            } else {
+                mimetype
+            };
+            assert!(supported.contains(&listed), "{mimetype}");
+        }
+        assert_eq!(detector.from_u8(include_bytes!("image/gif")), "image/gif");
+    }
+
+    /// Recognizes a made-up format that extends ZIP
+    struct ZipExtension;
+
+    impl Checker for ZipExtension {
+        fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
+            mimetype == "application/x-zip-extension" && bytes.ends_with(b"EXTENDED")
+        }
+
+        fn match_file(&self, file: &File, mimetype: &str) -> bool {
+            let mut bytes = Vec::new();
+            (&*file).read_to_end(&mut bytes).is_ok() && self.match_bytes(&bytes, mimetype)
+        }
+
+        fn get_supported(&self) -> Vec<&'static str> {
+            vec!["application/x-zip-extension"]
+        }
+
+        fn get_subclasses(&self) -> Vec<(&'static str, &'static str)> {
+            vec![("application/zip", "application/x-zip-extension")]
+        }
+
+        fn get_aliaslist(&self) -> HashMap<&'static str, &'static str> {
+            HashMap::from([("application/x-zipext", "application/x-zip-extension")])
+        }
+    }
+
+    #[test]
+    fn add_checker() {
+        let mut detector = Detector::new();
+        detector.add_checker(ZipExtension);
+
+        let mut input = include_bytes!("application/zip").to_vec();
+        assert_eq!(detector.from_u8(&input), "application/zip");
+        input.extend(b"EXTENDED");
+        assert_eq!(detector.from_u8(&input), "application/x-zip-extension");
+        assert!(detector.match_u8("application/x-zipext", &input));
+        assert!(detector.is_subclass("application/x-zip-extension", "application/zip"));
+    }
+
+    /// `ZipExtension`, without saying what it's a subclass of
+    struct Unlinked;
+
+    impl Checker for Unlinked {
+        fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
+            ZipExtension.match_bytes(bytes, mimetype)
+        }
+
+        fn match_file(&self, file: &File, mimetype: &str) -> bool {
+            ZipExtension.match_file(file, mimetype)
+        }
+
+        fn get_supported(&self) -> Vec<&'static str> {
+            ZipExtension.get_supported()
+        }
+
+        fn get_subclasses(&self) -> Vec<(&'static str, &'static str)> {
+            Vec::new()
+        }
+
+        fn get_aliaslist(&self) -> HashMap<&'static str, &'static str> {
+            ZipExtension.get_aliaslist()
+        }
+    }
+
+    #[test]
+    fn add_subclass() {
+        let mut detector = Detector::new();
+        let mut input = include_bytes!("application/zip").to_vec();
+        input.extend(b"EXTENDED");
+
+        detector.add_checker(Unlinked);
+        assert_eq!(detector.from_u8(&input), "application/zip");
+        assert!(detector.add_subclass("application/x-zipext", "application/x-zip-compressed"));
+        assert_eq!(detector.from_u8(&input), "application/x-zip-extension");
+        assert!(detector.is_subclass("application/x-zip-extension", "application/zip"));
+        assert!(!detector.add_subclass("application/zip", "application/x-zip-extension"));
+    }
+
+    #[test]
+    fn add_subclass_unknown() {
+        let mut detector = Detector::new();
+        assert!(detector.add_subclass("application/x-unknown-child", "application/x-unknown"));
+        assert!(detector.is_subclass("application/x-unknown", "application/octet-stream"));
+        assert!(detector.is_subclass("application/x-unknown-child", "application/x-unknown"));
+        assert_eq!(
+            detector.ancestors("application/x-unknown-child"),
+            [
+                "all/all",
+                "all/allfiles",
+                "application/octet-stream",
+                "application/x-unknown"
+            ]
+        );
+        // The walk still starts at the same root
+        assert_eq!(detector.from_u8(include_bytes!("image/gif")), "image/gif");
+    }
+
+    #[test]
+    fn add_alias() {
+        let mut detector = Detector::new();
+        let zip = include_bytes!("application/zip");
+        detector.add_alias("application/x-my-zip", "application/x-zip-compressed");
+        assert!(detector.match_u8("application/x-my-zip", zip));
+        assert_eq!(
+            detector.canonical_mime("application/x-my-zip"),
+            "application/zip"
+        );
+
+        // Registering again replaces the old mapping
+        detector.add_alias("application/x-my-zip", "image/png");
+        assert!(!detector.match_u8("application/x-my-zip", zip));
+        assert!(detector.match_u8("application/x-my-zip", include_bytes!("image/png")));
+
+        // And it sticks around when the graph is rebuilt
+        detector.add_checker(ZipExtension);
+        assert_eq!(detector.canonical_mime("application/x-my-zip"), "image/png");
+    }
+
+    #[test]
+    fn deterministic() {
+        // Each detector's graph is built from differently seeded hash maps
+        let detectors: Vec<Detector> = (0..4).map(|_| Detector::new()).collect();
+        for dir in [
+            "tests/application",
+            "tests/audio",
+            "tests/image",
+            "tests/text",
+        ] {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let input = std::fs::read(entry.unwrap().path()).unwrap();
+                let first = detectors[0].from_u8(&input);
+                for detector in &detectors[1..] {
+                    assert_eq!(detector.from_u8(&input), first);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn set_type_order() {
+        let input = include_bytes!("application/x-mach-binary-fat");
+        let mut detector = Detector::new();
+        assert_eq!(detector.from_u8(input), "application/x-mach-binary");
+
+        // Also matches the lower priority Java class magic, if it's checked first
+        detector.set_type_order(&["application/x-java"]);
+        assert_eq!(detector.type_order(), ["application/x-java"]);
+        assert_eq!(detector.from_u8(input), "application/x-java");
+
+        detector.set_type_order(&[]);
+        assert_eq!(detector.from_u8(include_bytes!("image/gif")), "image/gif");
+    }
+
+    #[test]
+    fn retain_magic_only() {
+        let mut detector = Detector::new();
+        detector.retain_checkers(&["fdo_magic"]);
+        assert_eq!(detector.from_u8(include_bytes!("image/gif")), "image/gif");
+        assert_eq!(detector.from_u8(include_bytes!("image/png")), "image/png");
+        // Nothing tells text apart without basetype
+        assert_eq!(
+            detector.from_u8(b"Hello, world!"),
+            "application/octet-stream"
+        );
+        assert!(!detector.match_u8("text/plain", b"Hello, world!"));
+        let file = File::open("tests/image/gif").unwrap();
+        assert_eq!(detector.from_file(&file), Some("image/gif"));
+    }
+
+    #[test]
+    fn retain_basetype_only() {
+        let mut detector = Detector::new();
+        detector.retain_checkers(&["basetype"]);
+        assert_eq!(detector.from_u8(b"Hello, world!"), "text/plain");
+        assert_eq!(
+            detector.from_u8(include_bytes!("image/gif")),
+            "application/octet-stream"
+        );
+        assert!(!detector.is_known("image/gif"));
+    }
+
+    #[test]
+    fn retain_keeps_additions() {
+        let mut detector = Detector::new();
+        detector.add_alias("application/x-my-gif", "image/gif");
+        detector.retain_checkers(&["fdo_magic", "basetype"]);
+        assert!(detector.match_u8("application/x-my-gif", include_bytes!("image/gif")));
+        // JSON is only found by the text heuristics
+        assert_ne!(detector.from_u8(b"{\"a\": [1, 2]}"), "application/json");
+    }
+
+    #[test]
+    fn retain_nothing() {
+        let mut detector = Detector::new();
+        detector.retain_checkers(&[]);
+        assert!(detector.supported_types().is_empty());
+        assert_eq!(
+            detector.try_from_u8(b"data"),
+            Some("application/octet-stream")
+        );
+    }
+}
+
+mod database_stats {
+    use std::path::Path;
+    use tree_magic_mini::{self as tree_magic, DatabaseSource, Detector};
+
+    #[test]
+    fn default_database() {
+        let stats = tree_magic::database_stats();
+        assert_eq!(stats, Detector::new().database_stats());
+        assert_eq!(stats.types, tree_magic::descendants("all/all").len() + 1);
+        assert!(stats.subclasses >= stats.types - 1);
+        assert!(stats.aliases > 0);
+        #[cfg(feature = "with-gpl-data")]
+        assert_eq!(stats.source, DatabaseSource::Embedded);
+        #[cfg(not(feature = "with-gpl-data"))]
+        assert_eq!(stats.source, DatabaseSource::System);
+    }
+
+    #[test]
+    fn directory() {
+        let detector = Detector::from_database_dir(Path::new("tests/database/custom")).unwrap();
+        let stats = detector.database_stats();
+        assert_eq!(stats.source, DatabaseSource::Directory);
+        assert!(stats.types < Detector::new().database_stats().types);
+    }
+
+    #[test]
+    fn after_changes() {
+        let mut detector = Detector::new();
+        let before = detector.database_stats();
+        detector.add_alias("image/x-my-gif", "image/gif");
+        detector.add_subclass("image/gif", "application/x-my-parent");
+        let after = detector.database_stats();
+        assert_eq!(after.aliases, before.aliases + 1);
+        assert!(after.subclasses > before.subclasses);
+
+        detector.retain_checkers(&["fdo_magic", "basetype"]);
+        assert_eq!(detector.database_stats().source, before.source);
+    }
+}
@@ -0,0 +1,77 @@
+mod ebml {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    /// Builds an EBML header with the given `DocType` element, followed by
+    /// the start of a segment
+    fn header(doc_type: &[u8]) -> Vec<u8> {
+        let mut body = vec![0x42, 0x86, 0x81, 0x01];
+        body.extend_from_slice(doc_type);
+        body.extend_from_slice(&[0x42, 0x87, 0x81, 0x04]);
+        let mut bytes = vec![0x1A, 0x45, 0xDF, 0xA3, 0x80 | body.len() as u8];
+        bytes.extend(body);
+        bytes.extend_from_slice(&[0x18, 0x53, 0x80, 0x67, 0x01, 0xFF, 0xFF, 0xFF]);
+        bytes
+    }
+
+    #[test]
+    fn matroska() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("video/x-matroska")),
+            "video/x-matroska"
+        );
+        assert!(tree_magic::is_subclass(
+            "video/x-matroska",
+            "application/x-matroska"
+        ));
+    }
+
+    #[test]
+    fn webm() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("video/webm")),
+            "video/webm"
+        );
+    }
+
+    #[test]
+    fn from_filepath() {
+        for mime in ["video/x-matroska", "video/webm"] {
+            let path = Path::new("tests").join(mime);
+            assert_eq!(tree_magic::from_filepath(&path), Some(mime));
+        }
+    }
+
+    // Sizes can take more bytes than they need, with leading zeros
+    #[test]
+    fn long_sizes() {
+        let doc_type = b"\x42\x82\x01\0\0\0\0\0\0\x04webm";
+        assert_eq!(tree_magic::from_u8(&header(doc_type)), "video/webm");
+        let doc_type = b"\x42\x82\x40\x08matroska";
+        assert_eq!(tree_magic::from_u8(&header(doc_type)), "video/x-matroska");
+    }
+
+    #[test]
+    fn padded_doc_type() {
+        let doc_type = b"\x42\x82\x86webm\0\0";
+        assert_eq!(tree_magic::from_u8(&header(doc_type)), "video/webm");
+    }
+
+    #[test]
+    fn other_doc_type() {
+        let input = header(b"\x42\x82\x84mkvx");
+        assert!(!tree_magic::match_u8("video/webm", &input));
+        assert!(!tree_magic::match_u8("application/x-matroska", &input));
+    }
+
+    // Input that ends before the whole DocType doesn't match
+    #[test]
+    fn truncated() {
+        let input = include_bytes!("video/webm");
+        let doc_type_end = input.windows(4).position(|w| w == b"webm").unwrap() + 4;
+        for len in 0..doc_type_end {
+            assert_ne!(tree_magic::from_u8(&input[..len]), "video/webm");
+        }
+        assert_eq!(tree_magic::from_u8(&input[..doc_type_end]), "video/webm");
+    }
+}
@@ -0,0 +1,149 @@
+mod explain {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn magic_rule() {
+        let explanation = tree_magic::explain("image/png", include_bytes!("image/png")).unwrap();
+        assert_eq!(explanation.mimetype, "image/png");
+        assert_eq!(explanation.checker, "fdo_magic");
+        assert_eq!(explanation.rules[0].offset, 0);
+        assert_eq!(explanation.rules[0].pattern, b"\x89PNG");
+    }
+
+    // One of the BMP rules is BM at the start with a header size after it
+    #[test]
+    fn nested_rules() {
+        let input = b"BM\x11\x22\x33\x44\x01\x01\0\0\0\0\0\0\x28\0\0\0";
+        let explanation = tree_magic::explain("image/bmp", input).unwrap();
+        let found: Vec<(usize, &[u8])> = explanation
+            .rules
+            .iter()
+            .map(|r| (r.offset, &r.pattern[..]))
+            .collect();
+        assert_eq!(found, [(0, &b"BM"[..]), (14, b"\x28")]);
+    }
+
+    #[test]
+    fn signature() {
+        let explanation =
+            tree_magic::explain("application/wasm", include_bytes!("application/wasm")).unwrap();
+        assert_eq!(explanation.checker, "signature");
+        assert_eq!(explanation.rules.len(), 1);
+        assert_eq!(explanation.rules[0].pattern, b"\0asm");
+    }
+
+    #[test]
+    fn without_rules() {
+        let explanation = tree_magic::explain("application/json", b"{\"a\": 1}").unwrap();
+        assert_eq!(explanation.checker, "text");
+        assert!(explanation.rules.is_empty());
+    }
+
+    #[test]
+    fn alias() {
+        let explanation = tree_magic::explain(
+            "application/x-zip-compressed",
+            include_bytes!("application/zip"),
+        )
+        .unwrap();
+        assert_eq!(explanation.mimetype, "application/zip");
+    }
+
+    #[test]
+    fn no_match() {
+        assert!(tree_magic::explain("image/png", include_bytes!("image/gif")).is_none());
+        assert!(tree_magic::explain("image/x-unknown", include_bytes!("image/gif")).is_none());
+    }
+}
+
+mod explain_detection {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn chain() {
+        let explanation = tree_magic::explain_detection(include_bytes!("image/gif")).unwrap();
+        assert_eq!(
+            explanation.chain,
+            [
+                "all/all",
+                "all/allfiles",
+                "application/octet-stream",
+                "image/gif"
+            ]
+        );
+        assert_eq!(explanation.leaf.mimetype, "image/gif");
+        assert_eq!(explanation.leaf.rules[0].pattern, b"GIF8");
+    }
+
+    #[test]
+    fn agrees_with_from_u8() {
+        let inputs: [&[u8]; 6] = [
+            include_bytes!("image/png"),
+            include_bytes!("audio/wav"),
+            include_bytes!("application/zip"),
+            include_bytes!("text/plain"),
+            b"{\"a\": 1}",
+            b"",
+        ];
+        for input in inputs {
+            let explanation = tree_magic::explain_detection(input).unwrap();
+            assert_eq!(explanation.leaf.mimetype, tree_magic::from_u8(input));
+            assert_eq!(explanation.chain.last(), Some(&explanation.leaf.mimetype));
+            assert!(explanation
+                .chain
+                .windows(2)
+                .all(|w| tree_magic::is_subclass(w[1], w[0])));
+        }
+    }
+}
+
+mod checker_for {
+    use tree_magic_mini::{self as tree_magic, Detector};
+
+    #[test]
+    fn builtin() {
+        for (mime, checker) in [
+            ("image/gif", "fdo_magic"),
+            ("text/plain", "basetype"),
+            ("application/zip", "signature"),
+            ("application/json", "text"),
+            ("video/webm", "ebml"),
+        ] {
+            assert_eq!(tree_magic::checker_for(mime), Some(checker), "{mime}");
+        }
+    }
+
+    #[test]
+    fn alias() {
+        assert_eq!(
+            tree_magic::checker_for("application/x-zip-compressed"),
+            tree_magic::checker_for("application/zip")
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(
+            tree_magic::checker_for("application/x-does-not-exist"),
+            None
+        );
+    }
+
+    #[test]
+    fn agrees_with_explain() {
+        let bytes = include_bytes!("image/png");
+        let explanation = tree_magic::explain("image/png", bytes).unwrap();
+        assert_eq!(
+            tree_magic::checker_for("image/png"),
+            Some(explanation.checker)
+        );
+    }
+
+    #[test]
+    fn retained() {
+        let mut detector = Detector::new();
+        detector.retain_checkers(&["fdo_magic"]);
+        assert_eq!(detector.checker_for("image/gif"), Some("fdo_magic"));
+        assert_eq!(detector.checker_for("inode/directory"), None);
+    }
+}
@@ -0,0 +1,249 @@
+mod common;
+
+mod from_file_with_buffer {
+    use std::fs::File;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn magic_past_default_buffer() {
+        let file = File::open("tests/application/vnd.stardivision.writer").unwrap();
+        assert_eq!(
+            tree_magic::from_file_with_buffer(&file, 4096),
+            Some("application/vnd.stardivision.writer")
+        );
+    }
+
+    #[test]
+    fn buffer_too_short_for_magic() {
+        let file = File::open("tests/application/vnd.stardivision.writer").unwrap();
+        assert_eq!(
+            tree_magic::from_file_with_buffer(&file, 1024),
+            Some("application/octet-stream")
+        );
+    }
+}
+
+mod from_file {
+    use std::fs::File;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn magic_past_2048_bytes() {
+        let file = File::open("tests/application/vnd.stardivision.writer").unwrap();
+        assert_eq!(
+            tree_magic::from_file(&file),
+            Some("application/vnd.stardivision.writer")
+        );
+    }
+}
+
+mod from_file_thorough {
+    use crate::common::magic_detector;
+    use std::fs::File;
+    use std::io::{Seek, SeekFrom, Write};
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn magic_past_max_buffer() {
+        let detector = magic_detector("deep");
+
+        let path =
+            std::env::temp_dir().join(format!("tree_magic_mini-thorough-{}", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        file.write_all(&vec![0; 100_000]).unwrap();
+        file.write_all(b"DEEPMAGIC").unwrap();
+
+        let file = File::open(&path).unwrap();
+        assert_eq!(detector.from_file(&file), Some("application/octet-stream"));
+        (&file).seek(SeekFrom::Start(0)).unwrap();
+        assert_eq!(
+            detector.from_file_thorough(&file),
+            Some("application/x-deep-magic")
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn found_on_first_pass() {
+        let file = File::open("tests/application/vnd.stardivision.writer").unwrap();
+        assert_eq!(
+            tree_magic::from_file_thorough(&file),
+            Some("application/vnd.stardivision.writer")
+        );
+    }
+}
+
+mod trailer {
+    use std::fs::File;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn prefixed_zip_from_file() {
+        let file = File::open("tests/application/zip-prefixed").unwrap();
+        assert_eq!(tree_magic::from_file(&file), Some("application/zip"));
+    }
+
+    #[test]
+    fn prefixed_zip_match_file() {
+        let file = File::open("tests/application/zip-prefixed").unwrap();
+        assert!(tree_magic::match_file("application/zip", &file));
+    }
+
+    /// Writes `bytes` to a file of its own and detects it
+    fn from_temp_file(name: &str, bytes: &[u8]) -> Option<&'static str> {
+        let path = std::env::temp_dir().join(format!(
+            "tree_magic_mini-trailer-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::write(&path, bytes).unwrap();
+        let mime = tree_magic::from_file(&File::open(&path).unwrap());
+        std::fs::remove_file(path).unwrap();
+        mime
+    }
+
+    #[test]
+    fn stray_end_record_before_end() {
+        // A ZIP's end record inside something else, like the last member of
+        // a tar, is followed by more than its comment
+        let mut bytes = vec![0x80; 100];
+        bytes.extend_from_slice(b"PK\x05\x06\0\0\0\0\x01\0\x01\0\x2e\0\0\0\x10\0\0\0\0\0");
+        bytes.extend_from_slice(&[0x80; 512]);
+        assert_eq!(
+            from_temp_file("stray", &bytes),
+            Some("application/octet-stream")
+        );
+    }
+
+    #[test]
+    fn end_record_without_central_directory() {
+        let mut bytes = vec![0x80; 100];
+        bytes.extend_from_slice(b"PK\x05\x06\0\0\0\0\x01\0\x01\0\x2e\0\0\0\x10\0\0\0\0\0");
+        assert_eq!(
+            from_temp_file("no-dir", &bytes),
+            Some("application/octet-stream")
+        );
+    }
+
+    #[test]
+    fn end_record_with_wrong_comment_length() {
+        let mut bytes = include_bytes!("application/zip-prefixed").to_vec();
+        bytes.extend_from_slice(b"trailing");
+        assert_eq!(
+            from_temp_file("comment", &bytes),
+            Some("application/octet-stream")
+        );
+    }
+
+    #[test]
+    fn empty_zip() {
+        assert_eq!(
+            from_temp_file("empty", b"PK\x05\x06\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0"),
+            Some("application/zip")
+        );
+    }
+
+    #[test]
+    fn prefixed_zip_bytes_ignore_trailer() {
+        assert!(!tree_magic::match_u8(
+            "application/zip",
+            include_bytes!("application/zip-prefixed")
+        ));
+    }
+}
+
+mod from_file_base_types {
+    use std::fs::File;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn directory() {
+        let file = File::open("tests/image").unwrap();
+        assert_eq!(tree_magic::from_file(&file), Some("inode/directory"));
+    }
+
+    #[test]
+    fn regular_file() {
+        let file = File::open("tests/text/plain").unwrap();
+        assert_eq!(tree_magic::from_file(&file), Some("text/plain"));
+    }
+
+    #[test]
+    fn empty_file() {
+        let file = File::open("tests/application/x-empty").unwrap();
+        assert_eq!(tree_magic::from_file(&file), Some("application/x-empty"));
+        assert_eq!(
+            tree_magic::from_filepath(std::path::Path::new("tests/application/x-empty")),
+            Some("application/x-empty")
+        );
+        let file = File::open("tests/application/x-empty").unwrap();
+        assert!(tree_magic::match_file("application/x-empty", &file));
+    }
+}
+
+// Bytes read are counted by the kernel for each thread
+#[cfg(target_os = "linux")]
+mod bytes_read {
+    use std::fs::File;
+    use std::path::PathBuf;
+    use tree_magic_mini as tree_magic;
+
+    /// Largest buffer read from the start of a file
+    const MAX_BUFFER_LEN: u64 = 32 * 1024;
+
+    /// Longest ZIP end of central directory record
+    const ZIP_END_LEN: u64 = 22 + 0xFFFF;
+
+    /// Some slack for reading the counter itself
+    const SLACK: u64 = 1024;
+
+    fn bytes_read() -> u64 {
+        let io = std::fs::read_to_string("/proc/thread-self/io").unwrap();
+        io.lines()
+            .find_map(|line| line.strip_prefix("rchar:"))
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap()
+    }
+
+    /// Writes a megabyte file and counts what detecting it reads
+    fn read_for(name: &str, byte: u8) -> (u64, u64) {
+        // Load the database first, so none of it is counted
+        tree_magic::from_u8(b"");
+
+        let path: PathBuf = std::env::temp_dir().join(format!(
+            "tree_magic_mini-read-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::write(&path, vec![byte; 1024 * 1024]).unwrap();
+        let file = File::open(&path).unwrap();
+
+        let before = bytes_read();
+        tree_magic::from_file(&file);
+        let detect = bytes_read() - before;
+
+        let before = bytes_read();
+        tree_magic::match_file("application/zip", &file);
+        let zip = bytes_read() - before;
+
+        std::fs::remove_file(path).unwrap();
+        (detect, zip)
+    }
+
+    // The end is only looked at for ZIP archives in binary files
+    #[test]
+    fn text() {
+        let (detect, zip) = read_for("text", b'a');
+        assert!(detect <= MAX_BUFFER_LEN + SLACK, "{detect}");
+        assert!(zip <= SLACK, "{zip}");
+    }
+
+    #[test]
+    fn binary() {
+        let (detect, zip) = read_for("binary", 0);
+        assert!(detect <= MAX_BUFFER_LEN + ZIP_END_LEN + SLACK, "{detect}");
+        assert!(zip <= ZIP_END_LEN + SLACK, "{zip}");
+    }
+}
@@ -1,3 +1,5 @@
+mod common;
+
 mod from_filepath {
     use std::path::Path;
     use tree_magic_mini as tree_magic;
@@ -9,4 +11,319 @@ mod from_filepath {
             None
         );
     }
+
+    #[test]
+    fn uses_file_name() {
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/hint/notes.md")),
+            Some("text/markdown")
+        );
+    }
+
+    #[test]
+    fn trailing_magic() {
+        let dir = Path::new("tests/application");
+        for mime in [
+            "application/vnd.apache.parquet",
+            "application/vnd.apache.arrow.file",
+        ] {
+            let name = mime.strip_prefix("application/").unwrap();
+            assert_eq!(tree_magic::from_filepath(&dir.join(name)), Some(mime));
+        }
+        // Without the footer, it's been cut off
+        assert_eq!(
+            tree_magic::from_filepath(&dir.join("vnd.apache.parquet-truncated")),
+            Some("application/octet-stream")
+        );
+    }
+
+    #[test]
+    fn contents_win() {
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/hint/picture.png")),
+            Some("image/gif")
+        );
+    }
+}
+
+mod from_filepath_with_hint {
+    use crate::common::magic_detector;
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn refines_text_plain() {
+        assert_eq!(
+            tree_magic::from_filepath_with_hint(Path::new("tests/hint/notes.md")),
+            Some("text/markdown")
+        );
+    }
+
+    #[test]
+    fn refines_zip() {
+        assert_eq!(
+            tree_magic::from_filepath_with_hint(Path::new("tests/hint/archive.jar")),
+            Some("application/x-java-archive")
+        );
+    }
+
+    #[test]
+    fn does_not_override_magic() {
+        assert_eq!(
+            tree_magic::from_filepath_with_hint(Path::new("tests/hint/picture.png")),
+            Some("image/gif")
+        );
+    }
+
+    // The database gives andrew-inset (*.ez) and oda (*.oda) the same rule at
+    // priority 50, and pkcs10 (*.p10) the same rule at 40
+    #[test]
+    fn breaks_ties() {
+        let detector = magic_detector("tie");
+        let dir = std::env::temp_dir().join(format!("tree_magic_mini-tie-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir(&dir).unwrap();
+        for (name, mime) in [
+            ("file.ez", "application/andrew-inset"),
+            ("file.oda", "application/oda"),
+            ("file.p10", "application/andrew-inset"),
+            ("file", "application/andrew-inset"),
+        ] {
+            let path = dir.join(name);
+            std::fs::write(&path, b"TIE").unwrap();
+            assert_eq!(detector.from_filepath_with_hint(&path), Some(mime));
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    // Also matches application/x-java, but with less certain rules
+    #[test]
+    fn keeps_more_certain_match() {
+        let path = std::env::temp_dir().join(format!(
+            "tree_magic_mini-certain-{}.class",
+            std::process::id()
+        ));
+        std::fs::copy("tests/application/x-mach-binary-fat", &path).unwrap();
+        assert_eq!(
+            tree_magic::from_filepath_with_hint(&path),
+            Some("application/x-mach-binary")
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn no_extension() {
+        assert_eq!(
+            tree_magic::from_filepath_with_hint(Path::new("tests/image/gif")),
+            Some("image/gif")
+        );
+    }
+
+    #[test]
+    fn nonexistent_file_returns_none() {
+        assert_eq!(
+            tree_magic::from_filepath_with_hint(Path::new("this/file/does/not/exist")),
+            None
+        );
+    }
+}
+
+mod from_filename {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn extension() {
+        assert_eq!(tree_magic::from_filename("notes.md"), Some("text/markdown"));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        assert_eq!(tree_magic::from_filename("IMAGE.PNG"), Some("image/png"));
+    }
+
+    #[test]
+    fn longest_pattern_wins() {
+        assert_eq!(
+            tree_magic::from_filename("backup.tar.gz"),
+            Some("application/x-compressed-tar")
+        );
+        assert_eq!(
+            tree_magic::from_filename("backup.gz"),
+            Some("application/gzip")
+        );
+    }
+
+    #[test]
+    fn ignores_directories() {
+        assert_eq!(
+            tree_magic::from_filename("some.png/main.rs"),
+            Some("text/rust")
+        );
+    }
+
+    #[test]
+    fn no_match() {
+        assert_eq!(tree_magic::from_filename("no_extension_here"), None);
+    }
+}
+
+mod from_filepath_result {
+    use std::io::ErrorKind;
+    use std::path::Path;
+    use tree_magic_mini::{self as tree_magic, DetectError};
+
+    #[test]
+    fn found() {
+        let result = tree_magic::from_filepath_result(Path::new("tests/hint/notes.md"));
+        assert_eq!(result.unwrap(), "text/markdown");
+    }
+
+    #[test]
+    fn directory() {
+        let result = tree_magic::from_filepath_result(Path::new("tests/hint"));
+        assert_eq!(result.unwrap(), "inode/directory");
+    }
+
+    #[test]
+    fn missing() {
+        let result = tree_magic::from_filepath_result(Path::new("tests/hint/missing"));
+        match result {
+            Err(DetectError::Io(e)) => assert_eq!(e.kind(), ErrorKind::NotFound),
+            other => panic!("expected an I/O error, got {other:?}"),
+        }
+    }
+}
+
+#[cfg(unix)]
+mod from_filepath_nofollow {
+    use std::os::unix::net::UnixListener;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use tree_magic_mini as tree_magic;
+
+    /// An empty directory of its own for each test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("tree_magic_mini-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn regular_file() {
+        assert_eq!(
+            tree_magic::from_filepath_nofollow(Path::new("tests/image/gif")),
+            Some("image/gif")
+        );
+    }
+
+    #[test]
+    fn symlink() {
+        let dir = scratch_dir("symlink");
+        let link = dir.join("link");
+        std::os::unix::fs::symlink(std::fs::canonicalize("tests/image/gif").unwrap(), &link)
+            .unwrap();
+        assert_eq!(
+            tree_magic::from_filepath_nofollow(&link),
+            Some("inode/symlink")
+        );
+        // Everything else follows the link
+        assert_eq!(tree_magic::from_filepath(&link), Some("image/gif"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    // Opening a FIFO with no writer would block
+    #[test]
+    fn fifo() {
+        let dir = scratch_dir("fifo");
+        let fifo = dir.join("fifo");
+        assert!(Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap()
+            .success());
+        assert_eq!(
+            tree_magic::from_filepath_nofollow(&fifo),
+            Some("inode/fifo")
+        );
+        assert_eq!(tree_magic::from_filepath(&fifo), Some("inode/fifo"));
+        assert!(tree_magic::match_filepath("inode/fifo", &fifo));
+        assert!(!tree_magic::match_filepath("text/plain", &fifo));
+        assert_eq!(
+            tree_magic::match_filepath_any(&["text/plain", "inode/fifo"], &fifo),
+            Some("inode/fifo")
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn socket() {
+        let dir = scratch_dir("socket");
+        let socket = dir.join("socket");
+        let _listener = UnixListener::bind(&socket).unwrap();
+        assert_eq!(
+            tree_magic::from_filepath_nofollow(&socket),
+            Some("inode/socket")
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn devices() {
+        assert_eq!(
+            tree_magic::from_filepath_nofollow(Path::new("/dev/null")),
+            Some("inode/chardevice")
+        );
+        assert!(tree_magic::is_subclass("inode/blockdevice", "all/all"));
+    }
+
+    #[test]
+    fn directory() {
+        assert_eq!(
+            tree_magic::from_filepath_nofollow(Path::new("tests/image")),
+            Some("inode/directory")
+        );
+    }
+}
+
+mod from_filepaths {
+    use std::path::PathBuf;
+    use tree_magic_mini as tree_magic;
+
+    fn paths() -> Vec<PathBuf> {
+        [
+            "image/gif",
+            "image/png",
+            "hint/notes.md",
+            "missing",
+            "image/bmp",
+        ]
+        .iter()
+        .map(|name| PathBuf::from("tests").join(name))
+        .collect()
+    }
+
+    #[test]
+    fn in_order() {
+        let paths = paths();
+        let result = tree_magic::from_filepaths(&paths);
+        assert_eq!(result.len(), paths.len());
+        for ((path, mime), expected) in result.iter().zip(&paths) {
+            assert_eq!(path, expected);
+            assert_eq!(*mime, tree_magic::from_filepath(path));
+        }
+        assert_eq!(result[3].1, None);
+    }
+
+    #[test]
+    fn parallel() {
+        // More paths than threads, so each thread gets several
+        let paths: Vec<PathBuf> = paths().into_iter().cycle().take(100).collect();
+        assert_eq!(
+            tree_magic::from_filepaths_parallel(&paths),
+            tree_magic::from_filepaths(&paths)
+        );
+        assert!(tree_magic::from_filepaths_parallel(&[]).is_empty());
+    }
 }
@@ -0,0 +1,91 @@
+mod from_read {
+    use std::io::Read;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn image_png() {
+        let mut input: &[u8] = include_bytes!("image/png");
+        assert_eq!(tree_magic::from_read(&mut input), Some("image/png"));
+    }
+
+    #[test]
+    fn leaves_remainder_unread() {
+        let mut data = include_bytes!("image/png").to_vec();
+        data.resize(4096, 0);
+
+        let mut input: &[u8] = &data;
+        assert_eq!(tree_magic::from_read(&mut input), Some("image/png"));
+
+        let mut rest = Vec::new();
+        input.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, &data[2048..]);
+    }
+}
+
+mod match_reader {
+    use std::io::Read;
+    use tree_magic_mini as tree_magic;
+
+    /// A StarWriter file's magic, which is found past the first 2048 bytes
+    fn star_writer() -> Vec<u8> {
+        let mut data = vec![0; 2089];
+        data.extend_from_slice(b"StarWriter");
+        data.resize(4096, 0);
+        data
+    }
+
+    #[test]
+    fn image_png() {
+        let mut input: &[u8] = include_bytes!("image/png");
+        assert!(tree_magic::match_reader("image/png", &mut input));
+
+        let mut input: &[u8] = include_bytes!("image/png");
+        assert!(!tree_magic::match_reader("image/gif", &mut input));
+    }
+
+    #[test]
+    fn alias() {
+        let mut input: &[u8] = include_bytes!("application/zip");
+        assert!(tree_magic::match_reader(
+            "application/x-zip-compressed",
+            &mut input
+        ));
+    }
+
+    #[test]
+    fn reads_more_on_demand() {
+        let data = star_writer();
+        assert!(!tree_magic::match_u8(
+            "application/vnd.stardivision.writer",
+            &data[..2048]
+        ));
+
+        let mut input: &[u8] = &data;
+        assert!(tree_magic::match_reader(
+            "application/vnd.stardivision.writer",
+            &mut input
+        ));
+    }
+
+    #[test]
+    fn leaves_remainder_unread() {
+        let mut data = include_bytes!("image/png").to_vec();
+        data.resize(4096, 0);
+
+        let mut input: &[u8] = &data;
+        assert!(tree_magic::match_reader("image/png", &mut input));
+
+        let mut rest = Vec::new();
+        input.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, &data[2048..]);
+    }
+
+    #[test]
+    fn unknown_type() {
+        let mut input: &[u8] = include_bytes!("image/png");
+        assert!(!tree_magic::match_reader(
+            "image/x-does-not-exist",
+            &mut input
+        ));
+    }
+}
@@ -1,3 +1,5 @@
+mod common;
+
 mod from_u8 {
     use tree_magic_mini as tree_magic;
 
@@ -82,6 +84,316 @@ mod from_u8 {
         );
     }
 
+    #[test]
+    fn application_zstd() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/zstd")),
+            convmime!("application/zstd")
+        );
+        assert!(tree_magic::is_subclass(
+            "application/zstd",
+            "application/octet-stream"
+        ));
+    }
+    #[test]
+    fn application_lz4() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-lz4")),
+            convmime!("application/x-lz4")
+        );
+    }
+
+    /// Font tests
+    #[test]
+    fn font_ttf() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("font/ttf")),
+            convmime!("font/ttf")
+        );
+    }
+    #[test]
+    fn font_otf() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("font/otf")),
+            convmime!("font/otf")
+        );
+    }
+    #[test]
+    fn font_woff() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("font/woff")),
+            convmime!("font/woff")
+        );
+    }
+    #[test]
+    fn font_woff2() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("font/woff2")),
+            convmime!("font/woff2")
+        );
+    }
+    #[test]
+    fn font_tag_in_text() {
+        for text in [
+            &b"true story\nit happened\n"[..],
+            b"true",
+            b"OTTO was here\n",
+            b"\0\x01\0\0 and then some text\n",
+        ] {
+            let mime = tree_magic::from_u8(text);
+            assert_ne!(mime, convmime!("font/ttf"), "{:?}", text);
+            assert_ne!(mime, convmime!("font/otf"), "{:?}", text);
+        }
+    }
+    #[test]
+    fn font_bad_table_directory() {
+        let mut bytes = include_bytes!("font/ttf").to_vec();
+        // Ten tables have a search range of 128, not 64
+        bytes[6..8].copy_from_slice(&[0, 0x40]);
+        assert_ne!(tree_magic::from_u8(&bytes), convmime!("font/ttf"));
+
+        let mut bytes = include_bytes!("font/otf").to_vec();
+        bytes[4..6].copy_from_slice(&[0, 0]);
+        assert_ne!(tree_magic::from_u8(&bytes), convmime!("font/otf"));
+    }
+
+    /// Binary tests
+    #[test]
+    fn application_wasm() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/wasm")),
+            convmime!("application/wasm")
+        );
+    }
+    #[test]
+    fn application_sqlite() {
+        let header = include_bytes!("application/vnd.sqlite3");
+        assert_eq!(
+            tree_magic::from_u8(header),
+            convmime!("application/vnd.sqlite3")
+        );
+        assert!(tree_magic::match_u8("application/x-sqlite3", header));
+    }
+
+    #[test]
+    fn application_pcap() {
+        let header = include_bytes!("application/vnd.tcpdump.pcap");
+        assert_eq!(
+            tree_magic::from_u8(header),
+            convmime!("application/vnd.tcpdump.pcap")
+        );
+        assert!(tree_magic::match_u8("application/x-pcap", header));
+
+        // Big-endian, and both orders of the nanosecond magic
+        let mut other = header.to_vec();
+        for magic in [
+            b"\xa1\xb2\xc3\xd4",
+            b"\xa1\xb2\x3c\x4d",
+            b"\x4d\x3c\xb2\xa1",
+        ] {
+            other[..4].copy_from_slice(magic);
+            assert_eq!(tree_magic::from_u8(&other), "application/vnd.tcpdump.pcap");
+        }
+    }
+
+    #[test]
+    fn application_pcapng() {
+        let header = include_bytes!("application/x-pcapng");
+        assert_eq!(
+            tree_magic::from_u8(header),
+            convmime!("application/x-pcapng")
+        );
+
+        let mut big_endian = header.to_vec();
+        big_endian[8..12].copy_from_slice(b"\x1a\x2b\x3c\x4d");
+        assert_eq!(tree_magic::from_u8(&big_endian), "application/x-pcapng");
+
+        // The block type alone isn't enough
+        let mut garbled = header.to_vec();
+        garbled[8..12].copy_from_slice(b"\x1a\x2b\x3c\x4e");
+        assert_ne!(tree_magic::from_u8(&garbled), "application/x-pcapng");
+    }
+
+    // Only the magic at the start can be checked here
+    #[test]
+    fn application_parquet() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.apache.parquet-truncated")),
+            convmime!("application/vnd.apache.parquet")
+        );
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.apache.arrow.file")),
+            convmime!("application/vnd.apache.arrow.file")
+        );
+        assert_ne!(
+            tree_magic::from_u8(b"ARROW1\x01\x02"),
+            "application/vnd.apache.arrow.file"
+        );
+    }
+
+    #[test]
+    fn application_git_pack() {
+        let pack = include_bytes!("application/x-git-pack");
+        assert_eq!(
+            tree_magic::from_u8(pack),
+            convmime!("application/x-git-pack")
+        );
+
+        let mut v3 = pack.to_vec();
+        v3[7] = 3;
+        assert_eq!(tree_magic::from_u8(&v3), "application/x-git-pack");
+
+        // Only versions 2 and 3 have ever been written
+        let mut v9 = pack.to_vec();
+        v9[7] = 9;
+        assert_ne!(tree_magic::from_u8(&v9), "application/x-git-pack");
+
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-git-pack-index")),
+            convmime!("application/x-git-pack-index")
+        );
+    }
+
+    #[test]
+    fn application_mach_binary() {
+        let thin = include_bytes!("application/x-mach-binary");
+        assert_eq!(
+            tree_magic::from_u8(thin),
+            convmime!("application/x-mach-binary")
+        );
+
+        // The same header from a big-endian 32-bit machine
+        let mut big_endian = thin.to_vec();
+        big_endian[..4].copy_from_slice(b"\xfe\xed\xfa\xce");
+        assert_eq!(
+            tree_magic::from_u8(&big_endian),
+            "application/x-mach-binary"
+        );
+    }
+
+    #[test]
+    fn application_mach_binary_fat() {
+        let fat = include_bytes!("application/x-mach-binary-fat");
+        assert_eq!(
+            tree_magic::from_u8(fat),
+            convmime!("application/x-mach-binary")
+        );
+    }
+
+    // Java classes share the fat binary magic
+    #[test]
+    fn application_java() {
+        let class = include_bytes!("application/x-java");
+        assert_eq!(tree_magic::from_u8(class), convmime!("application/x-java"));
+        assert!(!tree_magic::match_u8("application/x-mach-binary", class));
+    }
+
+    #[test]
+    fn application_elf() {
+        let executable = include_bytes!("application/x-executable");
+        assert_eq!(
+            tree_magic::from_u8(executable),
+            convmime!("application/x-executable")
+        );
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-sharedlib")),
+            convmime!("application/x-sharedlib")
+        );
+        // Big-endian, unlike the others
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-object")),
+            convmime!("application/x-object")
+        );
+        let core = include_bytes!("application/x-core");
+        assert_eq!(tree_magic::from_u8(core), convmime!("application/x-core"));
+        assert!(tree_magic::match_u8("application/x-coredump", core));
+
+        for mime in [
+            "application/x-executable",
+            "application/x-sharedlib",
+            "application/x-object",
+            "application/x-core",
+        ] {
+            assert!(tree_magic::is_subclass(mime, "application/x-elf"));
+        }
+    }
+
+    // The type field is read in the byte order the header gives
+    #[test]
+    fn application_elf_byte_order() {
+        let mut big_endian = include_bytes!("application/x-sharedlib").to_vec();
+        big_endian[5] = 2;
+        assert_ne!(tree_magic::from_u8(&big_endian), "application/x-sharedlib");
+        big_endian[16..18].copy_from_slice(&[0, 3]);
+        assert_eq!(tree_magic::from_u8(&big_endian), "application/x-sharedlib");
+    }
+
+    #[test]
+    fn image_jxl() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/jxl")),
+            convmime!("image/jxl")
+        );
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/jxl-container")),
+            convmime!("image/jxl")
+        );
+    }
+
+    #[test]
+    fn image_webp() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/webp")),
+            convmime!("image/webp")
+        );
+    }
+
+    #[test]
+    fn video_msvideo() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("video/x-msvideo")),
+            convmime!("video/x-msvideo")
+        );
+        assert!(tree_magic::match_u8(
+            "video/avi",
+            include_bytes!("video/x-msvideo")
+        ));
+    }
+
+    // Each form type is a subclass of RIFF, and needs the RIFF header
+    #[test]
+    fn riff_forms() {
+        for mime in ["image/webp", "audio/x-wav", "video/x-msvideo"] {
+            assert!(tree_magic::is_subclass(mime, "application/x-riff"));
+        }
+        assert_eq!(
+            tree_magic::from_u8(b"RIFF\x04\0\0\0ABCD"),
+            "application/x-riff"
+        );
+
+        let mut not_riff = include_bytes!("audio/wav").to_vec();
+        not_riff[..4].copy_from_slice(b"JUNK");
+        assert_ne!(tree_magic::from_u8(&not_riff), "audio/x-wav");
+    }
+
+    // The magic comes after a 128-byte preamble
+    #[test]
+    fn application_dicom() {
+        let header = include_bytes!("application/dicom");
+        assert_eq!(tree_magic::from_u8(header), convmime!("application/dicom"));
+        assert_ne!(tree_magic::from_u8(&header[1..]), "application/dicom");
+    }
+
+    // The database names RTF application/rtf, with text/rtf as an alias
+    #[test]
+    fn application_rtf() {
+        let document = include_bytes!("application/rtf");
+        assert_eq!(tree_magic::from_u8(document), convmime!("application/rtf"));
+        assert!(tree_magic::match_u8("text/rtf", document));
+        assert!(tree_magic::is_subclass("application/rtf", "text/plain"));
+        assert_eq!(tree_magic::from_u8(b"{\\rtx1 not rtf}"), "text/plain");
+    }
+
     /// Text tests
     #[test]
     fn text_html() {
@@ -99,6 +411,13 @@ mod from_u8 {
         );
     }
 
+    #[test]
+    fn empty() {
+        assert_eq!(tree_magic::from_u8(b""), "application/x-empty");
+        assert!(tree_magic::match_u8("application/x-empty", b""));
+        assert!(!tree_magic::match_u8("application/x-empty", b"\n"));
+    }
+
     // Audio tests
     #[test]
     fn audio_flac() {
@@ -115,4 +434,335 @@ mod from_u8 {
             convmime!("audio/mpeg")
         );
     }
+
+    #[test]
+    fn audio_wav() {
+        // Also matches the lower priority application/x-riff
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/wav")),
+            convmime!("audio/x-wav")
+        );
+    }
+}
+
+mod try_from_u8 {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn image_gif() {
+        assert_eq!(
+            tree_magic::try_from_u8(include_bytes!("image/gif")),
+            Some("image/gif")
+        );
+    }
+
+    #[test]
+    fn empty_input_still_matches() {
+        assert_eq!(tree_magic::try_from_u8(&[]), Some("application/x-empty"));
+    }
+}
+
+mod from_u8_under {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn subtree() {
+        assert_eq!(
+            tree_magic::from_u8_under("text/plain", b"{\"a\": [1, 2]}\n"),
+            Some("application/json")
+        );
+        assert_eq!(
+            tree_magic::from_u8_under("application/octet-stream", include_bytes!("image/gif")),
+            Some("image/gif")
+        );
+    }
+
+    // Types in other branches are never checked
+    #[test]
+    fn other_branch() {
+        assert_eq!(
+            tree_magic::from_u8_under("application/zip", include_bytes!("image/gif")),
+            Some("application/zip")
+        );
+        assert_eq!(
+            tree_magic::from_u8_under("text/plain", include_bytes!("image/png")),
+            Some("text/plain")
+        );
+    }
+
+    #[test]
+    fn aliases() {
+        assert_eq!(
+            tree_magic::from_u8_under(
+                "application/x-zip-compressed",
+                include_bytes!("application/epub+zip")
+            ),
+            Some("application/epub+zip")
+        );
+    }
+
+    #[test]
+    fn unknown_start() {
+        assert_eq!(
+            tree_magic::from_u8_under("text/x-unknown", include_bytes!("image/gif")),
+            None
+        );
+    }
+
+    #[test]
+    fn root_is_from_u8() {
+        let gif = include_bytes!("image/gif");
+        assert_eq!(
+            tree_magic::from_u8_under("all/all", gif),
+            Some(tree_magic::from_u8(gif))
+        );
+    }
+}
+
+mod from_u8_all {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn image_gif() {
+        let result = tree_magic::from_u8_all(include_bytes!("image/gif"));
+        assert!(result.contains(&"image/gif"));
+        assert!(!result.contains(&"application/octet-stream"));
+    }
+
+    #[test]
+    fn contains_from_u8_result() {
+        let input = include_bytes!("application/zip");
+        let result = tree_magic::from_u8_all(input);
+        assert!(result.contains(&tree_magic::from_u8(input)));
+    }
+
+    #[test]
+    fn text_plain() {
+        assert_eq!(
+            tree_magic::from_u8_all(include_bytes!("text/plain")),
+            ["text/plain"]
+        );
+    }
+}
+
+mod from_u8_best {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn image_gif() {
+        assert_eq!(
+            tree_magic::from_u8_best(include_bytes!("image/gif")),
+            "image/gif"
+        );
+    }
+
+    #[test]
+    fn is_deepest_match() {
+        let input = include_bytes!("application/zip");
+        let all = tree_magic::from_u8_all(input);
+        assert_eq!(tree_magic::from_u8_best(input), all[0]);
+    }
+
+    #[test]
+    fn keeps_from_u8_result_on_ties() {
+        // Also matches the equally specific application/x-java
+        let input = include_bytes!("application/x-mach-binary-fat");
+        assert!(tree_magic::from_u8_all(input).len() > 1);
+        assert_eq!(tree_magic::from_u8_best(input), tree_magic::from_u8(input));
+    }
+}
+
+mod sniff {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn ranked() {
+        // Also matches the equally specific application/x-java
+        let input = include_bytes!("application/x-mach-binary-fat");
+        let guesses = tree_magic::sniff(input, 10);
+        assert!(guesses.len() > 1);
+        assert_eq!(guesses[0].0, tree_magic::from_u8_best(input));
+        assert!(guesses.windows(2).all(|w| w[0].1 >= w[1].1));
+        assert!(guesses.iter().all(|&(_, score)| score > 0.0 && score < 1.0));
+    }
+
+    #[test]
+    fn deeper_scores_higher() {
+        let json = tree_magic::sniff(b"{\"a\": 1}", 1);
+        let text = tree_magic::sniff(b"just some words", 1);
+        assert_eq!(json[0].0, "application/json");
+        assert_eq!(text[0].0, "text/plain");
+        assert!(json[0].1 > text[0].1);
+        // all/all -> all/allfiles -> application/octet-stream -> text/plain
+        assert_eq!(text[0].1, 3.0 / 4.0);
+    }
+
+    #[test]
+    fn limit() {
+        let input = include_bytes!("audio/wav");
+        assert_eq!(tree_magic::sniff(input, 1).len(), 1);
+        assert!(tree_magic::sniff(input, 0).is_empty());
+    }
+
+    #[test]
+    fn deterministic() {
+        let input = include_bytes!("audio/wav");
+        assert_eq!(tree_magic::sniff(input, 10), tree_magic::sniff(input, 10));
+    }
+}
+
+mod from_u8_with_stats {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn same_result() {
+        for bytes in [
+            &include_bytes!("image/gif")[..],
+            include_bytes!("application/zip"),
+            b"plain old text\n",
+            &[0, 1, 2, 3],
+        ] {
+            let (result, _) = tree_magic::from_u8_with_stats(bytes);
+            assert_eq!(result, tree_magic::try_from_u8(bytes));
+        }
+    }
+
+    #[test]
+    fn counts() {
+        let (_, stats) = tree_magic::from_u8_with_stats(include_bytes!("image/png"));
+        // all/all, application/octet-stream, image/png at least
+        assert!(stats.nodes_visited >= 3);
+        assert!(stats.match_calls >= stats.nodes_visited - 1);
+        assert_eq!(stats.checker, "fdo_magic");
+    }
+
+    #[test]
+    fn text_checker() {
+        let (result, stats) = tree_magic::from_u8_with_stats(b"plain old text\n");
+        assert_eq!(result, Some("text/plain"));
+        assert_eq!(stats.checker, "basetype");
+    }
+
+    #[test]
+    fn empty() {
+        let (result, stats) = tree_magic::from_u8_with_stats(&[]);
+        assert_eq!(result, Some("application/x-empty"));
+        assert_eq!(stats.nodes_visited, 0);
+        assert_eq!(stats.match_calls, 0);
+        assert_eq!(stats.checker, "basetype");
+    }
+}
+
+mod from_u8_bounded {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn enough_budget() {
+        for bytes in [
+            &include_bytes!("image/gif")[..],
+            include_bytes!("application/zip"),
+            b"plain old text\n",
+            &[0, 1, 2, 3],
+        ] {
+            let (_, stats) = tree_magic::from_u8_with_stats(bytes);
+            assert_eq!(
+                tree_magic::from_u8_bounded(bytes, stats.match_calls),
+                tree_magic::from_u8(bytes)
+            );
+        }
+    }
+
+    #[test]
+    fn no_budget() {
+        assert_eq!(
+            tree_magic::from_u8_bounded(include_bytes!("image/png"), 0),
+            "application/octet-stream"
+        );
+    }
+
+    // Running out partway down gives the deepest match so far
+    #[test]
+    fn partial() {
+        let bytes = include_bytes!("image/png");
+        let (_, stats) = tree_magic::from_u8_with_stats(bytes);
+        let result = tree_magic::from_u8_bounded(bytes, stats.match_calls - 1);
+        assert_ne!(result, "image/png");
+        assert!(tree_magic::is_subclass("image/png", result));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(tree_magic::from_u8_bounded(&[], 0), "application/x-empty");
+    }
+}
+
+mod truncated {
+    use crate::common::magic_detector;
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    /// A small xorshift generator, so failures can be reproduced
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    fn fixtures() -> Vec<Vec<u8>> {
+        let mut files = Vec::new();
+        for dir in ["application", "audio", "font", "image", "text", "video"] {
+            for entry in std::fs::read_dir(Path::new("tests").join(dir)).unwrap() {
+                files.push(std::fs::read(entry.unwrap().path()).unwrap());
+            }
+        }
+        files
+    }
+
+    // Real headers cut short, so rules reach past the end of the input
+    #[test]
+    fn prefixes() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for file in fixtures() {
+            for len in 0..file.len().min(64) {
+                tree_magic::from_u8(&file[..len]);
+            }
+            for _ in 0..16 {
+                let len = rng.below(file.len().min(8192) + 1);
+                tree_magic::from_u8_all(&file[..len]);
+            }
+        }
+    }
+
+    // Headers with random bytes changed, cut at random lengths
+    #[test]
+    fn mutations() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for file in fixtures() {
+            let mut bytes = file[..file.len().min(4096)].to_vec();
+            for _ in 0..16 {
+                if !bytes.is_empty() {
+                    let i = rng.below(bytes.len());
+                    bytes[i] = rng.next() as u8;
+                }
+                let len = rng.below(bytes.len() + 1);
+                tree_magic::from_u8(&bytes[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_rule_value() {
+        let detector = magic_detector("zero");
+        assert!(!detector.match_u8("application/x-zero", &[0; 100]));
+        assert!(detector.match_u8("application/x-zero", &[0; 300]));
+    }
 }
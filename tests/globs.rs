@@ -0,0 +1,44 @@
+mod globs {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn image_png() {
+        assert_eq!(tree_magic::globs("image/png"), ["*.png"]);
+    }
+
+    #[test]
+    fn resolves_aliases() {
+        assert_eq!(
+            tree_magic::globs("application/x-zip-compressed"),
+            tree_magic::globs("application/zip")
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(tree_magic::globs("application/x-does-not-exist").is_empty());
+    }
+}
+
+mod extensions {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn image_jpeg() {
+        let result = tree_magic::extensions("image/jpeg");
+        assert!(result.contains(&"jpg"));
+        assert!(result.contains(&"jpeg"));
+    }
+
+    #[test]
+    fn skips_wildcard_patterns() {
+        let result = tree_magic::extensions("application/x-sharedlib");
+        assert!(result.contains(&"so"));
+        assert!(result.iter().all(|ext| !ext.contains('*')));
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(tree_magic::extensions("application/x-does-not-exist").is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+mod isobmff {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    /// Builds an `ftyp` box with the given brands, followed by an empty `mdat`
+    fn ftyp(major: &[u8; 4], compatible: &[&[u8; 4]]) -> Vec<u8> {
+        let size = 16 + 4 * compatible.len() as u32;
+        let mut bytes = size.to_be_bytes().to_vec();
+        bytes.extend_from_slice(b"ftyp");
+        bytes.extend_from_slice(major);
+        bytes.extend_from_slice(&[0; 4]);
+        for brand in compatible {
+            bytes.extend_from_slice(*brand);
+        }
+        bytes.extend_from_slice(b"\0\0\0\x08mdat");
+        bytes
+    }
+
+    #[test]
+    fn mp4() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("video/mp4")),
+            "video/mp4"
+        );
+    }
+
+    #[test]
+    fn quicktime() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("video/quicktime")),
+            "video/quicktime"
+        );
+    }
+
+    #[test]
+    fn quicktime_without_ftyp() {
+        let bytes = b"\0\0\0\x08wide\0\0\0\x10mdat\0\0\0\0\0\0\0\0";
+        assert_eq!(tree_magic::from_u8(bytes), "video/quicktime");
+    }
+
+    #[test]
+    fn threegpp() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("video/3gpp")),
+            "video/3gpp"
+        );
+    }
+
+    #[test]
+    fn m4a() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/mp4")),
+            "audio/mp4"
+        );
+    }
+
+    #[test]
+    fn m4b() {
+        assert_eq!(
+            tree_magic::from_u8(&ftyp(b"M4B ", &[b"M4B ", b"mp42", b"isom"])),
+            "audio/x-m4b"
+        );
+    }
+
+    #[test]
+    fn heif() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/heif")),
+            "image/heif"
+        );
+    }
+
+    #[test]
+    fn avif() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/avif")),
+            "image/avif"
+        );
+    }
+
+    // The generic HEIF brand only says what the file is built on
+    #[test]
+    fn compatible_brand() {
+        assert_eq!(
+            tree_magic::from_u8(&ftyp(b"mif1", &[b"mif1", b"avif"])),
+            "image/avif"
+        );
+        assert_eq!(
+            tree_magic::from_u8(&ftyp(b"mif1", &[b"mif1"])),
+            "image/heif"
+        );
+    }
+
+    #[test]
+    fn image_brands() {
+        for (name, mime) in [
+            ("avif", "image/avif"),
+            ("avif-mif1", "image/avif"),
+            ("avif-sequence", "image/avif"),
+            ("heif", "image/heif"),
+            ("heif-heix", "image/heif"),
+            ("heif-mif1", "image/heif"),
+            ("heif-generic", "image/heif"),
+            ("heif-sequence", "image/heif"),
+        ] {
+            let path = Path::new("tests/image").join(name);
+            assert_eq!(tree_magic::from_filepath(&path), Some(mime), "{name}");
+        }
+    }
+
+    // A specific major brand wins over specific compatible brands
+    #[test]
+    fn major_brand_first() {
+        assert_eq!(
+            tree_magic::from_u8(&ftyp(b"heic", &[b"mif1", b"avif"])),
+            "image/heif"
+        );
+        assert_eq!(
+            tree_magic::from_u8(&ftyp(b"avif", &[b"mif1", b"heic"])),
+            "image/avif"
+        );
+        assert_eq!(
+            tree_magic::from_u8(&ftyp(b"mif1", &[b"MA1B", b"heic"])),
+            "image/avif"
+        );
+    }
+
+    // HEIC is HEIF with a particular codec, and only has an alias
+    #[test]
+    fn heic() {
+        let heic = include_bytes!("image/heif");
+        assert!(tree_magic::match_u8("image/heic", heic));
+        assert!(!tree_magic::match_u8(
+            "image/heic",
+            include_bytes!("image/avif")
+        ));
+        assert_eq!(tree_magic::canonical_mime("image/heic"), "image/heif");
+        assert!(tree_magic::match_u8(
+            "image/avif-sequence",
+            include_bytes!("image/avif-sequence")
+        ));
+    }
+
+    #[test]
+    fn unknown_brand() {
+        assert_eq!(
+            tree_magic::from_u8(&ftyp(b"zzzz", &[b"zzzz"])),
+            "application/x-isobmff"
+        );
+    }
+
+    // Only the major brand is left of the box
+    #[test]
+    fn truncated() {
+        let bytes = ftyp(b"qt  ", &[b"qt  "]);
+        assert_eq!(tree_magic::from_u8(&bytes[..12]), "video/quicktime");
+        assert_eq!(
+            tree_magic::from_u8(&bytes[..10]),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        let mut bytes = ftyp(b"isom", &[b"isom"]);
+        bytes[3] = 12;
+        assert_eq!(tree_magic::from_u8(&bytes), "application/octet-stream");
+    }
+
+    #[test]
+    fn hierarchy() {
+        for mime in [
+            "video/mp4",
+            "video/quicktime",
+            "audio/mp4",
+            "image/heif",
+            "image/avif",
+        ] {
+            assert!(tree_magic::is_subclass(mime, "application/x-isobmff"));
+        }
+        assert!(tree_magic::is_subclass("video/3gpp", "video/mp4"));
+        assert!(tree_magic::is_subclass("audio/x-m4b", "audio/mp4"));
+    }
+}
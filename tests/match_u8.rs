@@ -1,3 +1,5 @@
+mod common;
+
 mod match_u8 {
     use tree_magic_mini as tree_magic;
 
@@ -115,3 +117,226 @@ mod match_u8 {
         ));
     }
 }
+
+mod match_any {
+    use std::fs::File;
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn first_match() {
+        let gif = include_bytes!("image/gif");
+        assert_eq!(
+            tree_magic::match_any(&["image/png", "image/gif"], gif),
+            Some("image/gif")
+        );
+        // A parent type matches too, so order decides
+        assert_eq!(
+            tree_magic::match_any(&["application/octet-stream", "image/gif"], gif),
+            Some("application/octet-stream")
+        );
+    }
+
+    #[test]
+    fn none_match() {
+        let gif = include_bytes!("image/gif");
+        assert_eq!(
+            tree_magic::match_any(&["image/png", "text/x-unknown"], gif),
+            None
+        );
+        assert_eq!(tree_magic::match_any(&[], gif), None);
+    }
+
+    #[test]
+    fn aliases() {
+        assert_eq!(
+            tree_magic::match_any(
+                &["application/x-zip-compressed"],
+                include_bytes!("application/zip")
+            ),
+            Some("application/zip")
+        );
+    }
+
+    #[test]
+    fn file() {
+        let candidates = ["image/png", "image/gif"];
+        let file = File::open("tests/image/gif").unwrap();
+        assert_eq!(
+            tree_magic::match_any_file(&candidates, &file),
+            Some("image/gif")
+        );
+        let file = File::open("tests/image").unwrap();
+        assert_eq!(
+            tree_magic::match_any_file(&["image/gif", "inode/directory"], &file),
+            Some("inode/directory")
+        );
+    }
+
+    #[test]
+    fn filepath() {
+        let candidates = ["image/png", "application/x-zip-compressed"];
+        assert_eq!(
+            tree_magic::match_filepath_any(&candidates, Path::new("tests/application/zip")),
+            Some("application/zip")
+        );
+        assert_eq!(
+            tree_magic::match_filepath_any(&candidates, Path::new("tests/image/gif")),
+            None
+        );
+        assert_eq!(
+            tree_magic::match_filepath_any(&candidates, Path::new("tests/missing")),
+            None
+        );
+        assert_eq!(
+            tree_magic::match_filepath_any(
+                &["image/gif", "inode/directory"],
+                Path::new("tests/image")
+            ),
+            Some("inode/directory")
+        );
+    }
+}
+
+mod masks {
+    use crate::common::magic_detector;
+    use tree_magic_mini as tree_magic;
+
+    // The rule's value has bits the mask clears, so it only matches if the
+    // mask is applied to the value as well as the input
+    #[test]
+    fn flag_bits() {
+        let detector = magic_detector("masked");
+        for input in [b"FLG\x01", b"FLG\x03", b"FLG\xff"] {
+            assert!(detector.match_u8("application/x-masked-flags", input));
+        }
+        for input in [b"FLG\x00", b"FLG\x80", b"FLH\x01"] {
+            assert!(!detector.match_u8("application/x-masked-flags", input));
+        }
+    }
+
+    // Clearing bit 0x20 of ASCII letters makes a case-insensitive match
+    #[test]
+    fn case_insensitive() {
+        let detector = magic_detector("masked");
+        for input in [b"tree", b"TREE", b"Tree"] {
+            assert_eq!(detector.from_u8(input), "application/x-masked-case");
+        }
+        assert!(!detector.match_u8("application/x-masked-case", b"trie"));
+    }
+
+    // The database's BMP rule masks out the file size after "BM"
+    #[test]
+    fn bmp_header() {
+        let input = b"BM\x12\x34\x56\x78\0\0";
+        assert!(tree_magic::match_u8("image/bmp", input));
+        assert!(!tree_magic::match_u8(
+            "image/bmp",
+            b"BM\x12\x34\x56\x78\x01\0"
+        ));
+    }
+}
+
+mod word_size {
+    use crate::common::magic_detector;
+
+    #[test]
+    fn big_and_little_endian() {
+        let detector = magic_detector("endian");
+        let big = 0x1234u16.to_be_bytes();
+        let little = 0x1234u16.to_le_bytes();
+        assert_eq!(detector.from_u8(&big), "application/x-big16");
+        assert_eq!(detector.from_u8(&little), "application/x-little16");
+        assert!(!detector.match_u8("application/x-big16", &little));
+        assert!(!detector.match_u8("application/x-little16", &big));
+    }
+
+    // Host-order words are stored big-endian and swapped, along with their
+    // masks, to match files written on this machine
+    #[test]
+    fn host_order() {
+        let detector = magic_detector("endian");
+        let mut input = vec![0; 4];
+        input.extend(0x1234_56ffu32.to_ne_bytes());
+        assert!(detector.match_u8("application/x-host32", &input));
+
+        let mut input = vec![0; 4];
+        input.extend(0x1234_56ffu32.to_ne_bytes().iter().rev());
+        assert!(!detector.match_u8("application/x-host32", &input));
+    }
+}
+
+mod range {
+    use crate::common::magic_detector;
+
+    fn at(offset: usize) -> Vec<u8> {
+        let mut input = vec![b'.'; offset];
+        input.extend(b"RANGE....");
+        input
+    }
+
+    // The rule's range has 8 offsets to try, from 4 to 11
+    #[test]
+    fn within_range() {
+        let detector = magic_detector("range");
+        for offset in 4..12 {
+            assert!(detector.match_u8("application/x-ranged", &at(offset)));
+        }
+        assert!(!detector.match_u8("application/x-ranged", &at(3)));
+        assert!(!detector.match_u8("application/x-ranged", &at(12)));
+    }
+
+    #[test]
+    fn offset_found() {
+        let detector = magic_detector("range");
+        let explanation = detector.explain("application/x-ranged", &at(9)).unwrap();
+        assert_eq!(explanation.rules[0].offset, 9);
+    }
+
+    // Input that ends inside the range is only searched as far as it goes
+    #[test]
+    fn truncated() {
+        let detector = magic_detector("range");
+        assert!(detector.match_u8("application/x-ranged", b"....RANGE"));
+        assert!(!detector.match_u8("application/x-ranged", b"......RAN"));
+    }
+}
+
+mod nested {
+    use crate::common::magic_detector;
+
+    // The database has NEST at 0, then A at 4, then either 1 or 2 at 5,
+    // under two separate A rules
+
+    #[test]
+    fn every_level_must_match() {
+        let detector = magic_detector("nested");
+        assert!(detector.match_u8("application/x-nested", b"NESTA1"));
+        assert!(!detector.match_u8("application/x-nested", b"NESTB1"));
+        assert!(!detector.match_u8("application/x-nested", b"NESTA3"));
+        assert!(!detector.match_u8("application/x-nested", b"NEST"));
+        assert!(!detector.match_u8("application/x-nested", b"....A1"));
+    }
+
+    // A branch that fails further down falls back to its siblings
+    #[test]
+    fn sibling_branches() {
+        let detector = magic_detector("nested");
+        assert!(detector.match_u8("application/x-nested", b"NESTA1"));
+        assert!(detector.match_u8("application/x-nested", b"NESTA2"));
+    }
+
+    #[test]
+    fn explain_path() {
+        let detector = magic_detector("nested");
+        for input in [b"NESTA1", b"NESTA2"] {
+            let explanation = detector.explain("application/x-nested", input).unwrap();
+            let found: Vec<(usize, &[u8])> = explanation
+                .rules
+                .iter()
+                .map(|r| (r.offset, &r.pattern[..]))
+                .collect();
+            assert_eq!(found, [(0, &b"NEST"[..]), (4, b"A"), (5, &input[5..])]);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(feature = "mime")]
+mod mime_conversion {
+    use std::fs::File;
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn from_u8_mime() {
+        let result = tree_magic::from_u8_mime(include_bytes!("image/png")).unwrap();
+        assert_eq!(result, mime::IMAGE_PNG);
+    }
+
+    #[test]
+    fn parts() {
+        let result = tree_magic::from_u8_mime(include_bytes!("image/tiff")).unwrap();
+        assert_eq!(result.type_(), mime::IMAGE);
+        assert_eq!(result.subtype(), "tiff");
+        assert_eq!(result.suffix(), None);
+    }
+
+    #[test]
+    fn from_file_mime() {
+        let file = File::open("tests/image/gif").unwrap();
+        assert_eq!(tree_magic::from_file_mime(&file), Some(mime::IMAGE_GIF));
+    }
+
+    #[test]
+    fn from_filepath_mime() {
+        let path = Path::new("tests/image/bmp");
+        assert_eq!(tree_magic::from_filepath_mime(path), Some(mime::IMAGE_BMP));
+        assert_eq!(
+            tree_magic::from_filepath_mime(Path::new("tests/missing")),
+            None
+        );
+    }
+
+    #[test]
+    fn matches_str() {
+        let bytes = include_bytes!("application/zip");
+        let result = tree_magic::from_u8_mime(bytes).unwrap();
+        assert_eq!(result.essence_str(), tree_magic::from_u8(bytes));
+    }
+}
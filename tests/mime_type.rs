@@ -0,0 +1,123 @@
+mod mime_type {
+    use std::collections::HashSet;
+    use std::path::Path;
+    use tree_magic_mini::{self as tree_magic, MimeType};
+
+    #[test]
+    fn parts() {
+        let mime = MimeType::from("application/vnd.oasis.opendocument.text");
+        assert_eq!(mime.toplevel(), "application");
+        assert_eq!(mime.subtype(), "vnd.oasis.opendocument.text");
+        assert_eq!(mime.as_str(), "application/vnd.oasis.opendocument.text");
+    }
+
+    #[test]
+    fn no_slash() {
+        let mime = MimeType::from("nonsense");
+        assert_eq!(mime.toplevel(), "nonsense");
+        assert_eq!(mime.subtype(), "");
+    }
+
+    #[test]
+    fn categories() {
+        assert!(MimeType::from("text/plain").is_text());
+        // Only the top-level type is looked at
+        assert!(!MimeType::from("application/json").is_text());
+        assert!(MimeType::from("image/png").is_image());
+        assert!(MimeType::from("audio/flac").is_audio());
+        assert!(MimeType::from("video/mp4").is_video());
+        assert!(MimeType::from("font/woff2").is_font());
+        assert!(MimeType::from("application/zip").is_application());
+        assert!(MimeType::from("inode/directory").is_inode());
+        assert!(!MimeType::from("image/png").is_video());
+    }
+
+    #[test]
+    fn conversions() {
+        let mime = MimeType::from("image/gif");
+        assert_eq!(mime.to_string(), "image/gif");
+        assert_eq!(mime.as_ref(), "image/gif");
+        assert_eq!(mime, "image/gif");
+        let bare: &str = mime.into();
+        assert_eq!(bare, "image/gif");
+        let set: HashSet<MimeType> = [mime, mime].into_iter().collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn typed_detection() {
+        let gif = include_bytes!("image/gif");
+        assert_eq!(tree_magic::from_u8_typed(gif), tree_magic::from_u8(gif));
+
+        let file = std::fs::File::open("tests/image/gif").unwrap();
+        assert_eq!(tree_magic::from_file_typed(&file).unwrap(), "image/gif");
+
+        let path = Path::new("tests/hint/notes.md");
+        assert!(tree_magic::from_filepath_typed(path).unwrap().is_text());
+        assert_eq!(
+            tree_magic::from_filepath_typed(Path::new("tests/missing")),
+            None
+        );
+    }
+}
+
+mod intern {
+    use std::collections::HashMap;
+    use tree_magic_mini::{Checker, Detector, MimeType};
+
+    #[test]
+    fn same_copy() {
+        let a = MimeType::intern(&String::from("application/x-interned"));
+        let b = MimeType::intern(&format!("application/x-{}", "interned"));
+        assert_eq!(a, b);
+        assert!(std::ptr::eq(a.as_str(), b.as_str()));
+    }
+
+    /// A checker for a type whose name is only known at runtime
+    struct Runtime {
+        mime: &'static str,
+        magic: Vec<u8>,
+    }
+
+    impl Checker for Runtime {
+        fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
+            mimetype == self.mime && bytes.starts_with(&self.magic)
+        }
+        fn match_file(&self, _file: &std::fs::File, _mimetype: &str) -> bool {
+            false
+        }
+        fn get_supported(&self) -> Vec<&'static str> {
+            vec![self.mime]
+        }
+        fn get_subclasses(&self) -> Vec<(&'static str, &'static str)> {
+            vec![]
+        }
+        fn get_aliaslist(&self) -> HashMap<&'static str, &'static str> {
+            HashMap::new()
+        }
+    }
+
+    #[test]
+    fn runtime_checker() {
+        let config = String::from("application/x-from-config = RUNTIME");
+        let (name, magic) = config.split_once(" = ").unwrap();
+
+        let mut detector = Detector::new();
+        detector.add_checker(Runtime {
+            mime: MimeType::intern(name).as_str(),
+            magic: magic.as_bytes().to_vec(),
+        });
+        let alias = String::from("application/x-config-alias");
+        detector.add_alias(&alias, name);
+
+        assert_eq!(
+            detector.from_u8(b"RUNTIME data"),
+            "application/x-from-config"
+        );
+        assert!(std::ptr::eq(
+            detector.from_u8(b"RUNTIME data"),
+            MimeType::intern(name).as_str()
+        ));
+        assert!(detector.match_u8(&alias, b"RUNTIME data"));
+    }
+}
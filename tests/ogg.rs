@@ -0,0 +1,130 @@
+mod ogg {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    /// Builds a page holding `packet` for stream `serial`
+    fn page(flags: u8, serial: u32, packet: &[u8]) -> Vec<u8> {
+        let mut lacing = vec![255; packet.len() / 255];
+        lacing.push((packet.len() % 255) as u8);
+        let mut bytes = b"OggS\0".to_vec();
+        bytes.push(flags);
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.extend_from_slice(&serial.to_le_bytes());
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.push(lacing.len() as u8);
+        bytes.extend(lacing);
+        bytes.extend_from_slice(packet);
+        bytes
+    }
+
+    fn opus_head() -> Vec<u8> {
+        let mut packet = b"OpusHead\x01\x02".to_vec();
+        packet.extend_from_slice(&[0; 9]);
+        packet
+    }
+
+    #[test]
+    fn vorbis() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/ogg")),
+            "audio/x-vorbis+ogg"
+        );
+    }
+
+    #[test]
+    fn opus() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/opus")),
+            "audio/x-opus+ogg"
+        );
+        assert!(tree_magic::match_u8(
+            "audio/opus",
+            include_bytes!("audio/opus")
+        ));
+    }
+
+    // The Vorbis stream comes first, but the file is still a video
+    #[test]
+    fn theora() {
+        let bytes = include_bytes!("video/ogg");
+        assert_eq!(tree_magic::from_u8(bytes), "video/x-theora+ogg");
+        assert!(tree_magic::match_u8("video/ogg", bytes));
+        assert!(!tree_magic::match_u8("audio/ogg", bytes));
+    }
+
+    #[test]
+    fn from_filepath() {
+        for (name, mime) in [
+            ("audio/ogg", "audio/x-vorbis+ogg"),
+            ("audio/opus", "audio/x-opus+ogg"),
+            ("video/ogg", "video/x-theora+ogg"),
+        ] {
+            let path = Path::new("tests").join(name);
+            assert_eq!(tree_magic::from_filepath(&path), Some(mime));
+        }
+    }
+
+    #[test]
+    fn subclasses() {
+        for (child, parent) in [
+            ("audio/x-opus+ogg", "audio/ogg"),
+            ("audio/x-flac+ogg", "audio/ogg"),
+            ("video/x-theora+ogg", "video/ogg"),
+            ("audio/ogg", "application/ogg"),
+            ("video/ogg", "application/ogg"),
+        ] {
+            assert!(tree_magic::is_subclass(child, parent));
+        }
+    }
+
+    #[test]
+    fn after_skeleton() {
+        let mut bytes = page(0x02, 1, b"fishead\0\x03\0\0\0");
+        bytes.extend(page(0x02, 2, &opus_head()));
+        assert_eq!(tree_magic::from_u8(&bytes), "audio/x-opus+ogg");
+    }
+
+    #[test]
+    fn flac() {
+        let mut packet = b"\x7fFLAC\x01\x00\x00\x01fLaC".to_vec();
+        packet.extend_from_slice(&[0; 38]);
+        assert_eq!(
+            tree_magic::from_u8(&page(0x02, 1, &packet)),
+            "audio/x-flac+ogg"
+        );
+    }
+
+    // The lacing table can be longer than one segment, which moves the packet
+    #[test]
+    fn long_lacing() {
+        let mut packet = opus_head();
+        packet.resize(600, 0);
+        let bytes = page(0x02, 1, &packet);
+        assert_eq!(bytes[26], 3);
+        assert_eq!(tree_magic::from_u8(&bytes), "audio/x-opus+ogg");
+    }
+
+    // Streams only start on pages before any other pages
+    #[test]
+    fn stops_at_data_pages() {
+        let mut bytes = page(0x02, 1, &opus_head());
+        bytes.extend(page(0x00, 1, b"OpusTags"));
+        bytes.extend(page(0x02, 2, b"\x80theora\x03\x02\x01"));
+        assert_eq!(tree_magic::from_u8(&bytes), "audio/x-opus+ogg");
+    }
+
+    #[test]
+    fn unknown_codec() {
+        let bytes = page(0x02, 1, b"\x00unknown");
+        assert_eq!(tree_magic::from_u8(&bytes), "application/ogg");
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = include_bytes!("video/ogg");
+        for len in 0..bytes.len() {
+            let _ = tree_magic::from_u8(&bytes[..len]);
+        }
+        assert!(!tree_magic::match_u8("application/ogg", &bytes[..20]));
+    }
+}
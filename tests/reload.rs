@@ -0,0 +1,56 @@
+// These swap the detector every other test uses, so they get a test binary to
+// themselves and run as one test
+mod reload {
+    use std::path::Path;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tree_magic_mini::{self as tree_magic, Detector};
+
+    const GIF: &[u8] = include_bytes!("image/gif");
+
+    fn custom() -> Detector {
+        Detector::from_database_dir(Path::new("tests/database/custom")).unwrap()
+    }
+
+    #[test]
+    fn swaps_database() {
+        assert_eq!(tree_magic::from_u8(GIF), "image/gif");
+
+        tree_magic::set_detector(custom());
+        assert_eq!(
+            tree_magic::from_u8(b"TREEMAGIC data"),
+            "application/x-tree-magic"
+        );
+        assert!(tree_magic::is_known("application/x-tree-magic"));
+
+        tree_magic::reload().unwrap();
+        assert_eq!(tree_magic::from_u8(GIF), "image/gif");
+        assert!(!tree_magic::is_known("application/x-tree-magic"));
+
+        // Readers see one database or the other, never a mix
+        let done = AtomicBool::new(false);
+        std::thread::scope(|scope| {
+            let readers: Vec<_> = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        while !done.load(Ordering::Relaxed) {
+                            let found = tree_magic::from_u8(GIF);
+                            assert!(
+                                found == "image/gif" || found == "application/octet-stream",
+                                "{found}"
+                            );
+                        }
+                    })
+                })
+                .collect();
+            for _ in 0..4 {
+                tree_magic::set_detector(custom());
+                tree_magic::reload().unwrap();
+            }
+            done.store(true, Ordering::Relaxed);
+            for reader in readers {
+                reader.join().unwrap();
+            }
+        });
+        assert_eq!(tree_magic::from_u8(GIF), "image/gif");
+    }
+}
@@ -0,0 +1,174 @@
+mod is_subclass {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn direct_parent() {
+        assert!(tree_magic::is_subclass(
+            "text/plain",
+            "application/octet-stream"
+        ));
+    }
+
+    #[test]
+    fn indirect_parent() {
+        assert!(tree_magic::is_subclass(
+            "text/html",
+            "application/octet-stream"
+        ));
+        assert!(tree_magic::is_subclass("text/html", "all/all"));
+    }
+
+    #[test]
+    fn itself() {
+        assert!(tree_magic::is_subclass("image/png", "image/png"));
+    }
+
+    #[test]
+    fn not_a_parent() {
+        assert!(!tree_magic::is_subclass("image/png", "text/plain"));
+        assert!(!tree_magic::is_subclass(
+            "application/octet-stream",
+            "text/plain"
+        ));
+    }
+
+    #[test]
+    fn resolves_aliases() {
+        assert!(tree_magic::is_subclass(
+            "application/x-zip-compressed",
+            "application/octet-stream"
+        ));
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(!tree_magic::is_subclass(
+            "application/x-does-not-exist",
+            "all/all"
+        ));
+        assert!(!tree_magic::is_subclass(
+            "all/all",
+            "application/x-does-not-exist"
+        ));
+    }
+}
+
+mod ancestors {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn text_plain() {
+        assert_eq!(
+            tree_magic::ancestors("text/plain"),
+            ["all/all", "all/allfiles", "application/octet-stream"]
+        );
+    }
+
+    #[test]
+    fn root() {
+        assert!(tree_magic::ancestors("all/all").is_empty());
+    }
+
+    #[test]
+    fn resolves_aliases() {
+        assert_eq!(
+            tree_magic::ancestors("application/x-zip-compressed"),
+            tree_magic::ancestors("application/zip")
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(tree_magic::ancestors("application/x-does-not-exist").is_empty());
+    }
+}
+
+mod descendants {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn text_plain() {
+        let result = tree_magic::descendants("text/plain");
+        assert!(result.contains(&"text/html"));
+        assert!(!result.contains(&"text/plain"));
+        assert!(!result.contains(&"image/png"));
+    }
+
+    #[test]
+    fn sorted() {
+        let result = tree_magic::descendants("all/all");
+        assert!(result.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(tree_magic::descendants("application/x-does-not-exist").is_empty());
+    }
+}
+
+mod subclass_tree_dot {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn edges() {
+        let dot = tree_magic::subclass_tree_dot();
+        assert!(dot.starts_with("digraph subclasses {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("    \"application/zip\" -> \"application/x-java-archive\";\n"));
+        assert!(dot.contains("    \"image/gif\";\n"));
+    }
+
+    #[test]
+    fn aliases() {
+        let dot = tree_magic::subclass_tree_dot();
+        assert!(dot.contains(
+            "    \"application/x-zip-compressed\" -> \"application/zip\" [style=dashed];\n"
+        ));
+    }
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(
+            tree_magic::subclass_tree_dot(),
+            tree_magic::subclass_tree_dot()
+        );
+    }
+}
+
+mod subclass_tree_text {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn outline() {
+        let tree = tree_magic::subclass_tree_text();
+        assert!(tree.starts_with("all/all\n  all/allfiles\n    application/octet-stream\n"));
+        let zip = tree.find("\n      application/zip\n").unwrap();
+        assert!(tree[zip..].contains("\n        application/x-java-archive\n"));
+    }
+
+    // Every type in the graph is listed, and expanded once
+    #[test]
+    fn every_type_once() {
+        let tree = tree_magic::subclass_tree_text();
+        let lines: Vec<&str> = tree.lines().map(str::trim_start).collect();
+        let expanded: Vec<&str> = lines
+            .iter()
+            .copied()
+            .filter(|line| !line.ends_with(" (see above)"))
+            .collect();
+        let mut unique = expanded.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), expanded.len());
+        for mime in tree_magic::supported_types() {
+            assert!(expanded.contains(&mime), "{mime}");
+        }
+    }
+
+    // x-executable is under both application/x-elf and octet-stream
+    #[test]
+    fn shared_children() {
+        let tree = tree_magic::subclass_tree_text();
+        assert!(tree.contains("application/x-executable (see above)\n"));
+    }
+}
@@ -0,0 +1,118 @@
+mod supported_types {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn contains_base_types() {
+        let types = tree_magic::supported_types();
+        assert!(types.contains(&"all/all"));
+        assert!(types.contains(&"application/octet-stream"));
+        assert!(types.contains(&"text/plain"));
+    }
+
+    #[test]
+    fn contains_magic_types() {
+        let types = tree_magic::supported_types();
+        assert!(types.contains(&"image/png"));
+        assert!(types.contains(&"application/zip"));
+    }
+
+    #[test]
+    fn sorted_without_duplicates() {
+        let types = tree_magic::supported_types();
+        assert!(types.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn excludes_aliases() {
+        let types = tree_magic::supported_types();
+        assert!(!types.contains(&"application/x-zip-compressed"));
+    }
+
+    // Older databases give some types names that are now aliases
+    #[test]
+    fn merges_old_names() {
+        let types = tree_magic::supported_types();
+        assert!(types.contains(&"font/ttf"));
+        assert!(!types.contains(&"application/x-font-ttf"));
+    }
+}
+
+mod alias_merging {
+    use std::collections::HashMap;
+    use std::fs::File;
+    use tree_magic_mini::{Checker, Detector};
+
+    /// Detects two made-up formats from their first bytes, with aliases given
+    /// when it's created
+    struct Formats(HashMap<&'static str, &'static str>);
+
+    impl Checker for Formats {
+        fn match_bytes(&self, bytes: &[u8], mimetype: &str) -> bool {
+            match mimetype {
+                "application/x-old-format" => bytes.starts_with(b"OLDFMT"),
+                "application/x-other-format" => bytes.starts_with(b"OTHERFMT"),
+                _ => false,
+            }
+        }
+
+        fn match_file(&self, _file: &File, _mimetype: &str) -> bool {
+            false
+        }
+
+        fn get_supported(&self) -> Vec<&'static str> {
+            vec!["application/x-old-format", "application/x-other-format"]
+        }
+
+        fn get_subclasses(&self) -> Vec<(&'static str, &'static str)> {
+            vec![
+                ("application/octet-stream", "application/x-old-format"),
+                ("application/octet-stream", "application/x-other-format"),
+            ]
+        }
+
+        fn get_aliaslist(&self) -> HashMap<&'static str, &'static str> {
+            self.0.clone()
+        }
+    }
+
+    // An alias with rules of its own keeps them when nothing detects the
+    // type it's an alias of
+    #[test]
+    fn canonical_not_detected() {
+        let mut detector = Detector::new();
+        detector.add_checker(Formats(HashMap::from([(
+            "application/x-old-format",
+            "application/x-new-format",
+        )])));
+        assert_eq!(detector.from_u8(b"OLDFMT"), "application/x-old-format");
+        assert!(detector
+            .supported_types()
+            .contains(&"application/x-old-format"));
+    }
+
+    // An alias whose canonical type is detected shares its rules
+    #[test]
+    fn canonical_detected() {
+        let mut detector = Detector::new();
+        detector.add_checker(Formats(HashMap::from([(
+            "application/x-old-format",
+            "application/x-other-format",
+        )])));
+        assert_eq!(detector.from_u8(b"OTHERFMT"), "application/x-other-format");
+        assert!(!detector
+            .supported_types()
+            .contains(&"application/x-old-format"));
+    }
+
+    // Neither name of a pair that's aliased both ways is lost
+    #[test]
+    fn aliased_both_ways() {
+        let mut detector = Detector::new();
+        detector.add_checker(Formats(HashMap::from([
+            ("application/x-old-format", "application/x-other-format"),
+            ("application/x-other-format", "application/x-old-format"),
+        ])));
+        assert_eq!(detector.from_u8(b"OLDFMT"), "application/x-old-format");
+        assert_eq!(detector.from_u8(b"OTHERFMT"), "application/x-other-format");
+    }
+}
@@ -0,0 +1,680 @@
+mod shebang {
+    use std::path::Path;
+    use tree_magic_mini::{self as tree_magic, Detector};
+
+    fn detector() -> Detector {
+        Detector::from_database_dir(Path::new("tests/database/custom")).unwrap()
+    }
+
+    #[test]
+    fn shell() {
+        let detector = detector();
+        assert_eq!(
+            detector.from_u8(b"#!/bin/sh\necho hi\n"),
+            "application/x-shellscript"
+        );
+        assert_eq!(
+            detector.from_u8(b"#! /usr/bin/bash -e\n"),
+            "application/x-shellscript"
+        );
+        assert_eq!(detector.from_u8(b"#!/bin/tcsh\n"), "application/x-csh");
+    }
+
+    #[test]
+    fn env() {
+        let detector = detector();
+        assert_eq!(
+            detector.from_u8(b"#!/usr/bin/env python3\nprint()\n"),
+            "text/x-python3"
+        );
+        assert_eq!(
+            detector.from_u8(b"#!/usr/bin/env -S LANG=C node --harmony\n"),
+            "application/javascript"
+        );
+    }
+
+    #[test]
+    fn versioned() {
+        let detector = detector();
+        assert_eq!(detector.from_u8(b"#!/usr/bin/python2.7\n"), "text/x-python");
+        assert_eq!(
+            detector.from_u8(b"#!/usr/bin/perl5.36\n"),
+            "application/x-perl"
+        );
+        assert!(detector.match_u8("text/x-python", b"#!/usr/bin/python3.12\n"));
+    }
+
+    #[test]
+    fn unknown_interpreter() {
+        let detector = detector();
+        assert_eq!(detector.from_u8(b"#!/usr/bin/shellcheck\n"), "text/plain");
+        assert_eq!(detector.from_u8(b"#!/usr/bin/env\n"), "text/plain");
+        assert_eq!(
+            detector.from_u8(b"# not a shebang: #!/bin/sh\n"),
+            "text/plain"
+        );
+    }
+
+    #[test]
+    fn binary_is_not_a_script() {
+        assert_eq!(
+            detector().from_u8(b"#!/bin/sh\n\0\0\0"),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn perl_from_database() {
+        // The database splits Perl's magic over two priorities
+        assert_eq!(
+            tree_magic::from_u8(b"#!/usr/bin/perl\nprint;\n"),
+            "application/x-perl"
+        );
+    }
+}
+
+mod xml {
+    use tree_magic_mini as tree_magic;
+
+    const DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+
+    fn from_str(input: &str) -> &'static str {
+        tree_magic::from_u8(input.as_bytes())
+    }
+
+    #[test]
+    fn root_elements() {
+        for (root, expected) in [
+            ("<svg width=\"10\">", "image/svg+xml"),
+            ("<rss>", "application/rss+xml"),
+            ("<feed>", "application/atom+xml"),
+            ("<html>", "application/xhtml+xml"),
+            ("<plist version=\"1.0\">", "application/x-plist"),
+            ("<note>", "application/xml"),
+        ] {
+            assert_eq!(from_str(&format!("{DECLARATION}{root}</x>")), expected);
+        }
+    }
+
+    #[test]
+    fn prolog() {
+        let input = format!(
+            "\u{feff}{DECLARATION}<?xml-stylesheet href=\"a.css\"?>\n\
+             <!-- made by hand -->\n\
+             <!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\" [\n  <!ENTITY a \"<b>\">\n]>\n\
+             <svg:svg xmlns:svg=\"http://www.w3.org/2000/svg\"/>"
+        );
+        assert_eq!(from_str(&input), "image/svg+xml");
+    }
+
+    #[test]
+    fn namespace_without_declaration() {
+        assert_eq!(
+            from_str("<svg xmlns=\"http://www.w3.org/2000/svg\"/>"),
+            "image/svg+xml"
+        );
+        assert_eq!(
+            from_str("<html xmlns=\"http://www.w3.org/1999/xhtml\"><body>"),
+            "text/html"
+        );
+        assert_eq!(from_str("<note>remember</note> text"), "text/plain");
+        // Not XML, but the HTML sniffer knows the tag
+        assert_eq!(from_str("<b>bold</b> text"), "text/html");
+    }
+
+    #[test]
+    fn unterminated_prolog() {
+        assert_eq!(
+            from_str(&format!("{DECLARATION}<!-- never closed <svg>")),
+            "application/xml"
+        );
+    }
+}
+
+mod html {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    fn from_str(input: &str) -> &'static str {
+        tree_magic::from_u8(input.as_bytes())
+    }
+
+    #[test]
+    fn fixtures() {
+        let dir = Path::new("tests/text");
+        for name in [
+            "html",
+            "html-doctype",
+            "html-head",
+            "html-comment",
+            "html-fragment",
+        ] {
+            assert_eq!(
+                tree_magic::from_filepath(&dir.join(name)),
+                Some("text/html"),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn leading_patterns() {
+        for input in [
+            "<!doctype html>\n<title>x</title>",
+            "<!DocType HTML>",
+            "<HTML lang=\"en\">",
+            "<Head><title>x</title></Head>",
+            "<body>",
+            "<script>alert(1)</script>",
+            "<iframe src=\"x\"></iframe>",
+            "<h1>Title</h1>",
+            "<div class=\"x\">",
+            "<table>",
+            "<p>A paragraph.</p>",
+            "<a href=\"/\">home</a>",
+            "<!-- generated -->\n<p>x</p>",
+        ] {
+            assert_eq!(from_str(input), "text/html", "{input:?}");
+        }
+    }
+
+    #[test]
+    fn leading_whitespace() {
+        assert_eq!(from_str("\n\t  \r\n<html>"), "text/html");
+    }
+
+    // The tag name has to end where the pattern does
+    #[test]
+    fn tag_name_prefix() {
+        for input in [
+            "<htmlx>",
+            "<pre>code</pre>",
+            "<abbr>x</abbr>",
+            "<html",
+            "<!---->",
+        ] {
+            assert_eq!(from_str(input), "text/plain", "{input:?}");
+        }
+    }
+
+    #[test]
+    fn not_at_start() {
+        assert_eq!(from_str("Some text, then <html>"), "text/plain");
+    }
+
+    // XHTML with a declaration is XML, and XML is checked first
+    #[test]
+    fn xhtml() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/xhtml+xml")),
+            "application/xhtml+xml"
+        );
+        assert_eq!(
+            from_str("<?xml version=\"1.0\"?>\n<a xmlns=\"urn:x\"/>"),
+            "application/xml"
+        );
+    }
+
+    #[test]
+    fn subclass_of_text() {
+        assert!(tree_magic::is_subclass("text/html", "text/plain"));
+        assert_eq!(tree_magic::checker_for("text/html"), Some("text"));
+    }
+}
+
+mod plist {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn binary() {
+        let bytes = include_bytes!("application/x-plist");
+        assert_eq!(tree_magic::from_u8(bytes), "application/x-plist");
+        assert!(!tree_magic::match_u8("application/xml", bytes));
+    }
+
+    #[test]
+    fn xml() {
+        let bytes = include_bytes!("application/x-plist-xml");
+        assert_eq!(tree_magic::from_u8(bytes), "application/x-plist");
+        assert!(tree_magic::match_u8("application/xml", bytes));
+    }
+
+    #[test]
+    fn from_filepath() {
+        for name in ["x-plist", "x-plist-xml"] {
+            let path = Path::new("tests/application").join(name);
+            assert_eq!(
+                tree_magic::from_filepath(&path),
+                Some("application/x-plist")
+            );
+        }
+    }
+
+    #[test]
+    fn parents() {
+        assert!(tree_magic::is_subclass(
+            "application/x-plist",
+            "application/xml"
+        ));
+        assert!(tree_magic::is_subclass(
+            "application/x-plist",
+            "application/octet-stream"
+        ));
+    }
+
+    #[test]
+    fn other_versions() {
+        assert_ne!(
+            tree_magic::from_u8(b"bplist15\0\0\0\0"),
+            "application/x-plist"
+        );
+    }
+}
+
+#[cfg(feature = "csv")]
+mod delimited {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    fn from_str(input: &str) -> &'static str {
+        tree_magic::from_u8(input.as_bytes())
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(tree_magic::from_u8(include_bytes!("text/csv")), "text/csv");
+        assert!(tree_magic::is_subclass("text/csv", "text/plain"));
+    }
+
+    #[test]
+    fn tsv() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("text/tab-separated-values")),
+            "text/tab-separated-values"
+        );
+        assert!(tree_magic::is_subclass(
+            "text/tab-separated-values",
+            "text/plain"
+        ));
+    }
+
+    #[test]
+    fn from_filepath() {
+        for mime in ["text/csv", "text/tab-separated-values"] {
+            assert_eq!(
+                tree_magic::from_filepath(&Path::new("tests").join(mime)),
+                Some(mime)
+            );
+        }
+    }
+
+    // Quoted fields can hold delimiters, quotes, and newlines
+    #[test]
+    fn quoted() {
+        assert_eq!(
+            from_str("a,b,c\n\"x, \"\"y\"\"\",\"two\nlines\",z\n1,2,3\n"),
+            "text/csv"
+        );
+    }
+
+    // A doubled quote doesn't end the field, so the comma after it stays in
+    #[test]
+    fn doubled_quotes() {
+        assert_eq!(
+            from_str(
+                "who,said,when\nme,\"she said \"\"hi, there\"\"\",now\nyou,\"\"\"no\"\"\",then\n"
+            ),
+            "text/csv"
+        );
+    }
+
+    // The last line is ignored, since it may have been cut off
+    #[test]
+    fn cut_off() {
+        assert_eq!(from_str("a,b,c\n1,2,3\n4,5,6\n7,"), "text/csv");
+    }
+
+    #[test]
+    fn ragged() {
+        assert_eq!(from_str("a,b,c\n1,2,3\n4,5\n"), "text/plain");
+    }
+
+    #[test]
+    fn too_few_lines() {
+        assert_eq!(from_str("a,b\n1,2\n"), "text/plain");
+    }
+
+    #[test]
+    fn prose() {
+        for input in [
+            "Hello, world.\nThis, too, is prose.\nSo, is this.\n",
+            "Well, I think we should go\nYes, but what about the dog\nNo, the dog stays\n",
+            "Hello, world\nYes, indeed\nNo, never\nOh, well\n",
+        ] {
+            assert_eq!(from_str(input), "text/plain", "{input:?}");
+        }
+    }
+
+    #[test]
+    fn code() {
+        for input in [
+            "foo(a, b)\nbar(c, d)\nbaz(e, f)\n",
+            "x = 1,\ny = 2,\nz = 3,\n",
+            "call(a, b, c)\ncall(d, e, f)\ncall(g, h, i)\n",
+            "{a, b, c}\n{d, e, f}\n{g, h, i}\n",
+        ] {
+            assert_eq!(from_str(input), "text/plain", "{input:?}");
+        }
+    }
+
+    // Even when the contents would pass for CSV
+    #[test]
+    fn other_name_wins() {
+        let path =
+            std::env::temp_dir().join(format!("tree_magic_mini-csv-{}.py", std::process::id()));
+        std::fs::write(&path, "a,b,c\n1,2,3\n4,5,6\n").unwrap();
+        assert_eq!(tree_magic::from_filepath(&path), Some("text/x-python"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn indented_code() {
+        assert_eq!(
+            from_str("fn main() {\n\tfoo();\n\tbar();\n\tbaz();\n}\n"),
+            "text/plain"
+        );
+    }
+
+    // Lines of JSON have commas too
+    #[test]
+    fn ndjson() {
+        assert_eq!(from_str("[1,2]\n[3,4]\n[5,6]\n"), "application/x-ndjson");
+    }
+}
+
+mod markdown {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    fn from_str(input: &str) -> &'static str {
+        tree_magic::from_u8(input.as_bytes())
+    }
+
+    #[test]
+    fn heading_and_markup() {
+        for input in [
+            "# Title\n\nSee [the docs](https://example.com).\n",
+            "# Usage\n\n```sh\ncargo build\n```\n",
+            "# Logo\n\n![the logo](logo.png)\n",
+        ] {
+            assert_eq!(from_str(input), "text/markdown", "{input:?}");
+        }
+    }
+
+    // Scripts start with comments that look like headings
+    #[test]
+    fn heading_alone() {
+        assert_eq!(from_str("# Notes\n\nSome *notes* here.\n"), "text/plain");
+        assert_eq!(
+            from_str("# Set up the build\nx = 2**8\nprint(x)\n"),
+            "text/plain"
+        );
+        assert_eq!(
+            from_str("# compute the norm\nr = (x**2 + y**2) ** 0.5\n"),
+            "text/plain"
+        );
+        assert_eq!(
+            from_str("# call the handler\nhandlers[name](event)\n"),
+            "text/plain"
+        );
+    }
+
+    #[test]
+    fn not_a_heading() {
+        assert_ne!(from_str("#include <stdio.h>\n[a](b)\n"), "text/markdown");
+        assert_ne!(from_str("Intro\n# Title\n[a](b)\n"), "text/markdown");
+    }
+
+    // The name wins over the contents
+    #[test]
+    fn by_name() {
+        for name in ["notes.md", "notes.markdown", "notes.mkd"] {
+            assert_eq!(tree_magic::from_filename(name), Some("text/markdown"));
+        }
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/hint/notes.md")),
+            Some("text/markdown")
+        );
+    }
+
+    // Even when the contents look like Markdown
+    #[test]
+    fn other_name_wins() {
+        let dir = std::env::temp_dir().join(format!("tree_magic_mini-md-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let contents = "# Fetch the page\n# See [the docs](https://example.com)\nimport urllib\n";
+        for (name, mime) in [("fetch.py", "text/x-python"), ("fetch", "text/markdown")] {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).unwrap();
+            assert_eq!(tree_magic::from_filepath(&path), Some(mime), "{name}");
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+mod json {
+    use tree_magic_mini as tree_magic;
+
+    fn from_str(input: &str) -> &'static str {
+        tree_magic::from_u8(input.as_bytes())
+    }
+
+    #[test]
+    fn documents() {
+        assert_eq!(
+            from_str("{\"a\": [1, -2.5e3, true, null]}"),
+            "application/json"
+        );
+        assert_eq!(from_str("\n  [\"\\u00e9\", {}, []]\n"), "application/json");
+        assert_eq!(from_str("{}"), "application/json");
+    }
+
+    #[test]
+    fn truncated() {
+        // Only the start of a large file is checked
+        assert_eq!(
+            from_str("[{\"id\": 1}, {\"id\": 2}, {\"i"),
+            "application/json"
+        );
+        assert_eq!(from_str("{\"id\": 1"), "text/plain");
+    }
+
+    #[test]
+    fn not_json() {
+        for input in [
+            "[INFO] server started",
+            "{ some notes in braces }",
+            "[1, 2,, 3]",
+            "{\"a\": 01}",
+            "{\"a\": 1} and more",
+            "\"just a string\"",
+        ] {
+            assert_eq!(from_str(input), "text/plain", "{input}");
+        }
+    }
+
+    #[test]
+    fn deeply_nested() {
+        assert_eq!(from_str(&"[".repeat(1000)), "text/plain");
+    }
+}
+
+mod ndjson {
+    use tree_magic_mini as tree_magic;
+
+    fn from_str(input: &str) -> &'static str {
+        tree_magic::from_u8(input.as_bytes())
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(
+            from_str("{\"level\": \"info\"}\n{\"level\": \"warn\"}\n"),
+            "application/x-ndjson"
+        );
+        assert_eq!(
+            from_str("[1, 2]\r\n\r\n{\"a\": {}}\r\n[3]"),
+            "application/x-ndjson"
+        );
+    }
+
+    #[test]
+    fn truncated_last_line() {
+        assert_eq!(
+            from_str("{\"a\": 1}\n{\"b\": 2}\n{\"c\": tr"),
+            "application/x-ndjson"
+        );
+    }
+
+    #[test]
+    fn single_line_is_json() {
+        assert_eq!(from_str("{\"a\": 1}\n"), "application/json");
+    }
+
+    #[test]
+    fn not_ndjson() {
+        for input in [
+            "{\"a\": 1}\nplain text\n",
+            "{\"a\": 1}\n{\"b\": 2",
+            "1\n2\n3\n",
+            "{\"a\":\n 1}\n{\"b\": 2}\n",
+        ] {
+            assert_eq!(from_str(input), "text/plain", "{input:?}");
+        }
+    }
+}
+
+mod bom {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn fixtures() {
+        for (name, mime) in [
+            ("image/svg+xml", "image/svg+xml"),
+            ("application/json", "application/json"),
+            ("text/html", "text/html"),
+        ] {
+            for suffix in ["", "-bom"] {
+                let path = Path::new("tests").join(format!("{name}{suffix}"));
+                assert_eq!(tree_magic::from_filepath(&path), Some(mime), "{path:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn text_types() {
+        for (input, mime) in [
+            ("\u{feff}[1, 2, 3]\n", "application/json"),
+            ("\u{feff}{\"a\": 1}\n{\"a\": 2}\n", "application/x-ndjson"),
+            (
+                "\u{feff}<rss version=\"2.0\" xmlns:a=\"urn:a\"></rss>",
+                "application/rss+xml",
+            ),
+            (
+                "\u{feff}# Title\n\nSee [the docs](docs.md)\n",
+                "text/markdown",
+            ),
+        ] {
+            assert_eq!(tree_magic::from_u8(input.as_bytes()), mime, "{input:?}");
+        }
+    }
+
+    // Only at the very start
+    #[test]
+    fn after_whitespace() {
+        assert_eq!(
+            tree_magic::from_u8("\n\u{feff}[1, 2, 3]\n".as_bytes()),
+            "text/plain"
+        );
+    }
+}
+
+mod plain {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn utf8() {
+        let text = "Grüße, 世界!\r\n\tindented\x0cnext page\n";
+        assert_eq!(tree_magic::from_u8(text.as_bytes()), "text/plain");
+    }
+
+    #[test]
+    fn latin1() {
+        assert_eq!(tree_magic::from_u8(b"Gr\xfc\xdfe, caf\xe9\n"), "text/plain");
+    }
+
+    #[test]
+    fn binary() {
+        assert_eq!(
+            tree_magic::from_u8(b"hello\0world"),
+            "application/octet-stream"
+        );
+        assert!(!tree_magic::match_u8("text/plain", b"\x01\x02\x03\x04"));
+    }
+
+    fn utf16(text: &str, little_endian: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for unit in std::iter::once(0xfeff).chain(text.encode_utf16()) {
+            if little_endian {
+                bytes.extend(unit.to_le_bytes());
+            } else {
+                bytes.extend(unit.to_be_bytes());
+            }
+        }
+        bytes
+    }
+
+    fn utf32(text: &str, little_endian: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for c in std::iter::once('\u{feff}').chain(text.chars()) {
+            if little_endian {
+                bytes.extend((c as u32).to_le_bytes());
+            } else {
+                bytes.extend((c as u32).to_be_bytes());
+            }
+        }
+        bytes
+    }
+
+    // ASCII characters in these encodings are padded out with NULs
+    #[test]
+    fn utf16_and_utf32() {
+        let text = "Hello, 世界 🌍\r\n";
+        for little_endian in [true, false] {
+            assert_eq!(
+                tree_magic::from_u8(&utf16(text, little_endian)),
+                "text/plain"
+            );
+            assert_eq!(
+                tree_magic::from_u8(&utf32(text, little_endian)),
+                "text/plain"
+            );
+        }
+    }
+
+    #[test]
+    fn utf16_binary() {
+        assert!(!tree_magic::match_u8("text/plain", &utf16("a\0b", true)));
+        assert!(!tree_magic::match_u8("text/plain", &utf32("a\x01b", false)));
+    }
+
+    // Printable bytes up front don't make the rest of the window text
+    #[test]
+    fn control_characters() {
+        assert!(!tree_magic::match_u8("text/plain", b"header\x1b[0m"));
+        assert!(!tree_magic::match_u8("text/plain", b"record\x7f\x10"));
+        assert!(!tree_magic::match_u8("text/plain", b"vertical\x0btab"));
+    }
+}
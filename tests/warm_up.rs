@@ -0,0 +1,50 @@
+mod warm_up {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn from_several_threads() {
+        let threads: Vec<_> = (0..4)
+            .map(|_| std::thread::spawn(tree_magic::warm_up))
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        tree_magic::warm_up();
+
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/gif")),
+            "image/gif"
+        );
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/hint/notes.md")),
+            Some("text/markdown")
+        );
+    }
+}
+
+mod init {
+    use std::path::PathBuf;
+    use tree_magic_mini::{self as tree_magic, Error};
+
+    #[test]
+    fn loads_database() {
+        assert!(tree_magic::init().is_ok());
+        // Only loaded once, so the answer doesn't change
+        assert!(tree_magic::init().is_ok());
+    }
+
+    #[test]
+    fn not_found_lists_paths() {
+        let error = Error::NotFound {
+            searched: vec![
+                PathBuf::from("/usr/share/mime/magic"),
+                PathBuf::from("/usr/local/share/mime/magic"),
+            ],
+        };
+        assert_eq!(
+            error.to_string(),
+            "no MIME database found in /usr/share/mime/magic, /usr/local/share/mime/magic"
+        );
+    }
+}
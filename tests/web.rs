@@ -0,0 +1,117 @@
+mod sniff_web {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn no_supplied_type() {
+        for (bytes, mime) in [
+            (&include_bytes!("image/png")[..], "image/png"),
+            (include_bytes!("image/gif"), "image/gif"),
+            (include_bytes!("image/webp"), "image/webp"),
+            (include_bytes!("audio/mpeg"), "audio/mpeg"),
+            (include_bytes!("audio/wav"), "audio/wave"),
+            (include_bytes!("video/mp4"), "video/mp4"),
+            (include_bytes!("video/webm"), "video/webm"),
+            (include_bytes!("video/x-msvideo"), "video/avi"),
+            (include_bytes!("application/zip"), "application/zip"),
+            (include_bytes!("application/gzip"), "application/x-gzip"),
+            (include_bytes!("text/plain"), "text/plain"),
+            (&[0, 1, 2, 3], "application/octet-stream"),
+        ] {
+            assert_eq!(tree_magic::sniff_web(bytes, None), mime);
+        }
+    }
+
+    #[test]
+    fn unknown_supplied_type() {
+        let png = include_bytes!("image/png");
+        for supplied in [
+            "application/unknown",
+            "*/*",
+            "Unknown/Unknown; x=y",
+            "nonsense",
+        ] {
+            assert_eq!(tree_magic::sniff_web(png, Some(supplied)), "image/png");
+        }
+    }
+
+    // Scriptable content is never sniffed as what it is
+    #[test]
+    fn no_escalation() {
+        for bytes in [
+            &include_bytes!("text/html")[..],
+            b"<?xml version=\"1.0\"?><svg/>",
+            b"%PDF-1.7\n",
+        ] {
+            assert_eq!(tree_magic::sniff_web(bytes, None), "text/plain");
+        }
+        assert_eq!(
+            tree_magic::sniff_web(include_bytes!("text/html"), Some("text/plain")),
+            "text/plain"
+        );
+        assert_eq!(
+            tree_magic::sniff_web(include_bytes!("text/html"), Some("image/png")),
+            "image/png"
+        );
+    }
+
+    #[test]
+    fn supplied_type_kept() {
+        let png = include_bytes!("image/png");
+        for supplied in [
+            "text/html; charset=utf-8",
+            "application/json",
+            "image/svg+xml",
+            "text/plain; charset=utf-8",
+        ] {
+            assert_eq!(tree_magic::sniff_web(png, Some(supplied)), supplied);
+        }
+    }
+
+    // Media types are only corrected within their kind
+    #[test]
+    fn media_corrected() {
+        let png = include_bytes!("image/png");
+        assert_eq!(tree_magic::sniff_web(png, Some("image/jpeg")), "image/png");
+        assert_eq!(tree_magic::sniff_web(png, Some("video/mp4")), "video/mp4");
+        let webm = include_bytes!("video/webm");
+        assert_eq!(tree_magic::sniff_web(webm, Some("audio/ogg")), "video/webm");
+    }
+
+    // Old Apache servers called every file text/plain
+    #[test]
+    fn apache_text_plain() {
+        let png = include_bytes!("image/png");
+        assert_eq!(
+            tree_magic::sniff_web(png, Some("text/plain")),
+            "application/octet-stream"
+        );
+        assert_eq!(
+            tree_magic::sniff_web(b"plain old text\n", Some("text/plain; charset=UTF-8")),
+            "text/plain"
+        );
+    }
+
+    // Without an ID3 tag, it takes two frame headers the right distance apart
+    #[test]
+    fn mp3_frames() {
+        // MPEG-1 layer 3 at 128 kbit/s and 44.1 kHz makes 417 byte frames
+        let mut bytes = vec![0; 417 + 4];
+        bytes[..4].copy_from_slice(b"\xff\xfb\x90\x00");
+        bytes[417..].copy_from_slice(b"\xff\xfb\x90\x00");
+        assert_eq!(tree_magic::sniff_web(&bytes, None), "audio/mpeg");
+
+        bytes[417] = 0;
+        assert_eq!(
+            tree_magic::sniff_web(&bytes, None),
+            "application/octet-stream"
+        );
+    }
+
+    // Only the start of the resource counts
+    #[test]
+    fn resource_header() {
+        let mut bytes = vec![b'a'; 1445];
+        bytes.push(0);
+        assert_eq!(tree_magic::sniff_web(&bytes, None), "text/plain");
+    }
+}
@@ -0,0 +1,227 @@
+mod ooxml {
+    use std::fs::File;
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    const DOCX: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+    const XLSX: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+    const PPTX: &str = "application/vnd.openxmlformats-officedocument.presentationml.presentation";
+
+    #[test]
+    fn docx() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!(
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+            )),
+            DOCX
+        );
+    }
+
+    #[test]
+    fn xlsx() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!(
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            )),
+            XLSX
+        );
+    }
+
+    #[test]
+    fn pptx() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!(
+                "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+            )),
+            PPTX
+        );
+    }
+
+    #[test]
+    fn from_filepath() {
+        let dir = Path::new("tests/application");
+        for mime in [DOCX, XLSX, PPTX] {
+            let name = mime.strip_prefix("application/").unwrap();
+            assert_eq!(tree_magic::from_filepath(&dir.join(name)), Some(mime));
+        }
+    }
+
+    // The document part is past the first few KB of the archive, so it's
+    // only found in the central directory
+    #[test]
+    fn late_entry() {
+        let path = Path::new(
+            "tests/application/vnd.openxmlformats-officedocument.wordprocessingml.document-late",
+        );
+        assert_eq!(tree_magic::from_filepath(path), Some(DOCX));
+
+        let prefix = &std::fs::read(path).unwrap()[..2048];
+        assert_eq!(tree_magic::from_u8(prefix), "application/zip");
+    }
+
+    // Archives that only differ in their central directory are still told
+    // apart, even with the same size and modification time
+    #[test]
+    fn same_start_and_size() {
+        let docx = std::fs::read(
+            "tests/application/vnd.openxmlformats-officedocument.wordprocessingml.document-late",
+        )
+        .unwrap();
+        let dir_name = docx.len() - 39;
+        assert_eq!(&docx[dir_name..dir_name + 5], b"word/");
+        let mut xlsx = docx.clone();
+        xlsx[dir_name..dir_name + 5].copy_from_slice(b"xl/xx");
+
+        let modified = std::time::SystemTime::UNIX_EPOCH;
+        for (name, bytes, mime) in [("docx", &docx, DOCX), ("xlsx", &xlsx, XLSX)] {
+            let path = std::env::temp_dir().join(format!(
+                "tree_magic_mini-zip-{}-{}",
+                name,
+                std::process::id()
+            ));
+            std::fs::write(&path, bytes).unwrap();
+            let file = File::open(&path).unwrap();
+            file.set_modified(modified).unwrap();
+            assert_eq!(tree_magic::from_file(&file), Some(mime));
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn plain_zip() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/zip")),
+            "application/zip"
+        );
+    }
+
+    #[test]
+    fn subclass_of_zip() {
+        for mime in [DOCX, XLSX, PPTX] {
+            assert!(tree_magic::is_subclass(mime, "application/zip"));
+        }
+    }
+}
+
+mod odf {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn text() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.oasis.opendocument.text")),
+            "application/vnd.oasis.opendocument.text"
+        );
+    }
+
+    #[test]
+    fn spreadsheet() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!(
+                "application/vnd.oasis.opendocument.spreadsheet"
+            )),
+            "application/vnd.oasis.opendocument.spreadsheet"
+        );
+    }
+
+    #[test]
+    fn presentation() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!(
+                "application/vnd.oasis.opendocument.presentation"
+            )),
+            "application/vnd.oasis.opendocument.presentation"
+        );
+    }
+
+    // Databases say they're `application/vnd.oasis.opendocument.base`
+    #[test]
+    fn database() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!(
+                "application/vnd.oasis.opendocument.database"
+            )),
+            "application/vnd.oasis.opendocument.database"
+        );
+        assert_eq!(
+            tree_magic::canonical_mime("application/vnd.oasis.opendocument.base"),
+            "application/vnd.oasis.opendocument.database"
+        );
+    }
+
+    #[test]
+    fn from_filepath() {
+        let path = Path::new("tests/application/vnd.oasis.opendocument.text");
+        assert_eq!(
+            tree_magic::from_filepath(path),
+            Some("application/vnd.oasis.opendocument.text")
+        );
+    }
+
+    #[test]
+    fn unrecognized() {
+        let mut bytes = include_bytes!("application/vnd.oasis.opendocument.text").to_vec();
+        let pos = bytes
+            .windows(17)
+            .position(|w| w == b"opendocument.text")
+            .unwrap();
+        bytes[pos..pos + 17].copy_from_slice(b"opendocument.txet");
+        assert_eq!(tree_magic::from_u8(&bytes), "application/zip");
+    }
+}
+
+mod archives {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    const JAR: &str = "application/x-java-archive";
+    const EPUB: &str = "application/epub+zip";
+    const APK: &str = "application/vnd.android.package-archive";
+
+    #[test]
+    fn jar() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-java-archive")),
+            JAR
+        );
+        assert!(tree_magic::match_u8(
+            "application/java-archive",
+            include_bytes!("application/x-java-archive")
+        ));
+    }
+
+    #[test]
+    fn epub() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/epub+zip")),
+            EPUB
+        );
+    }
+
+    // APKs have a JAR manifest too
+    #[test]
+    fn apk() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.android.package-archive")),
+            APK
+        );
+    }
+
+    // The central directory read for one file mustn't be used for the next
+    #[test]
+    fn from_filepath() {
+        let dir = Path::new("tests/application");
+        for mime in [JAR, EPUB, APK, JAR, "application/zip"] {
+            let name = mime.strip_prefix("application/").unwrap();
+            assert_eq!(tree_magic::from_filepath(&dir.join(name)), Some(mime));
+        }
+    }
+
+    #[test]
+    fn subclass_of_zip() {
+        for mime in [JAR, EPUB, APK] {
+            assert!(tree_magic::is_subclass(mime, "application/zip"));
+        }
+    }
+}